@@ -1,17 +1,71 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use std::path::PathBuf;
+
+use crate::graph::{Format, Validation};
 /// Check property on transitions in mealy machine dot file
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
-pub(crate) struct Args {
-    /// dot file to be verified
-    #[arg(action=clap::ArgAction::Append)]
-    pub(crate) graphs: Vec<String>,
+#[command(args_conflicts_with_subcommands = true, arg_required_else_help = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    #[command(flatten)]
+    pub args: Option<Args>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Run every campaign of a configuration file
+    Run {
+        /// configuration file mapping rule files to models
+        config: PathBuf,
+        ///Output folder, replacing the one of the configuration file
+        #[arg(short, long)]
+        output_folder: Option<PathBuf>,
+        ///Write in the output folders even if they already hold results
+        #[arg(long)]
+        force: bool,
+        ///Rule names or severities (low, medium, high) whose violations make the run fail, every rule if not provided
+        #[arg(long, value_delimiter = ',')]
+        fail_on: Vec<String>,
+    },
+}
+
+/// Rules checked on the given Mealy machines
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// dot or automaton files to be verified, the models of a folder are searched recursively
+    #[arg(action=clap::ArgAction::Append, required = true)]
+    pub graphs: Vec<String>,
 
     /// rules to check against the mealy machines
     #[arg(short, long)]
-    pub(crate) rules: String,
-    ///Output folder, if not provided a random name is chosen
+    pub rules: String,
+    ///Output folder, if not provided a new folder named after the current time is created
     #[arg(short, long)]
-    pub(crate) output_folder: Option<PathBuf>,
+    pub output_folder: Option<PathBuf>,
+    ///Write in the output folder even if it already holds results
+    #[arg(long)]
+    pub force: bool,
+    ///Also write the whole Mealy machine with the transitions reported by each rule highlighted
+    #[arg(long)]
+    pub annotate: bool,
+    ///Format of the Mealy machine files
+    #[arg(short, long, value_enum, default_value_t = Format::Auto)]
+    pub format: Format,
+    ///Separator between the input and the output in the labels of the Mealy machines, "/" if not provided
+    #[arg(long)]
+    pub io_separator: Option<String>,
+    ///Initial state of the Mealy machines, if not provided it is marked in the files or guessed
+    #[arg(long)]
+    pub initial_state: Option<String>,
+    ///What to do with the Mealy machines that are not input complete, not deterministic or have unreachable states
+    #[arg(long, value_enum, default_value_t = Validation::Warn)]
+    pub validation: Validation,
+    ///Output of the transitions added for the missing inputs with `--validation complete`, "missing" if not provided
+    #[arg(long)]
+    pub default_output: Option<String>,
+    ///Rule names or severities (low, medium, high) whose violations make the run fail, every rule if not provided
+    #[arg(long, value_delimiter = ',')]
+    pub fail_on: Vec<String>,
 }
@@ -0,0 +1,262 @@
+//! Configuration file describing a verification campaign: the rule files checked on each set of models.
+//!
+//!```toml
+//!output_folder = "result"
+//!
+//![[campaign]]
+//!name = "opcua"
+//!rules = "rules/opcua.opcua"
+//!models = ["model/ready_to_verify/opcua_model/*/*/automata.dot"]
+//!
+//![[campaign]]
+//!name = "ssh"
+//!rules = "rules/ssh"
+//!models = ["model/ready_to_verify/ssh_models/*.dot"]
+//!io_separator = "/"
+//!```
+//!The paths are relative to the folder of the configuration file, a pattern matching a folder stands for every model found in it.
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::{
+    error::{Error, Location, Result},
+    graph::{self, Format, Graph, LoadOptions, Validation},
+    utils::output,
+    verifier::{result::GraphResult, rules::parse_file, Verifier},
+};
+
+fn default_output_folder() -> PathBuf {
+    PathBuf::from("result")
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// folder receiving the results of every campaign, one sub folder per campaign
+    #[serde(default = "default_output_folder")]
+    pub output_folder: PathBuf,
+    /// options used to load the models of every campaign, as given on the command line
+    pub format: Option<Format>,
+    pub io_separator: Option<String>,
+    pub initial_state: Option<String>,
+    pub validation: Option<Validation>,
+    pub default_output: Option<String>,
+    /// write the whole models annotated with the verdicts, as `--annotate`
+    #[serde(default)]
+    pub annotate: bool,
+    #[serde(rename = "campaign", default)]
+    pub campaigns: Vec<Campaign>,
+    /// folder of the configuration file, the paths are relative to it
+    #[serde(skip)]
+    base: PathBuf,
+}
+
+/// Rule file checked on the models matching some glob patterns.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Campaign {
+    pub name: String,
+    pub rules: PathBuf,
+    pub models: Vec<String>,
+    /// output folder of the campaign, the sub folder `name` of the global output folder if not provided
+    pub output_folder: Option<PathBuf>,
+    /// options overriding the global ones for the models of this campaign
+    pub format: Option<Format>,
+    pub io_separator: Option<String>,
+    pub initial_state: Option<String>,
+    pub validation: Option<Validation>,
+    pub default_output: Option<String>,
+    pub annotate: Option<bool>,
+}
+
+/// Verdicts of one campaign.
+#[derive(Debug)]
+pub struct CampaignResult {
+    pub name: String,
+    pub output_folder: PathBuf,
+    pub results: Vec<GraphResult>,
+    /// names of the rules parsed from the rule file
+    pub rules: Vec<String>,
+    /// rules and models that could not be loaded
+    pub errors: Vec<Error>,
+}
+
+impl Config {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| Error::io(path, e))?;
+        Config::from_str(&content, path)
+    }
+
+    pub(crate) fn from_str(content: &str, path: &Path) -> Result<Self> {
+        let mut config: Config = toml::from_str(content).map_err(|e| {
+            //the position of the error is given as a range of bytes
+            let (line, column) = match e.span() {
+                Some(span) => {
+                    let before = &content[..span.start];
+                    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+                    (
+                        before.matches('\n').count() + 1,
+                        before[line_start..].chars().count() + 1,
+                    )
+                }
+                None => (0, 0),
+            };
+            Error::Parse {
+                location: Location {
+                    file: path.display().to_string(),
+                    line,
+                    column,
+                },
+                message: e.message().to_string(),
+            }
+        })?;
+        if config.campaigns.is_empty() {
+            return Err(Error::config(path, "no campaign in the configuration file"));
+        }
+        config.base = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Ok(config)
+    }
+
+    /// Override the global output folder, given relative to the current folder.
+    pub fn set_output_folder(&mut self, output_folder: PathBuf) {
+        self.output_folder = std::env::current_dir().unwrap_or_default().join(output_folder);
+    }
+
+    pub fn get_output_folder(&self) -> PathBuf {
+        self.base.join(&self.output_folder)
+    }
+
+    fn campaign_output_folder(&self, campaign: &Campaign) -> PathBuf {
+        match &campaign.output_folder {
+            Some(output_folder) => self.base.join(output_folder),
+            None => self.get_output_folder().join(&campaign.name),
+        }
+    }
+
+    ///Refuse to run when an output folder already holds results, unless `force` is set.
+    pub fn check_output_folders(&self, force: bool) -> Result<()> {
+        output::check_output_folder(&self.get_output_folder(), force)?;
+        for campaign in self.campaigns.iter() {
+            output::check_output_folder(&self.campaign_output_folder(campaign), force)?;
+        }
+        Ok(())
+    }
+
+    ///Run every campaign, one after the other.
+    pub fn run(&self) -> Vec<CampaignResult> {
+        self.campaigns
+            .iter()
+            .map(|campaign| self.run_campaign(campaign))
+            .collect()
+    }
+
+    fn run_campaign(&self, campaign: &Campaign) -> CampaignResult {
+        let rules_path = self.base.join(&campaign.rules);
+        let (rules, mut errors) = parse_file(&rules_path.to_string_lossy());
+        let options = LoadOptions {
+            format: campaign.format.or(self.format).unwrap_or_default(),
+            io_separator: campaign.io_separator.clone().or(self.io_separator.clone()),
+            initial_state: campaign.initial_state.clone().or(self.initial_state.clone()),
+            validation: campaign.validation.or(self.validation).unwrap_or_default(),
+            default_output: campaign.default_output.clone().or(self.default_output.clone()),
+        };
+        let mut graphs = Vec::new();
+        for path in self.models(campaign, options.format, &mut errors) {
+            let path = path.to_string_lossy();
+            let added = Graph::load(&path, &options).and_then(|graph| graph::add_graph(&mut graphs, graph, &path));
+            if let Err(e) = added {
+                errors.push(e);
+            }
+        }
+        let output_folder = self.campaign_output_folder(campaign);
+        let mut verifier = Verifier::new(rules, graphs, output_folder.clone());
+        verifier.set_annotate(campaign.annotate.unwrap_or(self.annotate));
+        let results = verifier.apply();
+        CampaignResult {
+            name: campaign.name.clone(),
+            output_folder,
+            results,
+            rules: verifier.get_rules().iter().map(|r| r.get_name().to_string()).collect(),
+            errors,
+        }
+    }
+
+    ///Models of a campaign, the matches of each pattern are sorted and the matching folders are searched for models.
+    fn models(&self, campaign: &Campaign, format: Format, errors: &mut Vec<Error>) -> Vec<PathBuf> {
+        let mut models = Vec::new();
+        for pattern in campaign.models.iter() {
+            let full_pattern = self.base.join(pattern);
+            let paths = match glob::glob(&full_pattern.to_string_lossy()) {
+                Ok(paths) => paths,
+                Err(e) => {
+                    let message = format!("campaign {}: invalid pattern {}: {}", campaign.name, pattern, e);
+                    errors.push(Error::config(&full_pattern, message));
+                    continue;
+                }
+            };
+            let mut matches = Vec::new();
+            for path in paths {
+                match path {
+                    Ok(path) if path.is_dir() => match graph::find_models(&path, format) {
+                        Ok(found) => matches.extend(found),
+                        Err(e) => errors.push(e),
+                    },
+                    Ok(path) => matches.push(path),
+                    Err(e) => {
+                        let path = e.path().to_path_buf();
+                        errors.push(Error::io(path, e.into()))
+                    }
+                }
+            }
+            if matches.is_empty() {
+                let message = format!("campaign {}: no model matches {}", campaign.name, pattern);
+                errors.push(Error::config(&full_pattern, message));
+            }
+            matches.sort();
+            models.extend(matches);
+        }
+        models
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use crate::graph::{Format, Validation};
+
+    use super::Config;
+
+    #[test]
+    fn parse_config() {
+        let content = r#"
+            output_folder = "out"
+            io_separator = "/"
+
+            [[campaign]]
+            name = "opcua"
+            rules = "rules/opcua.opcua"
+            models = ["model/*/automata.dot"]
+            format = "dot"
+
+            [[campaign]]
+            name = "ssh"
+            rules = "rules/ssh"
+            models = ["ssh/*.dot"]
+            output_folder = "ssh_out"
+            validation = "complete"
+        "#;
+        let config = Config::from_str(content, Path::new("campaigns/config.toml")).unwrap();
+        assert_eq!(config.get_output_folder(), PathBuf::from("campaigns/out"));
+        assert_eq!(config.io_separator.as_deref(), Some("/"));
+        assert_eq!(config.campaigns.len(), 2);
+        assert_eq!(config.campaigns[0].format, Some(Format::Dot));
+        assert_eq!(config.campaigns[1].output_folder, Some(PathBuf::from("ssh_out")));
+        assert_eq!(config.campaigns[1].validation, Some(Validation::Complete));
+
+        let error =
+            Config::from_str("[[campaign]]\nname = \"a\"\nrule = \"r\"\n", Path::new("config.toml")).unwrap_err();
+        assert!(error.to_string().starts_with("config.toml:3:1: unknown field `rule`"));
+    }
+}
@@ -0,0 +1,155 @@
+use std::{
+    fmt::{self, Display},
+    io,
+    path::PathBuf,
+};
+
+/// Position of a problem in a rule or graph file.
+/// Lines and columns start at 1, 0 means the position is not known yet.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Location {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    /// A file could not be read or written.
+    Io { path: PathBuf, source: io::Error },
+    /// A rule or graph file is malformed.
+    Parse { location: Location, message: String },
+    /// The graph is not a Mealy machine the rules can be applied on.
+    Graph { graph: String, message: String },
+    /// A configuration file or a command line option describes a run that can not be made.
+    Config { path: PathBuf, message: String },
+    /// A rule failed while being applied on a graph.
+    Rule {
+        rule: String,
+        graph: String,
+        source: Box<Error>,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    pub(crate) fn io(path: impl Into<PathBuf>, source: io::Error) -> Self {
+        Error::Io {
+            path: path.into(),
+            source,
+        }
+    }
+
+    /// Parsing error whose file and line are filled by the caller owning the reader.
+    pub(crate) fn parse(column: usize, message: impl Into<String>) -> Self {
+        Error::Parse {
+            location: Location {
+                column,
+                ..Location::default()
+            },
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn graph(graph: &str, message: impl Into<String>) -> Self {
+        Error::Graph {
+            graph: graph.to_string(),
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn config(path: impl Into<PathBuf>, message: impl Into<String>) -> Self {
+        Error::Config {
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn rule(self, rule: &str, graph: &str) -> Self {
+        Error::Rule {
+            rule: rule.to_string(),
+            graph: graph.to_string(),
+            source: Box::new(self),
+        }
+    }
+
+    /// Set the column of a parsing error if it is not known yet.
+    pub(crate) fn at_column(mut self, column: usize) -> Self {
+        if let Error::Parse { location, .. } = &mut self {
+            if location.column == 0 {
+                location.column = column;
+            }
+        }
+        self
+    }
+
+    /// Set the file and line of a parsing error if they are not known yet.
+    pub(crate) fn locate(mut self, file: &str, line: usize) -> Self {
+        if let Error::Parse { location, .. } = &mut self {
+            if location.line == 0 {
+                location.file = file.to_string();
+                location.line = line;
+                location.column = location.column.max(1);
+            }
+        }
+        self
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            Error::Parse { location, message } => write!(f, "{}: {}", location, message),
+            Error::Graph { graph, message } => write!(f, "graph {}: {}", graph, message),
+            Error::Config { path, message } => write!(f, "{}: {}", path.display(), message),
+            Error::Rule { rule, graph, source } => write!(f, "rule {} on graph {}: {}", rule, graph, source),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Rule { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Column (starting at 1) of `token` within `line`, `token` being a slice of `line` or a substring of it.
+pub(crate) fn column(line: &str, token: &str) -> usize {
+    let start = line.as_ptr() as usize;
+    let token_start = token.as_ptr() as usize;
+    if token_start >= start && token_start + token.len() <= start + line.len() {
+        return line[..token_start - start].chars().count() + 1;
+    }
+    match line.find(token) {
+        Some(index) => line[..index].chars().count() + 1,
+        None => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{column, Error};
+
+    #[test]
+    fn error_location() {
+        let line = "  E:a/b";
+        assert_eq!(column(line, &line[4..]), 5);
+        assert_eq!(column(line, "a/b"), 5);
+        let error = Error::parse(0, "bad event").at_column(5).locate("rules", 3);
+        assert_eq!(error.to_string(), "rules:3:5: bad event");
+        let error = Error::parse(0, "missing end").locate("rules", 7);
+        assert_eq!(error.to_string(), "rules:7:1: missing end");
+    }
+}
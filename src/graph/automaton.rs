@@ -0,0 +1,94 @@
+//! Loader for the `.automaton` files written by the learner.
+//! The first line is the input alphabet separated by spaces, then each line is a transition `src, dst, input, output`.
+//! The transitions are turned into the same nodes and edges as the `automata.dot` file converted from it.
+use crate::error::{column, Error, Result};
+
+use super::dot::{Attribute, DotEdge, DotGraph, Id, IdKind};
+
+/// Parse the content of an automaton file, `source` is the name of the file used in the errors.
+pub(crate) fn parse(content: &str, source: &str) -> Result<DotGraph> {
+    let mut graph = DotGraph::default();
+    let mut alphabet: Option<Vec<&str>> = None;
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        //the alphabet is the only line without comma
+        if alphabet.is_none() && graph.edges.is_empty() && !line.contains(',') {
+            alphabet = Some(line.split_whitespace().collect());
+            continue;
+        }
+        let fields: Vec<&str> = line.splitn(4, ',').collect();
+        if fields.len() < 4 {
+            return Err(Error::parse(
+                column(line, line.trim()),
+                "expected a transition written as src, dst, input, output",
+            )
+            .locate(source, line_number));
+        }
+        let (from, to, input) = (fields[0].trim(), fields[1].trim(), fields[2].trim());
+        //the output keeps its trailing comma, as in the converted dot files
+        let output = fields[3].trim();
+        if let Some(alphabet) = &alphabet {
+            if !alphabet.contains(&input) {
+                return Err(Error::parse(
+                    column(line, fields[2].trim_start()),
+                    format!("input {} is not in the alphabet", input),
+                )
+                .locate(source, line_number));
+            }
+        }
+        for state in [from, to] {
+            if state.is_empty() {
+                return Err(
+                    Error::parse(column(line, line.trim()), "missing state in transition").locate(source, line_number)
+                );
+            }
+            if !graph.nodes.contains_key(state) {
+                graph.nodes.insert(state.to_string(), Vec::new());
+            }
+        }
+        let label = Attribute {
+            name: "label".to_string(),
+            value: Id {
+                text: format!("{} / {}", input, output),
+                kind: IdKind::Quoted,
+            },
+        };
+        graph.edges.push(DotEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            attributes: vec![label],
+            line: line_number,
+            column: column(line, line.trim()),
+        });
+    }
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    #[test]
+    fn parse_automaton() {
+        let input = "hello read_req\n0, 1, hello, Ack,\n0, 0, read_req, No resp,\n\n1, 1, hello, Err,\n";
+        let graph = parse(input, "0.automaton").unwrap();
+        assert_eq!(graph.nodes.keys().collect::<Vec<_>>(), vec!["0", "1"]);
+        let labels: Vec<&str> = graph
+            .edges
+            .iter()
+            .map(|e| e.get_attribute("label").unwrap().text.as_str())
+            .collect();
+        assert_eq!(labels, vec!["hello / Ack,", "read_req / No resp,", "hello / Err,"]);
+
+        let error = parse("hello\n0, 1, hello, Ack,\n0, 1, bye, Ack,\n", "0.automaton").unwrap_err();
+        assert_eq!(error.to_string(), "0.automaton:3:7: input bye is not in the alphabet");
+        let error = parse("hello\n0, 1\n", "0.automaton").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "0.automaton:2:1: expected a transition written as src, dst, input, output"
+        );
+    }
+}
@@ -0,0 +1,757 @@
+//! Tokenizer and parser for the Graphviz DOT language.
+//! The whole grammar is accepted (comments, subgraphs, edge chains, default attributes, ports...),
+//! the statements are then flattened into the nodes and edges the Mealy machine is built from.
+use std::fmt::Display;
+
+use indexmap::IndexMap;
+
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum IdKind {
+    Plain,
+    Quoted,
+    Html,
+}
+
+/// Identifier of the DOT language, `text` is its value without quotes and escapes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Id {
+    pub(crate) text: String,
+    pub(crate) kind: IdKind,
+}
+
+impl Display for Id {
+    //the identifier as it can be written back in a dot file
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            IdKind::Plain => write!(f, "{}", self.text),
+            IdKind::Quoted => write!(f, "\"{}\"", escape(&self.text)),
+            IdKind::Html => write!(f, "<{}>", self.text),
+        }
+    }
+}
+
+/// Text written between quotes in a dot file, read back as the same text by the lexer:
+/// the quotes are escaped and so are the backslashes that would escape the next character.
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' if matches!(chars.peek(), None | Some('\\' | '"' | '\n' | '\r')) => escaped.push_str("\\\\"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl Id {
+    /// Text of the identifier used as a label, the markup of HTML labels is removed.
+    /// In HTML labels, `+` is replaced by `_` as it is the choice between events in the rules.
+    pub(crate) fn label_text(&self) -> String {
+        match self.kind {
+            IdKind::Html => html_text(&self.text).replace('+', "_"),
+            _ => self.text.clone(),
+        }
+    }
+}
+
+/// Text of an HTML-like label: the tags are removed, the entities decoded and the white spaces collapsed.
+/// The cells of a table are concatenated, `<td>a</td><td>/</td><td>b</td>` gives `a/b`.
+pub(crate) fn html_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut rest = html;
+    while let Some(start) = rest.find(['<', '&']) {
+        text.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if rest.starts_with('<') {
+            //the tags are dropped, a line break separates the words around it
+            let end = rest.find('>').map_or(rest.len(), |end| end + 1);
+            if rest[..end].to_ascii_lowercase().starts_with("<br") {
+                text.push(' ');
+            }
+            rest = &rest[end..];
+        } else {
+            let (entity, end) = match rest.find(';') {
+                Some(end) => (decode_entity(&rest[1..end]), end + 1),
+                None => (None, 1),
+            };
+            match entity {
+                Some(c) => {
+                    text.push(c);
+                    rest = &rest[end..];
+                }
+                None => {
+                    text.push('&');
+                    rest = &rest[1..];
+                }
+            }
+        }
+    }
+    text.push_str(rest);
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    match entity {
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "amp" => Some('&'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        _ => {
+            let code = match entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => entity.strip_prefix('#')?.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Attribute {
+    pub(crate) name: String,
+    pub(crate) value: Id,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct DotEdge {
+    pub(crate) from: String,
+    pub(crate) to: String,
+    pub(crate) attributes: Vec<Attribute>,
+    /// position of the edge statement in the file
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+}
+
+impl DotEdge {
+    pub(crate) fn get_attribute(&self, name: &str) -> Option<&Id> {
+        self.attributes.iter().find(|a| a.name == name).map(|a| &a.value)
+    }
+}
+
+/// Content of a dot file, once the subgraphs and default attributes are resolved.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DotGraph {
+    pub(crate) name: Option<String>,
+    pub(crate) attributes: Vec<Attribute>,
+    /// nodes in the order of their first appearance, with their attributes
+    pub(crate) nodes: IndexMap<String, Vec<Attribute>>,
+    pub(crate) edges: Vec<DotEdge>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Id(Id),
+    LeftBrace,
+    RightBrace,
+    LeftBracket,
+    RightBracket,
+    Equal,
+    Semicolon,
+    Comma,
+    Colon,
+    //-> or --
+    EdgeOp(bool),
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Id(id) => write!(f, "{}", id),
+            Token::LeftBrace => write!(f, "{{"),
+            Token::RightBrace => write!(f, "}}"),
+            Token::LeftBracket => write!(f, "["),
+            Token::RightBracket => write!(f, "]"),
+            Token::Equal => write!(f, "="),
+            Token::Semicolon => write!(f, ";"),
+            Token::Comma => write!(f, ","),
+            Token::Colon => write!(f, ":"),
+            Token::EdgeOp(true) => write!(f, "->"),
+            Token::EdgeOp(false) => write!(f, "--"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Spanned {
+    token: Token,
+    line: usize,
+    column: usize,
+}
+
+struct Lexer<'a> {
+    chars: Vec<char>,
+    index: usize,
+    line: usize,
+    column: usize,
+    source: &'a str,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(content: &str, source: &'a str) -> Self {
+        Lexer {
+            chars: content.chars().collect(),
+            index: 0,
+            line: 1,
+            column: 1,
+            source,
+        }
+    }
+
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.index + offset).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.get(self.index).copied()?;
+        self.index += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn error(&self, line: usize, column: usize, message: impl Into<String>) -> Error {
+        Error::parse(column, message).locate(self.source, line)
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.chars[..self.index]
+            .iter()
+            .rev()
+            .take_while(|c| **c != '\n')
+            .all(|c| c.is_whitespace())
+    }
+
+    //skip white spaces, comments and preprocessor lines
+    fn skip_trivia(&mut self) -> Result<()> {
+        loop {
+            match (self.peek(0), self.peek(1)) {
+                (Some(c), _) if c.is_whitespace() => {
+                    self.bump();
+                }
+                (Some('/'), Some('/')) => self.skip_line(),
+                (Some('#'), _) if self.at_line_start() => self.skip_line(),
+                (Some('/'), Some('*')) => {
+                    let (line, column) = (self.line, self.column);
+                    self.bump();
+                    self.bump();
+                    loop {
+                        match (self.peek(0), self.peek(1)) {
+                            (Some('*'), Some('/')) => {
+                                self.bump();
+                                self.bump();
+                                break;
+                            }
+                            (Some(_), _) => {
+                                self.bump();
+                            }
+                            (None, _) => return Err(self.error(line, column, "unterminated comment")),
+                        }
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn skip_line(&mut self) {
+        while let Some(c) = self.peek(0) {
+            if c == '\n' {
+                break;
+            }
+            self.bump();
+        }
+    }
+
+    fn quoted(&mut self) -> Result<String> {
+        let (line, column) = (self.line, self.column);
+        self.bump();
+        let mut text = String::new();
+        loop {
+            match self.bump() {
+                Some('"') => return Ok(text),
+                Some('\\') => match self.peek(0) {
+                    Some('"') => {
+                        self.bump();
+                        text.push('"');
+                    }
+                    //an escaped backslash does not escape the next quote
+                    Some('\\') => {
+                        self.bump();
+                        text.push('\\');
+                    }
+                    //line continuation
+                    Some('\n') => {
+                        self.bump();
+                    }
+                    Some('\r') if self.peek(1) == Some('\n') => {
+                        self.bump();
+                        self.bump();
+                    }
+                    _ => text.push('\\'),
+                },
+                Some(c) => text.push(c),
+                None => return Err(self.error(line, column, "unterminated string")),
+            }
+        }
+    }
+
+    fn html(&mut self) -> Result<String> {
+        let (line, column) = (self.line, self.column);
+        self.bump();
+        let mut depth = 1;
+        let mut text = String::new();
+        loop {
+            match self.bump() {
+                Some('<') => depth += 1,
+                Some('>') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(text);
+                    }
+                }
+                Some(_) => (),
+                None => return Err(self.error(line, column, "unterminated HTML string")),
+            }
+            text.push(self.chars[self.index - 1]);
+        }
+    }
+
+    fn is_plain(&self, offset: usize) -> bool {
+        match self.peek(offset) {
+            None => false,
+            Some(c) if c.is_whitespace() || "{}[]=;,:\"<>".contains(c) => false,
+            Some('-') => !matches!(self.peek(offset + 1), Some('>') | Some('-')),
+            Some('/') => !matches!(self.peek(offset + 1), Some('/') | Some('*')),
+            Some(_) => true,
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Option<Spanned>> {
+        self.skip_trivia()?;
+        let (line, column) = (self.line, self.column);
+        let c = match self.peek(0) {
+            Some(c) => c,
+            None => return Ok(None),
+        };
+        let token = match c {
+            '{' | '}' | '[' | ']' | '=' | ';' | ',' | ':' => {
+                self.bump();
+                match c {
+                    '{' => Token::LeftBrace,
+                    '}' => Token::RightBrace,
+                    '[' => Token::LeftBracket,
+                    ']' => Token::RightBracket,
+                    '=' => Token::Equal,
+                    ';' => Token::Semicolon,
+                    ',' => Token::Comma,
+                    _ => Token::Colon,
+                }
+            }
+            '-' if matches!(self.peek(1), Some('>') | Some('-')) => {
+                self.bump();
+                Token::EdgeOp(self.bump() == Some('>'))
+            }
+            '"' => {
+                let mut text = self.quoted()?;
+                //"a" + "b" is the concatenation of the two strings
+                loop {
+                    let save = (self.index, self.line, self.column);
+                    self.skip_trivia()?;
+                    if self.peek(0) == Some('+') {
+                        self.bump();
+                        self.skip_trivia()?;
+                        if self.peek(0) == Some('"') {
+                            text.push_str(&self.quoted()?);
+                            continue;
+                        }
+                    }
+                    (self.index, self.line, self.column) = save;
+                    break;
+                }
+                Token::Id(Id {
+                    text,
+                    kind: IdKind::Quoted,
+                })
+            }
+            '<' => Token::Id(Id {
+                text: self.html()?,
+                kind: IdKind::Html,
+            }),
+            '>' => return Err(self.error(line, column, "unexpected character '>'")),
+            _ => {
+                let mut text = String::new();
+                while self.is_plain(0) {
+                    text.push(self.bump().unwrap());
+                }
+                Token::Id(Id {
+                    text,
+                    kind: IdKind::Plain,
+                })
+            }
+        };
+        Ok(Some(Spanned { token, line, column }))
+    }
+}
+
+//default attributes of the nodes and edges of a graph or subgraph
+#[derive(Debug, Clone, Default)]
+struct Scope {
+    node: Vec<Attribute>,
+    edge: Vec<Attribute>,
+}
+
+struct Parser<'a> {
+    tokens: Vec<Spanned>,
+    index: usize,
+    source: &'a str,
+    directed: bool,
+    graph: DotGraph,
+}
+
+fn set_attribute(attributes: &mut Vec<Attribute>, attribute: Attribute) {
+    match attributes.iter_mut().find(|a| a.name == attribute.name) {
+        Some(a) => a.value = attribute.value,
+        None => attributes.push(attribute),
+    }
+}
+
+fn is_keyword(token: &Token, keyword: &str) -> bool {
+    matches!(token, Token::Id(Id { text, kind: IdKind::Plain }) if text.eq_ignore_ascii_case(keyword))
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index).map(|s| &s.token)
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        self.peek().is_some_and(|t| is_keyword(t, keyword))
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error(&self, message: &str) -> Error {
+        match self.tokens.get(self.index) {
+            Some(s) => Error::parse(s.column, format!("{}, found '{}'", message, s.token)).locate(self.source, s.line),
+            None => {
+                let (line, column) = self.tokens.last().map_or((1, 1), |s| (s.line, s.column));
+                Error::parse(column, format!("{}, found end of file", message)).locate(self.source, line)
+            }
+        }
+    }
+
+    fn expect(&mut self, token: &Token) -> Result<()> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", token)))
+        }
+    }
+
+    fn id(&mut self) -> Result<Id> {
+        match self.peek() {
+            Some(Token::Id(id)) => {
+                let id = id.clone();
+                self.index += 1;
+                Ok(id)
+            }
+            _ => Err(self.error("expected an identifier")),
+        }
+    }
+
+    fn graph(&mut self) -> Result<()> {
+        self.eat_keyword("strict");
+        if self.eat_keyword("digraph") {
+            self.directed = true;
+        } else if !self.eat_keyword("graph") {
+            return Err(self.error("expected 'digraph' or 'graph'"));
+        }
+        if let Some(Token::Id(_)) = self.peek() {
+            self.graph.name = Some(self.id()?.text);
+        }
+        self.expect(&Token::LeftBrace)?;
+        let mut scope = Scope::default();
+        self.statements(&mut scope)?;
+        self.expect(&Token::RightBrace)?;
+        if self.peek().is_some() {
+            return Err(self.error("expected end of file after the graph"));
+        }
+        Ok(())
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if self.peek_keyword(keyword) {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    //statements until the closing brace, the nodes they contain are returned
+    fn statements(&mut self, scope: &mut Scope) -> Result<Vec<String>> {
+        let mut nodes = Vec::new();
+        while self.peek().is_some() && self.peek() != Some(&Token::RightBrace) {
+            self.statement(scope, &mut nodes)?;
+            self.eat(&Token::Semicolon);
+        }
+        Ok(nodes)
+    }
+
+    fn statement(&mut self, scope: &mut Scope, nodes: &mut Vec<String>) -> Result<()> {
+        if self.peek_keyword("graph") {
+            self.index += 1;
+            for attribute in self.attribute_lists()? {
+                set_attribute(&mut self.graph.attributes, attribute);
+            }
+            return Ok(());
+        }
+        if self.peek_keyword("node") || self.peek_keyword("edge") {
+            let is_node = self.peek_keyword("node");
+            self.index += 1;
+            let defaults = if is_node { &mut scope.node } else { &mut scope.edge };
+            for attribute in self.attribute_lists()? {
+                set_attribute(defaults, attribute);
+            }
+            return Ok(());
+        }
+        let start = self.tokens[self.index].clone();
+        //graph attribute written as ID = ID
+        if let (Some(Token::Id(_)), Some(Token::Equal)) =
+            (self.peek(), self.tokens.get(self.index + 1).map(|s| &s.token))
+        {
+            let name = self.id()?.text;
+            self.index += 1;
+            let value = self.id()?;
+            set_attribute(&mut self.graph.attributes, Attribute { name, value });
+            return Ok(());
+        }
+        let mut left = self.endpoint(scope, nodes)?;
+        if !matches!(self.peek(), Some(Token::EdgeOp(_))) {
+            //node statement, a subgraph alone has already been handled
+            if let Some(node) = left.single {
+                let attributes = self.attribute_lists()?;
+                let node_attributes = self.graph.nodes.get_mut(&node).expect("the node has been added");
+                for attribute in attributes {
+                    set_attribute(node_attributes, attribute);
+                }
+            }
+            return Ok(());
+        }
+        let mut pairs = Vec::new();
+        while let Some(Token::EdgeOp(directed)) = self.peek() {
+            if *directed != self.directed {
+                return Err(self.error(if self.directed {
+                    "expected '->' in a digraph"
+                } else {
+                    "expected '--' in a graph"
+                }));
+            }
+            self.index += 1;
+            let right = self.endpoint(scope, nodes)?;
+            for from in left.nodes.iter() {
+                for to in right.nodes.iter() {
+                    pairs.push((from.clone(), to.clone()));
+                }
+            }
+            left = right;
+        }
+        let mut attributes = scope.edge.clone();
+        for attribute in self.attribute_lists()? {
+            set_attribute(&mut attributes, attribute);
+        }
+        for (from, to) in pairs {
+            self.graph.edges.push(DotEdge {
+                from,
+                to,
+                attributes: attributes.clone(),
+                line: start.line,
+                column: start.column,
+            });
+        }
+        Ok(())
+    }
+
+    //node or subgraph at the end of an edge
+    fn endpoint(&mut self, scope: &mut Scope, nodes: &mut Vec<String>) -> Result<Endpoint> {
+        if self.peek_keyword("subgraph") || self.peek() == Some(&Token::LeftBrace) {
+            if self.eat_keyword("subgraph") {
+                if let Some(Token::Id(_)) = self.peek() {
+                    self.index += 1;
+                }
+            }
+            self.expect(&Token::LeftBrace)?;
+            let mut inner_scope = scope.clone();
+            let inner_nodes = self.statements(&mut inner_scope)?;
+            self.expect(&Token::RightBrace)?;
+            nodes.extend(inner_nodes.iter().cloned());
+            return Ok(Endpoint {
+                nodes: inner_nodes,
+                single: None,
+            });
+        }
+        let node = self.id()?.text;
+        //the port and compass point are not used
+        if self.eat(&Token::Colon) {
+            self.id()?;
+            if self.eat(&Token::Colon) {
+                self.id()?;
+            }
+        }
+        if !self.graph.nodes.contains_key(&node) {
+            self.graph.nodes.insert(node.clone(), scope.node.clone());
+        }
+        nodes.push(node.clone());
+        Ok(Endpoint {
+            nodes: vec![node.clone()],
+            single: Some(node),
+        })
+    }
+
+    fn attribute_lists(&mut self) -> Result<Vec<Attribute>> {
+        let mut attributes = Vec::new();
+        while self.eat(&Token::LeftBracket) {
+            while !self.eat(&Token::RightBracket) {
+                let name = self.id()?.text;
+                let value = if self.eat(&Token::Equal) {
+                    self.id()?
+                } else {
+                    Id {
+                        text: "true".to_string(),
+                        kind: IdKind::Plain,
+                    }
+                };
+                set_attribute(&mut attributes, Attribute { name, value });
+                if !self.eat(&Token::Comma) {
+                    self.eat(&Token::Semicolon);
+                }
+            }
+        }
+        Ok(attributes)
+    }
+}
+
+struct Endpoint {
+    nodes: Vec<String>,
+    //the node when the endpoint is not a subgraph
+    single: Option<String>,
+}
+
+/// Parse the content of a dot file, `source` is the name of the file used in the errors.
+pub(crate) fn parse(content: &str, source: &str) -> Result<DotGraph> {
+    let mut lexer = Lexer::new(content, source);
+    let mut tokens = Vec::new();
+    while let Some(token) = lexer.next_token()? {
+        tokens.push(token);
+    }
+    let mut parser = Parser {
+        tokens,
+        index: 0,
+        source,
+        directed: false,
+        graph: DotGraph::default(),
+    };
+    parser.graph()?;
+    Ok(parser.graph)
+}
+
+/// Parse a single statement, as if it was the only one of a digraph.
+#[cfg(test)]
+pub(crate) fn parse_statement(statement: &str) -> DotGraph {
+    parse(&format!("digraph {{\n{}\n}}", statement), "test").expect("malformed statement in test")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape, html_text, parse, IdKind};
+
+    #[test]
+    fn parse_dot_grammar() {
+        let input = r#"/* learned model */
+        digraph G {
+            # preprocessor line
+            node [shape=circle]; edge [fontsize=5]
+            s0 [label="s0"]; s1 // comment
+            s0 -> s1 -> s2 [label="a \"quoted\"/b"]
+            s1 -> { s0; s2 } [label=<x<b>y</b>/z>, color=red]
+            s2:p:n -> s2 [
+                label="c/"
+                    + "d"
+            ]
+            s2 -> s0 [label="e/f\\"]
+            rankdir=LR
+        }"#;
+        let graph = parse(input, "test").unwrap();
+        assert_eq!(graph.name.as_deref(), Some("G"));
+        assert_eq!(graph.nodes.keys().collect::<Vec<_>>(), vec!["s0", "s1", "s2"]);
+        assert_eq!(graph.nodes["s0"].len(), 2);
+        assert_eq!(graph.attributes[0].value.text, "LR");
+        let edges: Vec<(&str, &str, &str)> = graph
+            .edges
+            .iter()
+            .map(|e| {
+                (
+                    e.from.as_str(),
+                    e.to.as_str(),
+                    e.get_attribute("label").unwrap().text.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                ("s0", "s1", "a \"quoted\"/b"),
+                ("s1", "s2", "a \"quoted\"/b"),
+                ("s1", "s0", "x<b>y</b>/z"),
+                ("s1", "s2", "x<b>y</b>/z"),
+                ("s2", "s2", "c/d"),
+                ("s2", "s0", "e/f\\"),
+            ]
+        );
+        //written back as read, the other escapes are left to Graphviz
+        let label = graph.edges[5].get_attribute("label").unwrap();
+        assert_eq!(label.to_string(), r#""e/f\\""#);
+        assert_eq!(escape(r#"a\"b\n\"#), r#"a\\\"b\n\\"#);
+        assert_eq!(graph.edges[2].get_attribute("label").unwrap().kind, IdKind::Html);
+        assert_eq!(graph.edges[0].get_attribute("fontsize").unwrap().text, "5");
+        assert_eq!(graph.edges[0].line, 6);
+    }
+
+    #[test]
+    fn dot_syntax_error() {
+        let error = parse("digraph {\n  a -> [label=\"x\"]\n}", "model.dot").unwrap_err();
+        assert_eq!(error.to_string(), "model.dot:2:8: expected an identifier, found '['");
+        let error = parse("digraph {\n  a -- b\n}", "model.dot").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "model.dot:2:5: expected '->' in a digraph, found '--'"
+        );
+    }
+
+    #[test]
+    fn html_label_text() {
+        let label = r#"<table border="0" cellpadding="1" cellspacing="0"><tr><td>UA_PK_NOK</td><td>/</td><td>KEXINIT+DISCONNECT</td></tr></table>"#;
+        assert_eq!(html_text(label), "UA_PK_NOK/KEXINIT+DISCONNECT");
+        assert_eq!(html_text("a &lt;b&gt; &amp;<br/>c&#47;d &x"), "a <b> & c/d &x");
+        let graph = parse(&format!("digraph {{ s0 -> s1 [label=<{}>] }}", label), "test").unwrap();
+        let label = graph.edges[0].get_attribute("label").unwrap();
+        assert_eq!(label.label_text(), "UA_PK_NOK/KEXINIT_DISCONNECT");
+    }
+}
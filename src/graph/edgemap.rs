@@ -6,9 +6,9 @@ use std::{
 
 use indexmap::{IndexMap, IndexSet};
 
-use crate::utils::unquote;
+use crate::error::{Error, Result};
 
-use super::{element::Elements, multi_edge::MultiEdge, nodeid::NodeId};
+use super::{dot::DotEdge, element::Elements, multi_edge::MultiEdge, nodeid::NodeId};
 type EgdgeMapIntern = HashMap<NodeId, MultiEdge>;
 type EdgeMapInternal = IndexMap<NodeId, EgdgeMapIntern>;
 
@@ -23,9 +23,47 @@ impl EdgeMap {
         EdgeMap(EdgeMapInternal::new())
     }
 
+    ///Add an edge of a dot file, the label of the edge is the transition of the Mealy machine.
+    /// When the separator between the input and the output is given, the label is rewritten as input/output.
+    pub(crate) fn add_dot_edge(&mut self, edge: &DotEdge, io_separator: Option<&str>) -> Result<()> {
+        let mut label = match edge.get_attribute("label") {
+            Some(label) => label.label_text(),
+            None => {
+                return Err(Error::parse(
+                    edge.column,
+                    format!("failed to find the label of transition {} -> {}", edge.from, edge.to),
+                ))
+            }
+        };
+        if let Some(separator) = io_separator {
+            label = match label.split_once(separator) {
+                Some((input, output)) => format!("{}/{}", input.trim(), output.trim()),
+                None => {
+                    return Err(Error::parse(
+                        edge.column,
+                        format!(
+                            "no separator {:?} between input and output in label {:?}",
+                            separator, label
+                        ),
+                    ))
+                }
+            };
+        }
+        let elements = Elements::from_attributes(&edge.attributes);
+        self.inner_add_edge(
+            NodeId::from_dot(&edge.from),
+            NodeId::from_dot(&edge.to),
+            &label,
+            elements,
+        );
+        Ok(())
+    }
+
+    #[cfg(test)]
     pub(crate) fn add_edge_from_str(&mut self, edge_str: &str) {
-        let (from, to, label, elements) = Self::parse_label(edge_str);
-        self.inner_add_edge(from, to, &label, elements)
+        for edge in super::dot::parse_statement(edge_str).edges.iter() {
+            self.add_dot_edge(edge, None).expect("malformed edge in test")
+        }
     }
 
     fn inner_add_edge(&mut self, from: NodeId, to: NodeId, label: &str, elements: Elements) {
@@ -55,20 +93,6 @@ impl EdgeMap {
         }
     }
 
-    fn parse_label(line_str: &str) -> (NodeId, NodeId, String, Elements) {
-        let line: String = line_str.trim().to_string();
-        let index = line.find("[").expect("Malformed file");
-        let elements: Elements = Elements::new(&line[index + 1..]);
-        let label = match elements.get_label() {
-            Some(str) => unquote(str).to_string(),
-            None => panic!("Failed to found label for transition {:?}", line),
-        };
-        let index_state_separator = line.find("-").expect("Malformed file");
-        let from = NodeId::new(&line[..index_state_separator]);
-        let to = NodeId::new(&line[index_state_separator + 2..index]);
-        (from, to, label, elements)
-    }
-
     pub(crate) fn transpose(&self) -> Self {
         let mut result = Self::new();
         for edge_map in self.0.values() {
@@ -112,14 +136,17 @@ impl EdgeMap {
         }
     }
 
-    pub(crate) fn add_edge_with_indexes(&mut self, multi_edge: &MultiEdge,indexes:&[usize]) {
+    pub(crate) fn add_edge_with_indexes(&mut self, multi_edge: &MultiEdge, indexes: &[usize]) {
         let (from, to, labels, elements) = multi_edge.get_inner();
-        for i in indexes{
-            self.inner_add_edge(from.clone(), to.clone(), labels.get_index(*i).unwrap(), elements.get_index(*i).unwrap().clone());
+        for i in indexes {
+            self.inner_add_edge(
+                from.clone(),
+                to.clone(),
+                labels.get_index(*i).unwrap(),
+                elements.get_index(*i).unwrap().clone(),
+            );
         }
-
     }
-
 }
 
 impl Deref for EdgeMap {
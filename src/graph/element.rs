@@ -1,6 +1,6 @@
 use std::{fmt::Display, rc::Rc};
 
-use regex::Regex;
+use super::dot::Attribute;
 
 /// Element that describe an edge or a node in a graph (for dot format)
 #[derive(Debug, PartialEq, Clone, Eq, Hash)]
@@ -10,17 +10,25 @@ pub(crate) struct Element {
 }
 
 impl Element {
-    pub(crate) fn new_vec(elmtns_str: &str) -> Vec<Self> {
-        let mut elements: Vec<Element> = Vec::new();
-        // let start = std::time::Instant::now();
-        let re = Regex::new(r#"\w+\s*=\s*("[^"]*"|[^],])\s*[^],]*"#).unwrap();
-        for cap in re.captures_iter(elmtns_str) {
-            if let Some(e) = Element::new(&cap[0]) {
-                elements.push(e);
-            }
-        }
-        elements
+    ///Elements from the attributes of a dot statement, the color is not kept.
+    pub(crate) fn from_attributes(attributes: &[Attribute]) -> Vec<Self> {
+        attributes
+            .iter()
+            .filter(|attribute| attribute.name != "color")
+            .map(|attribute| Element {
+                field: attribute.name.clone(),
+                value: attribute.value.to_string(),
+            })
+            .collect()
+    }
+
+    ///Elements of an attribute list written as in a dot file.
+    #[cfg(test)]
+    pub(crate) fn new_vec(attributes: &str) -> Vec<Self> {
+        let graph = super::dot::parse_statement(&format!("node_for_test [{}]", attributes));
+        Element::from_attributes(&graph.nodes[0])
     }
+
     pub(crate) fn new(elmtns_str: &str) -> Option<Self> {
         let el: Vec<&str> = elmtns_str.split("=").collect();
         if el[0].trim() == "color" {
@@ -43,7 +51,7 @@ impl Element {
 
 impl std::fmt::Display for Element {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.field == "label" {
+        if self.field == "label" && !self.value.starts_with('<') {
             write!(f, "{}=\"{}\"", self.field, crate::utils::unquote(&self.value))
         } else {
             write!(f, "{}={}", self.field, self.value)
@@ -56,21 +64,12 @@ pub(crate) struct Elements {
     inner: Vec<Element>,
 }
 impl Elements {
-    pub(crate) fn new(line: &str) -> Self {
+    pub(crate) fn from_attributes(attributes: &[Attribute]) -> Self {
         Elements {
-            inner: Element::new_vec(line),
+            inner: Element::from_attributes(attributes),
         }
     }
 
-    pub(crate) fn get_label(&self) -> Option<&str> {
-        for element in self.inner.iter() {
-            if element.field == "label" {
-                return Some(&element.value);
-            }
-        }
-        return None;
-    }
-
     pub(crate) fn default_edge(label: &Rc<str>) -> Self {
         let font_size = Element {
             field: "fontsize".to_string(),
@@ -78,14 +77,11 @@ impl Elements {
         };
         let label = Element {
             field: "label".to_string(),
-            value: format!("\"{}\"", label),
+            value: format!("\"{}\"", super::dot::escape(label)),
         };
-        let mut inner = Vec::new();
-        inner.push(font_size);
-        inner.push(label);
+        let inner = vec![font_size, label];
         Self { inner }
     }
-
 }
 
 impl Display for Elements {
@@ -93,10 +89,10 @@ impl Display for Elements {
         let mut elements_str = String::from("[");
         elements_str.push_str(&self.inner[0].to_string());
         for element in self.inner[1..].iter() {
-            elements_str.push_str(",");
+            elements_str.push(',');
             elements_str.push_str(&element.to_string());
         }
-        elements_str.push_str("]");
+        elements_str.push(']');
         write!(f, "{}", elements_str)
     }
 }
@@ -128,7 +124,7 @@ mod tests {
                 value: "a/b".to_string(),
             },
         ];
-        let elements = Element::new_vec("shape=ellipse, style=filed, fillcolor=white, URL=\"0\", label=a/b]");
+        let elements = Element::new_vec("shape=ellipse, style=filed, fillcolor=white, URL=\"0\", label=a/b");
         assert_eq!(elements, expected_elements);
 
         let expected_elements = vec![
@@ -153,7 +149,7 @@ mod tests {
                 value: "\"a/b\"".to_string(),
             },
         ];
-        let elements = Element::new_vec("shape=ellipse, style=filed, fillcolor=white, URL=\"0\", label=\"a/b\"]");
+        let elements = Element::new_vec("shape=ellipse, style=filed, fillcolor=white, URL=\"0\", label=\"a/b\"");
         assert_eq!(elements, expected_elements);
     }
     #[test]
@@ -1,91 +1,226 @@
+pub(crate) mod automaton;
+pub(crate) mod dot;
 pub(crate) mod edgemap;
 pub(crate) mod element;
-pub(crate) mod multi_edge;
-pub(crate) mod node;
-pub(crate) mod nodeid;
+pub mod multi_edge;
+pub mod node;
+pub mod nodeid;
+pub(crate) mod validation;
 use indexmap::map::Keys;
 use indexmap::IndexMap;
 use node::Node;
 
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Component, Path, PathBuf};
 use std::rc::Rc;
 
-pub(crate) type NodeMap = IndexMap<NodeId, Node>;
+pub type NodeMap = IndexMap<NodeId, Node>;
 // pub(crate) type EdgeMap = IndexMap<NodeId, HashSet<Rc<MultiEdge>>>;
 
 //we use Reference counting to avoid complete copy of graph when using subgraph.
-pub(crate) struct Graph {
+pub struct Graph {
     name: String,
+    /// implementations sharing this Mealy machine, read from the `implem` file next to it
+    implementations: Vec<String>,
     nodes: NodeMap,
     edges: EdgeMap,
     transpose_edges: EdgeMap,
     root: Option<NodeId>,
     sinks: Vec<NodeId>,
+    /// problems found by the validation of the machine, which is still verified
+    warnings: Vec<String>,
 }
-use crate::utils::reader::Reader;
-use crate::utils::reader::ReaderFile;
+use crate::error::{Error, Result};
 use crate::verifier::event::Event;
 
+use self::dot::DotGraph;
 use self::edgemap::EdgeMap;
 use self::multi_edge::MultiEdge;
 use self::nodeid::NodeId;
+
+pub use self::validation::Validation;
+
+/// Format of a file describing a Mealy machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    /// picked from the extension of the file, dot unless it is `.automaton`
+    #[default]
+    Auto,
+    /// Graphviz dot file
+    Dot,
+    /// alphabet followed by `src, dst, input, output` rows, as written by the learner
+    Automaton,
+}
+
+impl Format {
+    fn resolve(self, path_to_file: &str) -> Self {
+        match self {
+            Format::Auto if path_to_file.ends_with(".automaton") => Format::Automaton,
+            Format::Auto => Format::Dot,
+            format => format,
+        }
+    }
+}
+
+/// Options used to load a Mealy machine from a file.
+#[derive(Debug, Clone, Default)]
+pub struct LoadOptions {
+    pub format: Format,
+    /// separator between the input and the output in the labels, the labels are rewritten as input/output
+    pub io_separator: Option<String>,
+    /// initial state of the Mealy machine, taking precedence over the markers of the file
+    pub initial_state: Option<String>,
+    /// what to do when the Mealy machine is not input complete, not deterministic or has unreachable states
+    pub validation: Validation,
+    /// output of the transitions added for the missing inputs, `missing` if not provided
+    pub default_output: Option<String>,
+}
+
 impl Graph {
-    pub(crate) fn new_file(path_to_file: &str) -> Self {
-        let mut reader = match ReaderFile::open(path_to_file) {
-            Ok(reader) => reader,
-            Err(e) => {
-                panic!("Error while reading graph file '{}' : {}", path_to_file, e.to_string())
-            }
+    ///Load a Mealy machine from a dot or automaton file, the format is picked from the extension.
+    pub fn new_file(path_to_file: &str) -> Result<Self> {
+        Graph::load(path_to_file, &LoadOptions::default())
+    }
+
+    ///Load a Mealy machine from a file with the given options.
+    pub fn load(path_to_file: &str, options: &LoadOptions) -> Result<Self> {
+        let content = std::fs::read_to_string(path_to_file).map_err(|e| Error::io(path_to_file, e))?;
+        let name = graph_name(Path::new(path_to_file));
+        let dot = match options.format.resolve(path_to_file) {
+            Format::Automaton => automaton::parse(&content, path_to_file)?,
+            _ => dot::parse(&content, path_to_file)?,
         };
+        let mut graph = Graph::from_dot(name, dot, path_to_file, options)?;
+        graph.resolve_start_state(options)?;
+        graph.validate(options.validation, options.default_output.as_deref())?;
+        graph.implementations = read_implementations(Path::new(path_to_file))?;
+
+        Ok(graph)
+    }
+
+    ///Initial state given in the options, else marked in the file, else guessed from the edges.
+    fn resolve_start_state(&mut self, options: &LoadOptions) -> Result<()> {
+        match &options.initial_state {
+            Some(initial_state) => self.set_initial_state(initial_state),
+            None if self.root.is_none() => self.identify_start_state(),
+            None => Ok(()),
+        }
+    }
+
+    ///Build the Mealy machine from the nodes and edges of a parsed file, `source` is the file named in the errors.
+    /// The root is set when the file marks the initial state, the `__start` nodes marking it are left out.
+    fn from_dot(name: String, dot: DotGraph, source: &str, options: &LoadOptions) -> Result<Self> {
+        let root = Graph::marked_start_state(&name, &dot)?;
         let mut nodes: NodeMap = NodeMap::new();
-        let mut edges: EdgeMap = EdgeMap::new();
-        let mut node: Node;
-        while let Some(line) = reader.read_line() {
-            if Graph::is_node(&line) {
-                node = Node::new(&line);
-                nodes.insert(node.nodeid.clone(), node);
-            } else if Graph::is_edge(line) {
-                edges.add_edge_from_str(line);
+        for (id, attributes) in dot.nodes.iter() {
+            if is_start_marker(id) {
+                continue;
             }
+            let node = Node::from_dot(id, attributes);
+            nodes.insert(node.nodeid.clone(), node);
         }
-        let name_vec: Vec<&str> = path_to_file.split("/").collect();
-        let name: String;
-        if name_vec.len() > 1 {
-            name = format!("{}_{}", name_vec[name_vec.len() - 2], name_vec[name_vec.len() - 1]);
-        } else {
-            name = name_vec.last().unwrap().to_string();
+        let mut edges: EdgeMap = EdgeMap::new();
+        for edge in dot.edges.iter() {
+            if is_start_marker(&edge.from) || is_start_marker(&edge.to) {
+                continue;
+            }
+            edges
+                .add_dot_edge(edge, options.io_separator.as_deref())
+                .map_err(|e| e.locate(source, edge.line))?;
         }
         let transpose = edges.transpose();
         let mut graph = Graph {
-            name: name,
+            name,
+            implementations: Vec::new(),
             nodes,
             edges,
             transpose_edges: transpose,
-            root: None,
+            root,
             sinks: Vec::new(),
+            warnings: Vec::new(),
         };
         graph.identify_sink_state();
-        graph.identify_start_state();
+        Ok(graph)
+    }
 
-        graph
+    ///Initial state marked in the file, by order of precedence:
+    /// the target of an edge from a `__start` node (LearnLib, AALpy), a node with an `init` attribute,
+    /// a node with a `doublecircle` shape.
+    fn marked_start_state(name: &str, dot: &DotGraph) -> Result<Option<NodeId>> {
+        let from_start_marker: Vec<&str> = dot
+            .edges
+            .iter()
+            .filter(|edge| is_start_marker(&edge.from) && !is_start_marker(&edge.to))
+            .map(|edge| edge.to.as_str())
+            .collect();
+        let with_attribute = |attribute: &str, is_marked: fn(&str) -> bool| -> Vec<&str> {
+            dot.nodes
+                .iter()
+                .filter(|(id, attributes)| {
+                    !is_start_marker(id)
+                        && attributes
+                            .iter()
+                            .any(|a| a.name == attribute && is_marked(&a.value.text))
+                })
+                .map(|(id, _)| id.as_str())
+                .collect()
+        };
+        let markers = [
+            ("__start", from_start_marker),
+            (
+                "init",
+                with_attribute("init", |value| !matches!(value, "false" | "0" | "")),
+            ),
+            (
+                "doublecircle",
+                with_attribute("shape", |value| value.eq_ignore_ascii_case("doublecircle")),
+            ),
+        ];
+        for (marker, mut marked) in markers {
+            marked.sort_unstable();
+            marked.dedup();
+            match marked.as_slice() {
+                [] => continue,
+                [initial_state] => return Ok(Some(NodeId::from_dot(initial_state))),
+                _ => {
+                    return Err(Error::graph(
+                        name,
+                        format!("several initial states marked by {}: {}", marker, marked.join(", ")),
+                    ))
+                }
+            }
+        }
+        Ok(None)
     }
 
-    fn is_edge(line: &str) -> bool {
-        line.contains("->")
+    ///Use the given state as the initial state.
+    pub(crate) fn set_initial_state(&mut self, initial_state: &str) -> Result<()> {
+        let node_id = NodeId::from_dot(initial_state);
+        if !self.nodes.contains_key(&node_id) {
+            return Err(Error::graph(
+                &self.name,
+                format!("initial state {} is not a state of the mealy machine", initial_state),
+            ));
+        }
+        self.root = Some(node_id);
+        Ok(())
     }
 
-    pub(crate) fn get_name(&self) -> &str {
+    pub fn get_name(&self) -> &str {
         &self.name
     }
 
-    fn is_node(line: &str) -> bool {
-        !Graph::is_edge(line) && line.contains("[")
+    pub fn get_implementations(&self) -> &[String] {
+        &self.implementations
     }
-    pub(crate) fn _set_root(&mut self, root_id: &str) {
-        self.root = Some(NodeId::new(root_id));
+
+    ///Problems found when the Mealy machine was loaded, see [`Validation`].
+    pub fn get_warnings(&self) -> &[String] {
+        &self.warnings
     }
 
-    fn identify_start_state(&mut self) {
+    fn identify_start_state(&mut self) -> Result<()> {
         //this function will be looking for the first node that has only outgoing edges.
         //Mealy machine shoudl only have one starting state.
         if self.root.is_some() {
@@ -100,32 +235,42 @@ impl Graph {
                     }
                     if is_start {
                         if self.root.is_some() {
-                            panic!("several starting state found, exactly one is expected");
+                            return Err(Error::graph(
+                                &self.name,
+                                "several starting state found, exactly one is expected",
+                            ));
                         }
                         self.root = Some(node.clone());
                     }
                 }
                 None => {
                     if self.root.is_some() {
-                        panic!("several starting state found, exactly one is expected");
+                        return Err(Error::graph(
+                            &self.name,
+                            "several starting state found, exactly one is expected",
+                        ));
                     }
                     self.root = Some(node.clone());
                 }
             }
-            if node=="0" && self.root.is_none(){
+            if node == "0" && self.root.is_none() {
                 if self.root.is_some() {
-                    panic!("several starting state found, exactly one is expected");
+                    return Err(Error::graph(
+                        &self.name,
+                        "several starting state found, exactly one is expected",
+                    ));
                 }
                 self.root = Some(node.clone());
             }
         }
         if self.root.is_none() {
-            panic!("no starting state on the mealy machine");
+            return Err(Error::graph(&self.name, "no starting state on the mealy machine"));
         }
+        Ok(())
     }
 
     //from a node id give the neighbors and
-    pub(crate) fn neighbors_edges_iterator(&self, node_id: &NodeId) -> Vec<(NodeId, &MultiEdge)> {
+    pub fn neighbors_edges_iterator(&self, node_id: &NodeId) -> Vec<(NodeId, &MultiEdge)> {
         let mut ret: Vec<(NodeId, &MultiEdge)> = Vec::new();
         let edges = match self.edges.get(node_id) {
             None => return ret,
@@ -137,8 +282,7 @@ impl Graph {
         ret
     }
 
-
-    pub(crate) fn neighbors_tranposed_edges(&self, node_id: &NodeId) -> std::vec::Vec<(NodeId, &MultiEdge)> {
+    pub fn neighbors_tranposed_edges(&self, node_id: &NodeId) -> std::vec::Vec<(NodeId, &MultiEdge)> {
         let mut ret: Vec<(NodeId, &MultiEdge)> = Vec::new();
         let edges = match self.transpose_edges.get(node_id) {
             None => return ret,
@@ -150,7 +294,7 @@ impl Graph {
         ret
     }
 
-    pub(crate) fn is_starting_node(&self, node_id: &NodeId) -> bool {
+    pub fn is_starting_node(&self, node_id: &NodeId) -> bool {
         if let Some(e) = &self.root {
             return node_id == e;
         }
@@ -158,18 +302,18 @@ impl Graph {
     }
 
     pub(crate) fn _get_nodes(&self) -> HashSet<NodeId> {
-        self.nodes.keys().map(|node_id| node_id.clone()).collect()
+        self.nodes.keys().cloned().collect()
     }
 
-    pub(crate) fn iter_node_id(&self) ->Keys<'_, NodeId, Node> {
+    pub fn iter_node_id(&self) -> Keys<'_, NodeId, Node> {
         self.nodes.keys()
     }
 
     pub(crate) fn _get_nodes_iterator(&self) -> impl Iterator<Item = NodeId> + '_ {
-        self.nodes.keys().map(|node_id| node_id.clone())
+        self.nodes.keys().cloned()
     }
 
-    pub(crate) fn get_outgoing_labels(&self, node_id: &NodeId) -> Vec<Rc<str>> {
+    pub fn get_outgoing_labels(&self, node_id: &NodeId) -> Vec<Rc<str>> {
         let mut result = Vec::new();
         if let Some(edges_map) = self.edges.get(node_id) {
             for edge in edges_map.values() {
@@ -180,20 +324,38 @@ impl Graph {
         result
     }
 
-    pub(crate) fn get_outgoing_edges(&self, node_id: &NodeId) -> Option<&std::collections::HashMap<NodeId, MultiEdge>> {
+    ///Inputs of the transitions of the Mealy machine, sorted.
+    pub fn get_inputs(&self) -> BTreeSet<String> {
+        self.alphabet(|(input, _)| input)
+    }
+
+    ///Outputs of the transitions of the Mealy machine, sorted.
+    pub fn get_outputs(&self) -> BTreeSet<String> {
+        self.alphabet(|(_, output)| output)
+    }
+
+    //one side of the labels, written input/output
+    fn alphabet(&self, side: for<'a> fn((&'a str, &'a str)) -> &'a str) -> BTreeSet<String> {
+        self.iter_edges()
+            .iter()
+            .flat_map(|edge| edge.get_labels())
+            .map(|label| side(label.split_once('/').unwrap_or((&label, ""))).trim().to_string())
+            .collect()
+    }
+
+    pub fn get_outgoing_edges(&self, node_id: &NodeId) -> Option<&std::collections::HashMap<NodeId, MultiEdge>> {
         self.edges.get(node_id)
     }
 
-    pub(crate) fn get_root(&self) -> Option<&NodeId> {
+    pub fn get_root(&self) -> Option<&NodeId> {
         self.root.as_ref()
     }
 
-
-    pub(crate) fn get_nodes_id(&self) -> Keys<'_, NodeId, Node> {
+    pub fn get_nodes_id(&self) -> Keys<'_, NodeId, Node> {
         self.nodes.keys()
     }
 
-    pub(crate) fn get_node(&self, nodeid: &NodeId) -> Option<&Node> {
+    pub fn get_node(&self, nodeid: &NodeId) -> Option<&Node> {
         self.nodes.get(nodeid)
     }
 
@@ -207,8 +369,8 @@ impl Graph {
         }
         false
     }
-    
-    fn identify_sink_state(&mut self){
+
+    fn identify_sink_state(&mut self) {
         let mut result = Vec::new();
         let mut is_sink: bool;
         for source in self.nodes.keys() {
@@ -227,17 +389,17 @@ impl Graph {
                 result.push(source.clone());
             }
         }
-        self.sinks=result;
+        self.sinks = result;
     }
-    pub(crate) fn get_sink_state(&self) -> &Vec<NodeId> {
-       &self.sinks
+    pub fn get_sink_state(&self) -> &Vec<NodeId> {
+        &self.sinks
     }
 
-    pub(crate) fn get_sink_state_set(&self) -> HashSet<&NodeId> {
+    pub fn get_sink_state_set(&self) -> HashSet<&NodeId> {
         self.sinks.iter().collect()
     }
 
-    pub(crate) fn iter_edges(&self) -> Vec<&MultiEdge> {
+    pub fn iter_edges(&self) -> Vec<&MultiEdge> {
         let mut res_iter: Vec<&MultiEdge> = Vec::new();
         for (_, edges) in self.edges.iter() {
             for edge in edges.values() {
@@ -248,53 +410,132 @@ impl Graph {
     }
 }
 
+///Add the graph loaded from `path_to_file` unless a graph already added has the same name, hence the same results folder.
+/// Two paths such as `a/../b` and `a/__/b`, or the same file given twice, would write their results in one folder.
+pub(crate) fn add_graph(graphs: &mut Vec<Graph>, graph: Graph, path_to_file: &str) -> Result<()> {
+    if graphs.iter().any(|g| g.get_name() == graph.get_name()) {
+        return Err(Error::graph(
+            graph.get_name(),
+            format!(
+                "{} has the results folder of a model already loaded, it is left out",
+                path_to_file
+            ),
+        ));
+    }
+    graphs.push(graph);
+    Ok(())
+}
+
+///Files describing Mealy machines found in a directory and its sub directories, sorted by path.
+/// Dot files are looked for, automaton files when this format is requested.
+/// A directory reached again through a symbolic link is not searched twice, so a cycle of links ends the walk.
+pub fn find_models(directory: &Path, format: Format) -> Result<Vec<PathBuf>> {
+    let extension = match format {
+        Format::Automaton => "automaton",
+        _ => "dot",
+    };
+    let mut models = Vec::new();
+    let mut visited: HashSet<PathBuf> = HashSet::new();
+    let mut directories = vec![directory.to_path_buf()];
+    while let Some(directory) = directories.pop() {
+        let canonical = directory.canonicalize().map_err(|e| Error::io(&directory, e))?;
+        if !visited.insert(canonical) {
+            continue;
+        }
+        let entries = std::fs::read_dir(&directory).map_err(|e| Error::io(&directory, e))?;
+        for entry in entries {
+            let path = entry.map_err(|e| Error::io(&directory, e))?.path();
+            if path.is_dir() {
+                directories.push(path);
+            } else if path.extension().is_some_and(|e| e == extension) {
+                models.push(path);
+            }
+        }
+    }
+    models.sort();
+    Ok(models)
+}
+
+///Name of the graph loaded from a file: its path relative to the current folder, used as the sub folder of its results.
+/// The root of an absolute path outside the current folder is left out and `..` is written `__`,
+/// so the results of every graph stay in the output folder.
+fn graph_name(path_to_file: &Path) -> String {
+    let current_dir = std::env::current_dir().unwrap_or_default();
+    let path = path_to_file.strip_prefix(&current_dir).unwrap_or(path_to_file);
+    let components: Vec<String> = path
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+            Component::ParentDir => Some("__".to_string()),
+            Component::CurDir | Component::RootDir | Component::Prefix(_) => None,
+        })
+        .collect();
+    components.join("/")
+}
+
+///Implementations listed in the `implem` file of the folder of a model, one per line.
+fn read_implementations(path_to_file: &Path) -> Result<Vec<String>> {
+    let implem = path_to_file.with_file_name("implem");
+    let content = match std::fs::read_to_string(&implem) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(Error::io(implem, e)),
+    };
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(implementation_name)
+        .collect())
+}
+
+///Readable name of an implementation listed in an `implem` file:
+/// `result/open62541.v1.1.3mode_1` gives `open62541 v1.1.3`.
+fn implementation_name(line: &str) -> String {
+    let name = line.rsplit('/').next().unwrap_or(line);
+    //the mode is the one of the folder of the model
+    let name = match name.rfind("mode_") {
+        Some(index) if name[index + 5..].chars().all(|c| c.is_ascii_digit()) => &name[..index],
+        _ => name,
+    };
+    name.replacen('.', " ", 1)
+}
+
+//nodes named __start0, __start1... mark the initial state with an edge to it
+fn is_start_marker(node_id: &str) -> bool {
+    node_id.starts_with("__start")
+}
+
 #[cfg(test)]
-impl Graph{
+impl Graph {
     pub(crate) fn get_edge(&self, from: &NodeId, to: &NodeId) -> Option<&MultiEdge> {
         if let Some(edges) = self.edges.get(from) {
             match edges.get(to) {
-                Some(multi_edge) => return Some(multi_edge),
-                None => return None,
-            };
+                Some(multi_edge) => Some(multi_edge),
+                None => None,
+            }
         } else {
             None
         }
     }
 }
 
-pub(crate) mod prelude {
+pub mod prelude {
     // pub(crate) use super::super::algorithm::cycle::Cycle;
-    pub(crate) use super::multi_edge::MultiEdge;
-    pub(crate) use super::nodeid::NodeId;
-    pub(crate) use super::Graph;
+    pub use super::multi_edge::MultiEdge;
+    pub use super::nodeid::NodeId;
+    pub use super::Graph;
 }
 #[cfg(test)]
 impl Graph {
     pub(crate) fn new(content: &str, starting_state: bool) -> Self {
-        let lines: Vec<&str> = content.split("\n").collect();
-        let mut nodes: NodeMap = NodeMap::new();
-        let mut edges: EdgeMap = EdgeMap::new();
-        let mut node: Node;
-        for line in lines {
-            if Graph::is_node(&line) {
-                node = Node::new(&line);
-                nodes.insert(node.nodeid.clone(), node);
-            } else if Graph::is_edge(line) {
-                edges.add_edge_from_str(line);
-            }
-        }
-        let transpose = edges.transpose();
-        let mut graph = Graph {
-            name: "test_graph".to_string(),
-            nodes,
-            edges,
-            transpose_edges: transpose,
-            root: None,
-            sinks: Vec::new(),
-        };
-        graph.identify_sink_state();
-        if starting_state {
-            graph.identify_start_state();
+        let dot = dot::parse(content, "test_graph").expect("malformed test graph");
+        let mut graph = Graph::from_dot("test_graph".to_string(), dot, "test_graph", &LoadOptions::default())
+            .expect("malformed test graph");
+        if starting_state && graph.root.is_none() {
+            graph
+                .identify_start_state()
+                .expect("no unique starting state in test graph");
         }
 
         graph
@@ -303,10 +544,13 @@ impl Graph {
 
 #[cfg(test)]
 mod tests {
-  
-    use std::collections::HashSet;
 
-    use crate::graph::{multi_edge::MultiEdge, node::Node, nodeid::NodeId, Graph};
+    use std::{collections::HashSet, path::Path};
+
+    use crate::graph::{
+        add_graph, dot, find_models, graph_name, implementation_name, multi_edge::MultiEdge, node::Node,
+        nodeid::NodeId, Format, Graph, LoadOptions,
+    };
 
     #[test]
     fn read_graph() {
@@ -424,4 +668,131 @@ mod tests {
         let graph: Graph = Graph::new(input, true);
         assert_eq!(*graph.get_root().unwrap(), NodeId::new("0"));
     }
+
+    #[test]
+    fn io_separator() {
+        let input = r#"digraph {
+            s0 -> s1 [label=<<table><tr><td>KEXINIT</td><td>|</td><td>KEXINIT+NEWKEYS</td></tr></table>>];
+            s1 -> s1 [label="hello | ack"];
+        }"#;
+        let options = LoadOptions {
+            io_separator: Some("|".to_string()),
+            ..LoadOptions::default()
+        };
+        let dot = dot::parse(input, "test").unwrap();
+        let graph = Graph::from_dot("test".to_string(), dot, "test", &options).unwrap();
+        let edge = graph.get_edge(&NodeId::new("s0"), &NodeId::new("s1")).unwrap();
+        assert_eq!(edge.get_labels()[0].as_ref(), "KEXINIT/KEXINIT_NEWKEYS");
+        let edge = graph.get_edge(&NodeId::new("s1"), &NodeId::new("s1")).unwrap();
+        assert_eq!(edge.get_labels()[0].as_ref(), "hello/ack");
+
+        let dot = dot::parse("digraph {\n s0 -> s1 [label=\"a/b\"]\n}", "test").unwrap();
+        let error = Graph::from_dot("test".to_string(), dot, "test", &options)
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "test:2:2: no separator \"|\" between input and output in label \"a/b\""
+        );
+    }
+
+    #[test]
+    fn initial_state_markers() {
+        let load = |input: &str, initial_state: Option<&str>| {
+            let options = LoadOptions {
+                initial_state: initial_state.map(|s| s.to_string()),
+                ..LoadOptions::default()
+            };
+            let dot = dot::parse(input, "test").unwrap();
+            let mut graph = Graph::from_dot("test".to_string(), dot, "test", &options)?;
+            graph.resolve_start_state(&options).map(|_| graph)
+        };
+        //the initial state has incoming edges, as in AALpy models
+        let input = r#"digraph learnedModel {
+            s0 [label="s0"];
+            s1 [label="s1"];
+            s0 -> s1 [label="a/b"];
+            s1 -> s0 [label="a/c"];
+            __start0 [label="", shape=none];
+            __start0 -> s0 [label=""];
+        }"#;
+        let graph = load(input, None).unwrap();
+        assert_eq!(*graph.get_root().unwrap(), NodeId::new("s0"));
+        assert_eq!(graph.iter_node_id().count(), 2);
+        let graph = load(input, Some("s1")).unwrap();
+        assert_eq!(*graph.get_root().unwrap(), NodeId::new("s1"));
+        let error = load(input, Some("s2")).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "graph test: initial state s2 is not a state of the mealy machine"
+        );
+
+        let input = r#"digraph {
+            s0 [shape=circle]; s1 [shape=doublecircle];
+            s0 -> s1 [label="a/b"]; s1 -> s0 [label="a/c"];
+        }"#;
+        assert_eq!(*load(input, None).unwrap().get_root().unwrap(), NodeId::new("s1"));
+        let input = r#"digraph {
+            s0 [init=true]; s1 [init=true];
+            s0 -> s1 [label="a/b"]; s1 -> s0 [label="a/c"];
+        }"#;
+        let error = load(input, None).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "graph test: several initial states marked by init: s0, s1"
+        );
+    }
+
+    #[test]
+    fn implementations() {
+        assert_eq!(implementation_name("result/open62541.v1.1.3mode_1"), "open62541 v1.1.3");
+        assert_eq!(implementation_name("result/rust.0.8mode_3"), "rust 0.8");
+        assert_eq!(implementation_name("s2opc"), "s2opc");
+
+        let directory = std::env::temp_dir().join("mealy_verifier_find_models");
+        let _ = std::fs::remove_dir_all(&directory);
+        let model = directory.join("1b380b73").join("automata.dot");
+        std::fs::create_dir_all(model.parent().unwrap()).unwrap();
+        std::fs::write(&model, "digraph { s0 -> s0 [label=\"a/b\"] }").unwrap();
+        std::fs::write(
+            model.with_file_name("implem"),
+            "result/open62541.v1.1.4mode_1\nresult/open62541.v1.1.3mode_1\n",
+        )
+        .unwrap();
+        std::fs::write(directory.join("notes.txt"), "").unwrap();
+        //a link to a parent folder does not make the search loop
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&directory, model.with_file_name("parent")).unwrap();
+        let models = find_models(&directory, Format::Auto).unwrap();
+        assert_eq!(models, vec![model.clone()]);
+        let graph = Graph::new_file(&model.to_string_lossy()).unwrap();
+        assert_eq!(graph.get_implementations(), ["open62541 v1.1.4", "open62541 v1.1.3"]);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn graph_names() {
+        let name = |path: &str| graph_name(Path::new(path));
+        assert_eq!(name("model/1b380b73/automata.dot"), "model/1b380b73/automata.dot");
+        assert_eq!(name("./ssh/../ssh/./openssh.dot"), "ssh/__/ssh/openssh.dot");
+        assert_eq!(name("/models/openssh.dot"), "models/openssh.dot");
+        let current_dir = std::env::current_dir().unwrap();
+        assert_eq!(name(&current_dir.join("a/b.dot").to_string_lossy()), "a/b.dot");
+
+        //`..` and `__` give the same folder, the second graph is left out
+        let mut graphs = Vec::new();
+        let errors: Vec<String> = ["a/../b.dot", "a/__/b.dot"]
+            .iter()
+            .filter_map(|path| {
+                let mut graph = Graph::new("digraph { s0 -> s0 [label=\"a/b\"] }", true);
+                graph.name = graph_name(Path::new(path));
+                add_graph(&mut graphs, graph, path).err().map(|e| e.to_string())
+            })
+            .collect();
+        assert_eq!(graphs.len(), 1);
+        assert_eq!(
+            errors,
+            ["graph a/__/b.dot: a/__/b.dot has the results folder of a model already loaded, it is left out"]
+        );
+    }
 }
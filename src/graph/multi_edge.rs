@@ -1,11 +1,11 @@
 use indexmap::IndexSet;
 use std::{hash::Hash, rc::Rc, vec::IntoIter};
 
-use crate::{utils::unquote, verifier::event::Event};
+use crate::verifier::event::Event;
 
-use super::{element::Elements, nodeid::NodeId};
+use super::{dot, element::Elements, nodeid::NodeId};
 #[derive(Clone, Eq, Debug)]
-pub(crate) struct MultiEdge {
+pub struct MultiEdge {
     from: NodeId,
     to: NodeId,
     label: IndexSet<Rc<str>>,
@@ -19,7 +19,7 @@ impl Hash for MultiEdge {
     }
 }
 impl MultiEdge {
-    pub(crate) fn get_labels(&self) -> Vec<Rc<str>> {
+    pub fn get_labels(&self) -> Vec<Rc<str>> {
         let mut result = Vec::new();
         for i in self.label.iter() {
             result.push(i.clone());
@@ -31,7 +31,7 @@ impl MultiEdge {
         Self {
             from,
             to,
-            label: IndexSet::from([Rc::from(unquote(label))]),
+            label: IndexSet::from([Rc::from(label)]),
             elements: IndexSet::from([elements]),
         }
     }
@@ -51,8 +51,8 @@ impl MultiEdge {
     }
 
     pub(crate) fn add_label(&mut self, label: &str, elements: Elements) {
-        let ret=self.label.insert(Rc::from(unquote(label)));
-        if ret{
+        let ret = self.label.insert(Rc::from(label));
+        if ret {
             self.elements.insert(elements);
         }
     }
@@ -66,7 +66,7 @@ impl MultiEdge {
         )
     }
 
-    pub(crate) fn get_dest(&self) -> &NodeId {
+    pub fn get_dest(&self) -> &NodeId {
         &self.to
     }
 
@@ -84,22 +84,36 @@ impl MultiEdge {
         // let output = String::new();
         let mut res = Vec::new();
         for index in indexes {
-            let s = format!("\t \"{}\"->\"{}\" {};\n", self.from, self.to, self.elements[index]);
+            let s = format!("\t {} {};\n", self.ends(), self.elements[index]);
             res.push(s);
         }
 
         res
     }
 
-    pub(crate) fn get_label_iterator(&self) -> indexmap::set::Iter<'_, Rc<str>> {
+    ///The transition of the label `index` with extra attributes written after its own ones.
+    pub(crate) fn to_string_label_with(&self, index: usize, attributes: &str) -> String {
+        format!("\t {} {} [{}];\n", self.ends(), self.elements[index], attributes)
+    }
+
+    //source and destination as written in a dot file
+    fn ends(&self) -> String {
+        format!(
+            "\"{}\"->\"{}\"",
+            dot::escape(&self.from.to_string()),
+            dot::escape(&self.to.to_string())
+        )
+    }
+
+    pub fn get_label_iterator(&self) -> indexmap::set::Iter<'_, Rc<str>> {
         self.label.iter()
     }
 
-    pub(crate) fn get_source(&self) -> &NodeId {
+    pub fn get_source(&self) -> &NodeId {
         &self.from
     }
 
-    pub(crate) fn get_nb_label(&self) -> usize {
+    pub fn get_nb_label(&self) -> usize {
         self.label.len()
     }
 }
@@ -114,7 +128,7 @@ impl std::fmt::Display for MultiEdge {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut output_string = String::new();
         for elements in self.elements.iter() {
-            output_string.push_str(&format!("\t \"{}\"->\"{}\" {};\n", self.from, self.to, elements));
+            output_string.push_str(&format!("\t {} {};\n", self.ends(), elements));
         }
         write!(f, "{}", output_string)
     }
@@ -122,22 +136,18 @@ impl std::fmt::Display for MultiEdge {
 
 #[cfg(test)]
 impl MultiEdge {
+    ///Edge from an edge statement of a dot file.
     pub(crate) fn new(line: &str) -> Self {
-        let line: String = line.trim().to_string();
-        let index = line.find("[").expect("Malformed file");
-        let elements: Elements = Elements::new(&line[index + 1..]);
-
-        let label = match elements.get_label() {
-            Some(str) => unquote(str).to_string(),
-            None => panic!("Failed to found label for transition {:?}", line),
-        };
-        let index2 = line.find("-").expect("Malformed file");
-        Self {
-            from: NodeId::new(&line[..index2]),
-            to: NodeId::new(&line[index2 + 2..index]),
-            label: IndexSet::from([Rc::from(label)]),
-            elements: IndexSet::from([elements]),
-        }
+        let graph = super::dot::parse_statement(line);
+        let edge = graph.edges.first().expect("no edge in the statement");
+        let label = edge.get_attribute("label").expect("no label in the edge").text.as_str();
+        let elements = Elements::from_attributes(&edge.attributes);
+        MultiEdge::from(
+            NodeId::from_dot(&edge.from),
+            NodeId::from_dot(&edge.to),
+            label,
+            elements,
+        )
     }
 }
 
@@ -157,7 +167,7 @@ mod tests {
             from: NodeId::new("0"),
             to: NodeId::new("1"),
             label: IndexSet::from([Rc::from("a/b")]),
-            elements: IndexSet::from([Elements::new("fontsize=5, label=\"a/b\", color=black")]),
+            elements: IndexSet::from([Elements::from_attributes(&[])]),
         };
         assert_eq!(edge, expected_edge);
     }
@@ -1,26 +1,34 @@
-use super::{element::Element, nodeid::NodeId};
+use std::hash::Hash;
+
+use super::{dot::Attribute, element::Element, nodeid::NodeId};
 // use crate::utils::*;
-#[derive(Debug, Eq, Clone, Hash)]
-pub(crate) struct Node {
+#[derive(Debug, Eq, Clone)]
+pub struct Node {
     pub(crate) nodeid: NodeId,
     pub(crate) elements: Vec<Element>,
 }
 
 impl Node {
-    pub(crate) fn new(line: &str) -> Self {
-        let line: String = line.trim().to_string();
-        let (nodeid, line) = NodeId::from_str(&line);
+    pub(crate) fn from_dot(id: &str, attributes: &[Attribute]) -> Self {
         Node {
-            nodeid: nodeid,
-            elements: Element::new_vec(line),
+            nodeid: NodeId::from_dot(id),
+            elements: Element::from_attributes(attributes),
         }
     }
 
+    ///Node from a node statement of a dot file.
+    #[cfg(test)]
+    pub(crate) fn new(line: &str) -> Self {
+        let graph = super::dot::parse_statement(line);
+        let (id, attributes) = graph.nodes.first().expect("no node in the statement");
+        Node::from_dot(id, attributes)
+    }
+
     pub(crate) fn add_element(&mut self, element: Element) {
         self.elements.push(element);
     }
 
-    pub(crate) fn get_node_id(&self) -> &NodeId {
+    pub fn get_node_id(&self) -> &NodeId {
         &self.nodeid
     }
 
@@ -35,16 +43,17 @@ impl PartialEq for Node {
     }
 }
 
+impl Hash for Node {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.nodeid.hash(state);
+    }
+}
+
 impl std::fmt::Display for Node {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut elements_str = String::from("[");
-        elements_str.push_str(&self.elements[0].to_string());
-        for element in self.elements[1..].iter() {
-            elements_str.push_str(",");
-            elements_str.push_str(&element.to_string());
-        }
-        elements_str.push_str("];");
-        write!(f, "\t \"{}\" {}\n", self.nodeid, elements_str)
+        let elements: Vec<String> = self.elements.iter().map(|e| e.to_string()).collect();
+        let id = super::dot::escape(&self.nodeid.to_string());
+        writeln!(f, "\t \"{}\" [{}];", id, elements.join(","))
     }
 }
 
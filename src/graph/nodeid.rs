@@ -2,22 +2,18 @@ use std::rc::Rc;
 
 use crate::utils::unquote;
 
-#[derive(Debug, Eq, Clone, Hash)]
-pub(crate) struct NodeId {
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct NodeId {
     pub(crate) inner: Rc<str>,
 }
 
 impl NodeId {
-    pub(crate) fn from_str(node_line: &str) -> (NodeId, &str) {
-        let line = node_line.trim();
-        let index = line.find("[").unwrap();
-        let nodeid = NodeId {
-            inner: Rc::from(unquote(line[..index].trim())),
-        };
-        (nodeid, &line[index..])
+    ///Node id from an identifier of a dot file, quotes and escapes are already removed.
+    pub(crate) fn from_dot(id: &str) -> Self {
+        NodeId { inner: Rc::from(id) }
     }
 
-    pub(crate) fn new(node_str: &str) -> Self {
+    pub fn new(node_str: &str) -> Self {
         let line: String = node_str.trim().to_string();
         NodeId {
             inner: Rc::from(unquote(&line)),
@@ -30,11 +26,6 @@ impl std::fmt::Display for NodeId {
         write!(f, "{}", self.inner)
     }
 }
-impl PartialEq for NodeId {
-    fn eq(&self, other: &Self) -> bool {
-        self.inner.as_ref().eq(other.inner.as_ref())
-    }
-}
 impl PartialEq<Rc<str>> for NodeId {
     fn eq(&self, other: &Rc<str>) -> bool {
         self.inner.as_ref().eq(other.as_ref())
@@ -48,7 +39,7 @@ impl PartialEq<str> for NodeId {
 
 impl PartialEq<String> for NodeId {
     fn eq(&self, other: &String) -> bool {
-        (&*self.inner).eq(&*other)
+        (*self.inner).eq(other)
     }
 }
 #[cfg(test)]
@@ -69,14 +60,8 @@ mod tests {
     }
 
     #[test]
-    fn test_from_str() {
-        let (node_id, other) =
-            NodeId::from_str("\"5\" [shape=ellipse,style=filled,fillcolor=white,URL=\"3\", color=red];");
-        assert_eq!(node_id.inner.as_ref(), "5");
-        assert_eq!(
-            other,
-            "[shape=ellipse,style=filled,fillcolor=white,URL=\"3\", color=red];"
-        );
-        assert!(!node_id.get_inner().contains("\""));
+    fn test_from_dot() {
+        let node_id = NodeId::from_dot("a \"5\"");
+        assert_eq!(node_id.inner.as_ref(), "a \"5\"");
     }
 }
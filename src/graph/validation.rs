@@ -0,0 +1,204 @@
+//! Checks made on a Mealy machine once loaded: every state answers every input of the machine,
+//! with one transition only, and is reachable from the initial state.
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
+use std::rc::Rc;
+
+use super::{element::Elements, multi_edge::MultiEdge, nodeid::NodeId, Graph};
+use crate::error::{Error, Result};
+
+/// What to do with a Mealy machine which is not input complete, not deterministic or has unreachable states.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Validation {
+    /// report the problems as warnings and verify the machine as it is
+    #[default]
+    Warn,
+    /// refuse the machine, it is reported as an error
+    Fail,
+    /// add a self loop with the default output for each missing input, the other problems are warnings
+    Complete,
+}
+
+/// Output of the transitions added for the missing inputs when none is given.
+pub const DEFAULT_OUTPUT: &str = "missing";
+
+//input of a label, trimmed as the inputs of `Graph::get_inputs` since .automaton labels are written `input / output`
+fn input(label: &str) -> &str {
+    label.split_once('/').map_or(label, |(input, _)| input).trim()
+}
+
+impl Graph {
+    //inputs of the machine without transition from each state, in the order of the graph
+    fn missing_inputs(&self) -> Vec<(NodeId, Vec<String>)> {
+        let inputs = self.get_inputs();
+        self.iter_node_id()
+            .filter_map(|node_id| {
+                let answered: BTreeSet<String> = self
+                    .get_outgoing_labels(node_id)
+                    .iter()
+                    .map(|label| input(label).to_string())
+                    .collect();
+                let missing: Vec<String> = inputs.difference(&answered).cloned().collect();
+                (!missing.is_empty()).then(|| (node_id.clone(), missing))
+            })
+            .collect()
+    }
+
+    //inputs of each state with several transitions, written `label -> destination`
+    fn nondeterministic_inputs(&self) -> Vec<(NodeId, String, Vec<String>)> {
+        let mut result = Vec::new();
+        for node_id in self.iter_node_id() {
+            let mut transitions: BTreeMap<String, Vec<String>> = BTreeMap::new();
+            for (neighbor_id, edge) in self.neighbors_edges_iterator(node_id) {
+                for label in edge.get_labels() {
+                    let transition = format!("{} -> {}", label, neighbor_id);
+                    transitions
+                        .entry(input(&label).to_string())
+                        .or_default()
+                        .push(transition);
+                }
+            }
+            for (input, mut transitions) in transitions {
+                if transitions.len() > 1 {
+                    transitions.sort();
+                    result.push((node_id.clone(), input, transitions));
+                }
+            }
+        }
+        result
+    }
+
+    //states that can not be reached from the initial state, in the order of the graph
+    fn unreachable_states(&self) -> Vec<NodeId> {
+        let root = match self.get_root() {
+            Some(root) => root,
+            None => return Vec::new(),
+        };
+        let mut reached: HashSet<NodeId> = HashSet::from([root.clone()]);
+        let mut queue = VecDeque::from([root.clone()]);
+        while let Some(node_id) = queue.pop_front() {
+            for (neighbor_id, _) in self.neighbors_edges_iterator(&node_id) {
+                if reached.insert(neighbor_id.clone()) {
+                    queue.push_back(neighbor_id);
+                }
+            }
+        }
+        self.iter_node_id()
+            .filter(|id| !reached.contains(*id))
+            .cloned()
+            .collect()
+    }
+
+    //a self loop answering each missing input with the output
+    fn complete(&mut self, missing: &[(NodeId, Vec<String>)], output: &str) {
+        for (node_id, inputs) in missing {
+            for input in inputs {
+                let label: Rc<str> = Rc::from(format!("{}/{}", input, output));
+                let elements = Elements::default_edge(&label);
+                let edge = MultiEdge::from(node_id.clone(), node_id.clone(), &label, elements);
+                self.edges.add_edge(&edge);
+            }
+        }
+        self.transpose_edges = self.edges.transpose();
+        self.identify_sink_state();
+    }
+
+    ///Check that the machine is input complete, deterministic and that every state is reachable.
+    /// The problems are kept as warnings of the graph, or make the loading fail, depending on `validation`.
+    pub(crate) fn validate(&mut self, validation: Validation, default_output: Option<&str>) -> Result<()> {
+        let mut issues = Vec::new();
+        let missing = self.missing_inputs();
+        if validation == Validation::Complete {
+            self.complete(&missing, default_output.unwrap_or(DEFAULT_OUTPUT));
+        } else {
+            for (node_id, inputs) in missing {
+                issues.push(format!("state {} has no transition for {}", node_id, inputs.join(", ")));
+            }
+        }
+        for (node_id, input, transitions) in self.nondeterministic_inputs() {
+            issues.push(format!(
+                "state {} has several transitions for {}: {}",
+                node_id,
+                input,
+                transitions.join(", ")
+            ));
+        }
+        let unreachable = self.unreachable_states();
+        if !unreachable.is_empty() {
+            let states: Vec<String> = unreachable.iter().map(|id| id.to_string()).collect();
+            issues.push(format!("unreachable states: {}", states.join(", ")));
+        }
+        match validation {
+            Validation::Fail if !issues.is_empty() => Err(Error::graph(
+                &self.name,
+                format!("not a valid Mealy machine, {}", issues.join("; ")),
+            )),
+            _ => {
+                self.warnings = issues;
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{nodeid::NodeId, Graph};
+
+    use super::Validation;
+
+    #[test]
+    fn validation() {
+        let input = r#"digraph "Automata" {
+            "0" [shape=doublecircle];
+            "0" -> "1" [label="a/x"];
+            "0" -> "0" [label="b/y"];
+            "1" -> "1" [label="a/x"];
+            "1" -> "0" [label="a/z"];
+            "2" -> "2" [label="a/x"];
+            "2" -> "2" [label="b/x"];
+        }"#;
+        //1 answers a twice and does not answer b, 2 is not reachable
+        let mut graph = Graph::new(input, true);
+        graph.validate(Validation::Warn, None).unwrap();
+        assert_eq!(
+            graph.get_warnings(),
+            [
+                "state 1 has no transition for b",
+                "state 1 has several transitions for a: a/x -> 1, a/z -> 0",
+                "unreachable states: 2",
+            ]
+        );
+
+        let mut graph = Graph::new(input, true);
+        let error = graph.validate(Validation::Fail, None).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "graph test_graph: not a valid Mealy machine, state 1 has no transition for b; \
+             state 1 has several transitions for a: a/x -> 1, a/z -> 0; unreachable states: 2"
+        );
+
+        let mut graph = Graph::new(input, true);
+        graph.validate(Validation::Complete, Some("none")).unwrap();
+        assert_eq!(graph.get_warnings().len(), 2);
+        let labels = graph.get_outgoing_labels(&NodeId::new("1"));
+        assert!(labels.iter().any(|label| label.as_ref() == "b/none"));
+        assert_eq!(graph.get_sink_state(), &vec![NodeId::new("2")]);
+
+        //the spaces around the separator do not make two inputs
+        let mut graph = Graph::new(
+            r#"digraph "Automata" {
+            "0" [shape=doublecircle];
+            "0" -> "0" [label="a/x"];
+            "0" -> "1" [label="a / y"];
+            "1" -> "1" [label="a / x"];
+        }"#,
+            true,
+        );
+        graph.validate(Validation::Warn, None).unwrap();
+        assert_eq!(
+            graph.get_warnings(),
+            ["state 0 has several transitions for a: a / y -> 1, a/x -> 0"]
+        );
+    }
+}
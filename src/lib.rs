@@ -0,0 +1,38 @@
+//!The Mealy verifier is a tool dedicated to analysis of Mealy machines.
+//!The main target of the Mealy verifier is the output of model learning of network protocol implementation.
+//!
+//!The crate can be embedded in other tools:
+//!```no_run
+//!use mealy_verifier::{parse_file, Graph, Verifier};
+//!
+//!let (rules, errors) = parse_file("rules/opcua.opcua");
+//!for error in errors.iter() {
+//!    eprintln!("{}", error);
+//!}
+//!let graph = Graph::new_file("automata.dot").expect("invalid graph");
+//!let mut verifier = Verifier::new(rules, vec![graph], "result".into());
+//!for graph_result in verifier.apply() {
+//!    for rule_result in graph_result.rules.iter() {
+//!        println!("{} {} {}", graph_result.graph, rule_result.rule, rule_result.holds);
+//!    }
+//!}
+//!```
+pub mod cli;
+pub mod config;
+pub mod error;
+pub mod graph;
+pub mod report;
+pub mod utils;
+pub mod verifier;
+
+pub use error::{Error, Location};
+pub use graph::{Format, Graph, LoadOptions, Validation};
+pub use report::{
+    matrix::Matrix,
+    outcome::{FailOn, Outcome},
+    Report,
+};
+pub use verifier::result::{GraphResult, RuleResult, Transition};
+pub use verifier::rules::{parse_file, Rule, Severity};
+pub use verifier::trace::Trace;
+pub use verifier::Verifier;
@@ -1,17 +1,148 @@
-mod cli;
-// mod error;
-mod graph;
-mod utils;
-mod verifier;
+use std::{
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
 
-use crate::cli::Args;
 use clap::Parser;
-use verifier::Verifier;
+use mealy_verifier::{
+    cli::{Args, Cli, Command},
+    config::Config,
+    utils::output,
+    Error, FailOn, GraphResult, Matrix, Outcome, Report, Verifier,
+};
 
 ///The Mealy verifier is a tool dedicated to analysis of Mealy machines.
 ///The main target of the Mealy verifier is the output of model learning of network protocol implementation.
-fn main() {
-    let args = Args::parse();
-    let mut verifier = Verifier::from_args(args);
-    verifier.apply();
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let outcome = match cli.command {
+        Some(Command::Run {
+            config,
+            output_folder,
+            force,
+            fail_on,
+        }) => run(&config, output_folder, force, &FailOn::new(&fail_on)),
+        None => check(cli.args.expect("the rules and graphs are required without subcommand")),
+    };
+    ExitCode::from(outcome.code())
+}
+
+///Check the rules on the graphs given on the command line.
+fn check(mut args: Args) -> Outcome {
+    let fail_on = FailOn::new(&args.fail_on);
+    match &args.output_folder {
+        Some(output_folder) => {
+            if let Err(e) = output::check_output_folder(output_folder, args.force) {
+                eprintln!("error: {}", e);
+                return Outcome::from_error(&e);
+            }
+        }
+        None => match output::new_output_folder() {
+            Ok(output_folder) => {
+                println!("Output folder is  {}", output_folder.display());
+                args.output_folder = Some(output_folder);
+            }
+            Err(e) => {
+                eprintln!("error: {}", e);
+                return Outcome::from_error(&e);
+            }
+        },
+    }
+    let (mut verifier, errors) = Verifier::from_args(args);
+    if let Err(e) = fail_on.check(verifier.get_rules().iter().map(|r| r.get_name())) {
+        eprintln!("error: {}", e);
+        return Outcome::from_error(&e);
+    }
+    let results = verifier.apply();
+    print_errors(&results, &errors);
+    let report = Report::new(&results, &errors);
+    print_failures(&report);
+    let outcome = Outcome::new(&results, &errors, &fail_on);
+    outcome.max(write_report(&report, verifier.get_output_folder()))
+}
+
+///Run every campaign of the configuration file and write a combined report.
+fn run(config_path: &Path, output_folder: Option<PathBuf>, force: bool, fail_on: &FailOn) -> Outcome {
+    let mut config = match Config::from_file(config_path) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return Outcome::from_error(&e);
+        }
+    };
+    if let Some(output_folder) = output_folder {
+        config.set_output_folder(output_folder);
+    }
+    if let Err(e) = config.check_output_folders(force) {
+        eprintln!("error: {}", e);
+        return Outcome::from_error(&e);
+    }
+    let mut outcome = Outcome::Holds;
+    let mut results = Vec::new();
+    let mut errors = Vec::new();
+    let mut rules = Vec::new();
+    for campaign in config.run() {
+        print_errors(&campaign.results, &campaign.errors);
+        let report = Report::new(&campaign.results, &campaign.errors);
+        print_failures(&report);
+        println!("{}: {}", campaign.name, report.summary());
+        outcome = outcome.max(write_report(&report, &campaign.output_folder));
+        results.extend(campaign.results);
+        errors.extend(campaign.errors);
+        rules.extend(campaign.rules);
+    }
+    //the reports of the campaigns are written, but a typo in --fail-on must not let the run pass
+    if let Err(e) = fail_on.check(rules.iter().map(String::as_str)) {
+        eprintln!("error: {}", e);
+        outcome = outcome.max(Outcome::from_error(&e));
+    }
+    let report = Report::new(&results, &errors);
+    println!("total: {}", report.summary());
+    outcome
+        .max(Outcome::new(&results, &errors, fail_on))
+        .max(write_report(&report, &config.get_output_folder()))
+}
+
+fn print_errors(results: &[GraphResult], errors: &[Error]) {
+    for result in results {
+        for warning in result.warnings.iter() {
+            eprintln!("warning: graph {}: {}", result.graph, warning);
+        }
+        for rule in result.rules.iter() {
+            for warning in rule.warnings.iter() {
+                eprintln!("warning: graph {}: rule {}: {}", result.graph, rule.rule, warning);
+            }
+        }
+    }
+    for error in errors.iter().chain(results.iter().flat_map(|r| r.errors.iter())) {
+        eprintln!("error: {}", error);
+    }
+}
+
+///Print the rules which do not hold, by implementation.
+fn print_failures(report: &Report) {
+    for failure in report.failures() {
+        println!("{}", failure);
+    }
+}
+
+///Write report.json, report.html and the verdict matrix in the output folder, an I/O error if they could not be written.
+fn write_report(report: &Report, output_folder: &Path) -> Outcome {
+    if let Err(e) = std::fs::create_dir_all(output_folder) {
+        eprintln!("error: {}: {}", output_folder.display(), e);
+        return Outcome::IoError;
+    }
+    if let Err(e) = report.write_json(&output_folder.join("report.json")) {
+        eprintln!("error: {}", e);
+        return Outcome::from_error(&e);
+    }
+    if let Err(e) = Matrix::new(report).write(output_folder) {
+        eprintln!("error: {}", e);
+        return Outcome::from_error(&e);
+    }
+    if let Err(e) = report.write_html(&output_folder.join("report.html")) {
+        eprintln!("error: {}", e);
+        return Outcome::from_error(&e);
+    }
+    Outcome::Holds
 }
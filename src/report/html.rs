@@ -0,0 +1,232 @@
+//! Self contained HTML page of a report: one collapsible entry per graph and rule,
+//! with the counterexamples drawn in SVG and the traces leading to the violations.
+use std::path::Path;
+
+use indexmap::IndexMap;
+
+use super::{html_escape, layout::Diagram, Report, ReportEntry, Status};
+use crate::{
+    error::{Error, Result},
+    graph::dot,
+};
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 20px; }
+details { margin: 4px 0 4px 16px; }
+summary { cursor: pointer; }
+.status { display: inline-block; width: 3.5em; text-align: center; border-radius: 3px; font-size: 0.9em; }
+.pass .status { background: #cfc; }
+.fail .status { background: #fcc; }
+.error .status { background: #ccc; }
+.severity { color: #666; }
+.figure { overflow-x: auto; margin: 8px 0; }
+svg rect { fill: white; stroke: black; }
+svg text.state { text-anchor: middle; font-size: 12px; }
+svg text.label { font-size: 11px; }
+pre { background: #f4f4f4; padding: 6px; }
+";
+
+const SCRIPT: &str = "
+function toggle(open) {
+    document.querySelectorAll('details').forEach(function (d) { d.open = open; });
+}
+";
+
+//arrow heads shared by every drawing of the page
+const MARKERS: &str = "<svg width=\"0\" height=\"0\" style=\"position: absolute\"><defs>\
+<marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" markerWidth=\"8\" markerHeight=\"8\" orient=\"auto\">\
+<path d=\"M 0 0 L 10 5 L 0 10 z\" fill=\"black\"/></marker>\
+<marker id=\"arrow-red\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" markerWidth=\"8\" markerHeight=\"8\" orient=\"auto\">\
+<path d=\"M 0 0 L 10 5 L 0 10 z\" fill=\"red\"/></marker>\
+</defs></svg>\n";
+
+impl Report {
+    /// HTML page of the report, the counterexample files written in the output folder are read to draw them.
+    pub fn to_html(&self) -> String {
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Mealy verifier report</title>\n\
+             <style>{}</style>\n<script>{}</script>\n</head>\n<body>\n{}",
+            STYLE, SCRIPT, MARKERS
+        );
+        html.push_str(&format!(
+            "<h1>Mealy verifier report</h1>\n<p>{}</p>\n\
+             <p><button onclick=\"toggle(true)\">expand all</button> \
+             <button onclick=\"toggle(false)\">collapse all</button></p>\n",
+            html_escape(&self.summary())
+        ));
+        if !self.errors.is_empty() {
+            html.push_str("<h2>Errors</h2>\n<ul>\n");
+            for error in self.errors.iter() {
+                html.push_str(&format!("<li>{}</li>\n", html_escape(error)));
+            }
+            html.push_str("</ul>\n");
+        }
+        if !self.warnings.is_empty() {
+            html.push_str("<h2>Warnings</h2>\n<ul>\n");
+            for warning in self.warnings.iter() {
+                html.push_str(&format!("<li>{}</li>\n", html_escape(warning)));
+            }
+            html.push_str("</ul>\n");
+        }
+        let mut graphs: IndexMap<&str, Vec<&ReportEntry>> = IndexMap::new();
+        for entry in self.entries.iter() {
+            graphs.entry(&entry.graph).or_default().push(entry);
+        }
+        for (graph, entries) in graphs {
+            let count = |status: Status| entries.iter().filter(|e| e.status == status).count();
+            let failed = count(Status::Fail) + count(Status::Error) > 0;
+            html.push_str(&format!(
+                "<details class=\"graph\"{}>\n<summary title=\"{}\"><b>{}</b>: {} pass, {} fail, {} error</summary>\n",
+                if failed { " open" } else { "" },
+                html_escape(graph),
+                html_escape(&entries[0].label()),
+                count(Status::Pass),
+                count(Status::Fail),
+                count(Status::Error)
+            ));
+            for entry in entries {
+                html.push_str(&entry_html(entry));
+            }
+            html.push_str("</details>\n");
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    pub fn write_html(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, self.to_html()).map_err(|e| Error::io(path, e))
+    }
+}
+
+fn entry_html(entry: &ReportEntry) -> String {
+    let mut html = format!(
+        "<details class=\"{}\">\n<summary><span class=\"status\">{}</span> {}",
+        entry.status.as_str(),
+        entry.status.as_str(),
+        html_escape(&entry.rule)
+    );
+    if let Some(severity) = entry.severity {
+        html.push_str(&format!(" <span class=\"severity\">({})</span>", severity));
+    }
+    if entry.violations > 0 {
+        html.push_str(&format!(": {} violating transitions", entry.violations));
+    } else if !entry.states.is_empty() {
+        html.push_str(&format!(": {} violating states", entry.states.len()));
+    }
+    html.push_str("</summary>\n");
+    if let Some(error) = &entry.error {
+        html.push_str(&format!("<p>{}</p>\n", html_escape(error)));
+    }
+    for warning in entry.warnings.iter() {
+        html.push_str(&format!("<p>warning: {}</p>\n", html_escape(warning)));
+    }
+    if !entry.edges.is_empty() {
+        html.push_str("<ul>\n");
+        for edge in entry.edges.iter() {
+            html.push_str(&format!("<li>{}</li>\n", html_escape(&edge.to_string())));
+        }
+        html.push_str("</ul>\n");
+    }
+    if !entry.states.is_empty() {
+        let states: Vec<String> = entry.states.iter().map(|s| html_escape(s)).collect();
+        html.push_str(&format!("<p>violating states: {}</p>\n", states.join(", ")));
+    }
+    let mut drawn = false;
+    for path in entry.counterexamples.iter() {
+        if path.extension().is_some_and(|e| e == "dot") {
+            html.push_str(&format!("<p>{}</p>\n", html_escape(&path.display().to_string())));
+            let figure = figure(path, entry);
+            drawn |= !figure.is_empty();
+            html.push_str(&figure);
+        }
+    }
+    //the witness is drawn from the transitions only when no file of the rule shows it already
+    if !drawn && !entry.witness.is_empty() {
+        let mut diagram = Diagram::default();
+        for transition in entry.witness.iter() {
+            diagram.add_edge(&transition.source, &transition.destination, &transition.label, false);
+        }
+        html.push_str("<p>witness</p>\n");
+        html.push_str(&format!("<div class=\"figure\">{}</div>\n", diagram.to_svg()));
+    }
+    if !entry.traces.is_empty() {
+        let traces: Vec<String> = entry.traces.iter().map(|t| html_escape(&t.to_string())).collect();
+        html.push_str(&format!("<p>traces</p>\n<pre>{}</pre>\n", traces.join("\n")));
+    }
+    html.push_str("</details>\n");
+    html
+}
+
+//drawing of a counterexample file, the violations of the entry in red
+fn figure(path: &Path, entry: &ReportEntry) -> String {
+    let source = path.display().to_string();
+    let graph = std::fs::read_to_string(path)
+        .map_err(|e| Error::io(path, e))
+        .and_then(|content| dot::parse(&content, &source));
+    match graph {
+        Ok(graph) => {
+            let diagram = Diagram::from_dot(&graph, |from, to, label| {
+                entry
+                    .edges
+                    .iter()
+                    .any(|t| t.source == from && t.destination == to && t.label == label)
+            });
+            if diagram.is_empty() {
+                return String::new();
+            }
+            format!("<div class=\"figure\">{}</div>\n", diagram.to_svg())
+        }
+        Err(e) => format!("<p>{}</p>\n", html_escape(&e.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        graph::nodeid::NodeId,
+        report::Report,
+        verifier::{
+            result::{GraphResult, RuleResult, Transition},
+            trace::Trace,
+        },
+    };
+
+    #[test]
+    fn html_report() {
+        let folder = std::env::temp_dir().join(format!("mealy_verifier_html_{}", std::process::id()));
+        std::fs::create_dir_all(&folder).unwrap();
+        let counterexample = folder.join("ce.dot");
+        std::fs::write(
+            &counterexample,
+            "digraph \"Automata\" {\n\t \"0\" [shape=ellipse];\n\t \"0\"->\"1\" [label=\"open/err\"];\n}\n",
+        )
+        .unwrap();
+        let violation = Transition::new(&NodeId::new("0"), &NodeId::new("1"), "open/err");
+        let mut failing = RuleResult::from_violations("open_first", vec![violation.clone()]);
+        failing.add_counterexample(Some(counterexample));
+        failing.add_traces(vec![Trace::new(vec![violation.clone()])], Vec::new());
+        let witness = folder.join("witness.dot");
+        std::fs::write(&witness, std::fs::read_to_string(folder.join("ce.dot")).unwrap()).unwrap();
+        let mut cycle = RuleResult::from_witness("cycle", Some(vec![violation]));
+        cycle.add_counterexample(Some(witness));
+        let graph_result = GraphResult {
+            graph: "model/automata.dot".to_string(),
+            implementations: vec!["rust 0.8".to_string()],
+            rules: vec![RuleResult::from_violations("hello_first", Vec::new()), failing, cycle],
+            errors: Vec::new(),
+            warnings: Vec::new(),
+        };
+        let html = Report::new(&[graph_result], &[]).to_html();
+        std::fs::remove_dir_all(&folder).unwrap();
+
+        assert!(html.contains("<details class=\"graph\" open>\n<summary title=\"model/automata.dot\"><b>rust 0.8</b>: 2 pass, 1 fail, 0 error</summary>"));
+        assert!(html.contains("<details class=\"fail\">\n<summary><span class=\"status\">fail</span> open_first: 1 violating transitions</summary>"));
+        assert!(
+            html.contains("<path d=\"M 46.0 50.0 C 46.0 82.0, 46.0 82.0, 46.0 120.0\" fill=\"none\" stroke=\"red\"")
+        );
+        assert!(html.contains("<pre># 0 -&gt; 1 [open/err]\nopen / err\n</pre>"));
+        //the witness is drawn once, from witness.dot
+        assert!(html.contains("witness.dot</p>\n<div class=\"figure\">"));
+        assert!(!html.contains("<p>witness</p>"));
+    }
+}
@@ -0,0 +1,447 @@
+//! Layered layout of the small graphs of the counterexamples, rendered to SVG for the HTML report without Graphviz.
+//! The edges closing a cycle are turned back, each state is put one layer below its deepest predecessor,
+//! then the states of each layer are ordered by the barycenter of their neighbours to limit the crossings.
+use indexmap::IndexMap;
+
+use super::html_escape;
+use crate::graph::dot::DotGraph;
+
+const MARGIN: f64 = 20.0;
+const NODE_HEIGHT: f64 = 30.0;
+const NODE_GAP: f64 = 40.0;
+const LAYER_GAP: f64 = 100.0;
+const CHAR_WIDTH: f64 = 7.0;
+const LABEL_CHAR_WIDTH: f64 = 6.5;
+const LINE_HEIGHT: f64 = 14.0;
+const LABEL_GAP: f64 = 6.0;
+//number of sweeps ordering the layers, alternately downward and upward
+const SWEEPS: usize = 4;
+
+#[derive(Debug)]
+pub(crate) struct DiagramEdge {
+    from: usize,
+    to: usize,
+    /// labels of the transitions between the two states, written one per line
+    labels: Vec<String>,
+    highlight: bool,
+}
+
+/// States and transitions to lay out, the transitions between the same states are merged.
+#[derive(Debug, Default)]
+pub(crate) struct Diagram {
+    nodes: IndexMap<String, String>,
+    edges: Vec<DiagramEdge>,
+}
+
+/// Position of the center of a state.
+#[derive(Debug, Clone, Copy)]
+struct Point {
+    x: f64,
+    y: f64,
+}
+
+impl Diagram {
+    /// Diagram of a parsed dot file, the transitions for which `highlight` is true are drawn in red.
+    pub(crate) fn from_dot(dot: &DotGraph, highlight: impl Fn(&str, &str, &str) -> bool) -> Self {
+        let mut diagram = Diagram::default();
+        for (id, attributes) in dot.nodes.iter() {
+            let label = attributes
+                .iter()
+                .find(|a| a.name == "label")
+                .map_or(id.clone(), |a| a.value.label_text());
+            diagram.nodes.insert(id.clone(), label);
+        }
+        for edge in dot.edges.iter() {
+            let label = edge.get_attribute("label").map(|l| l.label_text()).unwrap_or_default();
+            let highlighted = highlight(&edge.from, &edge.to, &label);
+            diagram.add_edge(&edge.from, &edge.to, &label, highlighted);
+        }
+        diagram
+    }
+
+    fn node_index(&mut self, id: &str) -> usize {
+        match self.nodes.get_index_of(id) {
+            Some(index) => index,
+            None => self.nodes.insert_full(id.to_string(), id.to_string()).0,
+        }
+    }
+
+    pub(crate) fn add_edge(&mut self, from: &str, to: &str, label: &str, highlight: bool) {
+        let (from, to) = (self.node_index(from), self.node_index(to));
+        match self.edges.iter_mut().find(|e| e.from == from && e.to == to) {
+            Some(edge) => {
+                edge.labels.push(label.to_string());
+                edge.highlight |= highlight;
+            }
+            None => self.edges.push(DiagramEdge {
+                from,
+                to,
+                labels: vec![label.to_string()],
+                highlight,
+            }),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    //edges closing a cycle in a depth first search from the states in their order
+    fn back_edges(&self) -> Vec<bool> {
+        let n = self.nodes.len();
+        let mut successors: Vec<Vec<(usize, usize)>> = vec![Vec::new(); n];
+        for (index, edge) in self.edges.iter().enumerate() {
+            successors[edge.from].push((edge.to, index));
+        }
+        let mut back = vec![false; self.edges.len()];
+        //0: not visited, 1: on the stack, 2: done
+        let mut state = vec![0u8; n];
+        for start in 0..n {
+            if state[start] != 0 {
+                continue;
+            }
+            let mut stack = vec![(start, 0)];
+            state[start] = 1;
+            while let Some((node, next)) = stack.pop() {
+                match successors[node].get(next) {
+                    Some(&(successor, edge)) => {
+                        stack.push((node, next + 1));
+                        match state[successor] {
+                            0 => {
+                                state[successor] = 1;
+                                stack.push((successor, 0));
+                            }
+                            //the loops are drawn on their own
+                            1 => back[edge] = successor != node,
+                            _ => (),
+                        }
+                    }
+                    None => state[node] = 2,
+                }
+            }
+        }
+        back
+    }
+
+    //layer of each state: the longest path to it once the back edges are left out
+    fn layers(&self, back: &[bool]) -> Vec<usize> {
+        let n = self.nodes.len();
+        let forward: Vec<&DiagramEdge> = self
+            .edges
+            .iter()
+            .zip(back.iter())
+            .filter(|(e, back)| !**back && e.from != e.to)
+            .map(|(e, _)| e)
+            .collect();
+        let mut incoming = vec![0; n];
+        for edge in forward.iter() {
+            incoming[edge.to] += 1;
+        }
+        let mut layer = vec![0; n];
+        let mut ready: Vec<usize> = (0..n).filter(|v| incoming[*v] == 0).rev().collect();
+        while let Some(node) = ready.pop() {
+            for edge in forward.iter().filter(|e| e.from == node) {
+                layer[edge.to] = layer[edge.to].max(layer[node] + 1);
+                incoming[edge.to] -= 1;
+                if incoming[edge.to] == 0 {
+                    ready.push(edge.to);
+                }
+            }
+        }
+        layer
+    }
+
+    //states of each layer, ordered by the barycenter of their neighbours in the previous layer of the sweep
+    fn order(&self, layer: &[usize]) -> Vec<Vec<usize>> {
+        let depth = layer.iter().max().map_or(0, |max| max + 1);
+        let mut layers: Vec<Vec<usize>> = vec![Vec::new(); depth];
+        for (node, l) in layer.iter().enumerate() {
+            layers[*l].push(node);
+        }
+        let mut position = vec![0.0; layer.len()];
+        for nodes in layers.iter() {
+            for (index, node) in nodes.iter().enumerate() {
+                position[*node] = index as f64;
+            }
+        }
+        for sweep in 0..SWEEPS {
+            let downward = sweep % 2 == 0;
+            let indexes: Vec<usize> = if downward {
+                (1..depth).collect()
+            } else {
+                (0..depth.saturating_sub(1)).rev().collect()
+            };
+            for l in indexes {
+                let fixed = if downward { l - 1 } else { l + 1 };
+                let mut keys: Vec<(usize, f64)> = layers[l]
+                    .iter()
+                    .map(|node| {
+                        let neighbours: Vec<f64> = self
+                            .edges
+                            .iter()
+                            .filter_map(|e| match (e.from == *node, e.to == *node) {
+                                (true, false) if layer[e.to] == fixed => Some(position[e.to]),
+                                (false, true) if layer[e.from] == fixed => Some(position[e.from]),
+                                _ => None,
+                            })
+                            .collect();
+                        if neighbours.is_empty() {
+                            (*node, position[*node])
+                        } else {
+                            (*node, neighbours.iter().sum::<f64>() / neighbours.len() as f64)
+                        }
+                    })
+                    .collect();
+                keys.sort_by(|a, b| a.1.total_cmp(&b.1));
+                layers[l] = keys.into_iter().map(|(node, _)| node).collect();
+                for (index, node) in layers[l].iter().enumerate() {
+                    position[*node] = index as f64;
+                }
+            }
+        }
+        layers
+    }
+
+    fn node_width(&self, node: usize) -> f64 {
+        let label = self.nodes.get_index(node).map_or("", |(_, label)| label.as_str());
+        (label.chars().count() as f64 * CHAR_WIDTH + 20.0).max(40.0)
+    }
+
+    /// SVG drawing of the diagram, the arrow heads `#arrow` and `#arrow-red` are defined once by the HTML report.
+    pub(crate) fn to_svg(&self) -> String {
+        let back = self.back_edges();
+        let layer = self.layers(&back);
+        let layers = self.order(&layer);
+        let widths: Vec<f64> = (0..self.nodes.len()).map(|node| self.node_width(node)).collect();
+        let forward = |edge: &DiagramEdge| edge.from != edge.to && layer[edge.to] > layer[edge.from];
+        //room taken on the left and on the right of the center of each state, with the labels written next to it
+        let mut extent: Vec<(f64, f64)> = widths.iter().map(|w| (w / 2.0, w / 2.0)).collect();
+        //height of the labels of the edges entering each state, stacked above it
+        let mut incoming = vec![0.0; self.nodes.len()];
+        //height of the labels entering each layer, and half the height of the labels of its loops
+        let mut label_height = vec![0.0; layers.len()];
+        let mut loop_height = vec![NODE_HEIGHT / 2.0; layers.len()];
+        for edge in self.edges.iter() {
+            let label_width = label_width(&edge.labels);
+            let height = edge.labels.len() as f64 * LINE_HEIGHT;
+            let l = layer[edge.to];
+            if edge.from == edge.to {
+                let (_, right) = &mut extent[edge.to];
+                *right = right.max(widths[edge.to] / 2.0 + 40.0 + label_width);
+                loop_height[l] = f64::max(loop_height[l], height / 2.0);
+            } else if forward(edge) {
+                let (left, right) = &mut extent[edge.to];
+                *left = left.max(label_width / 2.0);
+                *right = right.max(label_width / 2.0);
+                incoming[edge.to] += height + LABEL_GAP;
+                label_height[l] = f64::max(label_height[l], incoming[edge.to]);
+            }
+        }
+        //gap between the centers of a layer and of the previous one, enough for the labels written in it
+        let gaps: Vec<f64> = (0..layers.len())
+            .map(|l| match l {
+                0 => 0.0,
+                _ => f64::max(LAYER_GAP, loop_height[l - 1] + label_height[l] + loop_height[l] + 40.0),
+            })
+            .collect();
+        let layer_width = |nodes: &Vec<usize>| {
+            nodes.iter().map(|node| extent[*node].0 + extent[*node].1).sum::<f64>()
+                + NODE_GAP * nodes.len().saturating_sub(1) as f64
+        };
+        let max_width = layers.iter().map(layer_width).fold(0.0, f64::max);
+        let mut center = vec![Point { x: 0.0, y: 0.0 }; self.nodes.len()];
+        let mut y = MARGIN + NODE_HEIGHT / 2.0;
+        for (l, nodes) in layers.iter().enumerate() {
+            y += gaps[l];
+            let mut x = MARGIN + (max_width - layer_width(nodes)) / 2.0;
+            for node in nodes {
+                center[*node] = Point {
+                    x: x + extent[*node].0,
+                    y,
+                };
+                x += extent[*node].0 + extent[*node].1 + NODE_GAP;
+            }
+        }
+        let mut bounds = Bounds {
+            min: Point { x: 0.0, y: 0.0 },
+            max: Point {
+                x: MARGIN * 2.0 + max_width,
+                y: y + NODE_HEIGHT / 2.0 + MARGIN,
+            },
+        };
+        let mut body = String::new();
+        //the edges going back are drawn on the right of the states, each one right of the label of the previous one
+        let mut side = MARGIN + max_width + 20.0;
+        //height of the labels already stacked above each state
+        let mut stacked = vec![0.0; self.nodes.len()];
+        for edge in self.edges.iter() {
+            let (from, to) = (center[edge.from], center[edge.to]);
+            let (path, label, anchor) = if edge.from == edge.to {
+                let x = from.x + widths[edge.from] / 2.0;
+                (
+                    format!(
+                        "M {:.1} {:.1} C {:.1} {:.1}, {:.1} {:.1}, {:.1} {:.1}",
+                        x,
+                        from.y - 8.0,
+                        x + 40.0,
+                        from.y - 30.0,
+                        x + 40.0,
+                        from.y + 30.0,
+                        x,
+                        from.y + 8.0
+                    ),
+                    Point { x: x + 36.0, y: from.y },
+                    "start",
+                )
+            } else if forward(edge) {
+                let (y1, y2) = (from.y + NODE_HEIGHT / 2.0, to.y - NODE_HEIGHT / 2.0);
+                //the labels are stacked in the gap above the target, between the labels of the loops around it
+                let l = layer[edge.to];
+                let middle = to.y - loop_height[l] - (gaps[l] - loop_height[l - 1] - loop_height[l]) / 2.0;
+                let height = edge.labels.len() as f64 * LINE_HEIGHT;
+                let y = middle - incoming[edge.to] / 2.0 + stacked[edge.to] + height / 2.0;
+                stacked[edge.to] += height + LABEL_GAP;
+                (
+                    format!(
+                        "M {:.1} {:.1} C {:.1} {:.1}, {:.1} {:.1}, {:.1} {:.1}",
+                        from.x, y1, from.x, y, to.x, y, to.x, y2
+                    ),
+                    Point { x: to.x, y },
+                    "middle",
+                )
+            } else {
+                let (x1, x2) = (from.x + widths[edge.from] / 2.0, to.x + widths[edge.to] / 2.0);
+                //control points making the curve reach the column at its middle
+                let control = (8.0 * side - x1 - x2) / 6.0;
+                let label = Point {
+                    x: side + 4.0,
+                    y: (from.y + to.y) / 2.0,
+                };
+                side += 4.0 + label_width(&edge.labels) + 16.0;
+                (
+                    format!(
+                        "M {:.1} {:.1} C {:.1} {:.1}, {:.1} {:.1}, {:.1} {:.1}",
+                        x1, from.y, control, from.y, control, to.y, x2, to.y
+                    ),
+                    label,
+                    "start",
+                )
+            };
+            let (color, marker) = if edge.highlight {
+                ("red", "arrow-red")
+            } else {
+                ("black", "arrow")
+            };
+            body.push_str(&format!(
+                "<path d=\"{}\" fill=\"none\" stroke=\"{}\" marker-end=\"url(#{})\"/>\n",
+                path, color, marker
+            ));
+            let top = label.y - (edge.labels.len() as f64 - 1.0) * LINE_HEIGHT / 2.0;
+            body.push_str(&format!(
+                "<text class=\"label\" fill=\"{}\" text-anchor=\"{}\">",
+                color, anchor
+            ));
+            for (index, text) in edge.labels.iter().enumerate() {
+                body.push_str(&format!(
+                    "<tspan x=\"{:.1}\" y=\"{:.1}\">{}</tspan>",
+                    label.x,
+                    top + index as f64 * LINE_HEIGHT,
+                    html_escape(text)
+                ));
+            }
+            body.push_str("</text>\n");
+            let label_width = label_width(&edge.labels);
+            let left = if anchor == "middle" {
+                label.x - label_width / 2.0
+            } else {
+                label.x
+            };
+            bounds.include(Point {
+                x: left - MARGIN,
+                y: top - LINE_HEIGHT - MARGIN,
+            });
+            bounds.include(Point {
+                x: left + label_width + MARGIN,
+                y: top + edge.labels.len() as f64 * LINE_HEIGHT + MARGIN,
+            });
+        }
+        bounds.include(Point {
+            x: side + MARGIN,
+            y: 0.0,
+        });
+        for (node, (_, label)) in self.nodes.iter().enumerate() {
+            let Point { x, y } = center[node];
+            body.push_str(&format!(
+                "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" rx=\"15\"/>\n\
+                 <text class=\"state\" x=\"{:.1}\" y=\"{:.1}\">{}</text>\n",
+                x - widths[node] / 2.0,
+                y - NODE_HEIGHT / 2.0,
+                widths[node],
+                NODE_HEIGHT,
+                x,
+                y + 4.0,
+                html_escape(label)
+            ));
+        }
+        let (width, height) = (bounds.max.x - bounds.min.x, bounds.max.y - bounds.min.y);
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.0}\" height=\"{:.0}\" viewBox=\"{:.0} {:.0} {:.0} {:.0}\">\n{}</svg>\n",
+            width, height, bounds.min.x, bounds.min.y, width, height, body
+        )
+    }
+}
+
+/// Box holding every element of the drawing.
+struct Bounds {
+    min: Point,
+    max: Point,
+}
+
+impl Bounds {
+    fn include(&mut self, point: Point) {
+        self.min.x = self.min.x.min(point.x);
+        self.min.y = self.min.y.min(point.y);
+        self.max.x = self.max.x.max(point.x);
+        self.max.y = self.max.y.max(point.y);
+    }
+}
+
+fn label_width(labels: &[String]) -> f64 {
+    labels.iter().map(|l| l.chars().count()).max().unwrap_or(0) as f64 * LABEL_CHAR_WIDTH
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::dot;
+
+    use super::Diagram;
+
+    #[test]
+    fn layered_layout() {
+        let dot = dot::parse(
+            r#"digraph {
+            "0" -> "1" [label="hello/ack"];
+            "1" -> "2" [label="open/ack"];
+            "0" -> "2" [label="open/err"];
+            "2" -> "0" [label="close/ack"];
+            "2" -> "2" [label="read/data"];
+            "2" -> "2" [label="write/err"];
+            }"#,
+            "ce.dot",
+        )
+        .unwrap();
+        let diagram = Diagram::from_dot(&dot, |_, _, label| label == "open/err");
+        assert_eq!(diagram.edges.len(), 5);
+        let back = diagram.back_edges();
+        assert_eq!(back, vec![false, false, false, true, false]);
+        let layer = diagram.layers(&back);
+        assert_eq!(layer, vec![0, 1, 2]);
+
+        let svg = diagram.to_svg();
+        assert_eq!(svg.matches("<rect").count(), 3);
+        assert_eq!(svg.matches("<path").count(), 5);
+        assert_eq!(svg.matches("stroke=\"red\"").count(), 1);
+        //the two labels of the loop are written on two lines
+        assert!(svg.contains(">read/data</tspan><tspan"));
+    }
+}
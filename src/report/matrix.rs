@@ -0,0 +1,246 @@
+//! Verdict matrix of a run: one row per graph, one column per rule, the number of violations in each cell.
+//! It is written as CSV, Markdown and HTML to compare the implementations at a glance.
+use std::path::Path;
+
+use indexmap::IndexMap;
+
+use super::{html_escape, Report, Status};
+use crate::error::{Error, Result};
+
+/// Verdict of one rule on one graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub status: Status,
+    pub violations: usize,
+}
+
+impl std::fmt::Display for Cell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.status {
+            Status::Pass => write!(f, "0"),
+            //some rules fail without violating transition, such as a missing expected cycle
+            Status::Fail if self.violations == 0 => write!(f, "fail"),
+            Status::Fail => write!(f, "{}", self.violations),
+            Status::Error => write!(f, "error"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MatrixRow {
+    pub graph: String,
+    /// implementations of the graph, empty when they are not known
+    pub implementations: Vec<String>,
+    /// one cell per rule of the matrix, None when the rule was not checked on the graph
+    pub cells: Vec<Option<Cell>>,
+}
+
+#[derive(Debug)]
+pub struct Matrix {
+    pub rules: Vec<String>,
+    pub rows: Vec<MatrixRow>,
+}
+
+impl Matrix {
+    /// Matrix of the entries of a report, the graphs and rules are kept in the order of the report.
+    pub fn new(report: &Report) -> Self {
+        let mut rules: IndexMap<&str, ()> = IndexMap::new();
+        let mut graphs: IndexMap<&str, (&Vec<String>, IndexMap<&str, Cell>)> = IndexMap::new();
+        for entry in report.entries.iter() {
+            rules.insert(&entry.rule, ());
+            let (_, cells) = graphs
+                .entry(&entry.graph)
+                .or_insert_with(|| (&entry.implementations, IndexMap::new()));
+            cells.insert(
+                &entry.rule,
+                Cell {
+                    status: entry.status,
+                    violations: entry.violations,
+                },
+            );
+        }
+        let rows = graphs
+            .into_iter()
+            .map(|(graph, (implementations, cells))| MatrixRow {
+                graph: graph.to_string(),
+                implementations: implementations.clone(),
+                cells: rules.keys().map(|rule| cells.get(rule).copied()).collect(),
+            })
+            .collect();
+        Matrix {
+            rules: rules.keys().map(|rule| rule.to_string()).collect(),
+            rows,
+        }
+    }
+
+    fn cell_text(cell: &Option<Cell>) -> String {
+        cell.map(|c| c.to_string()).unwrap_or_default()
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        let header: Vec<String> = ["graph", "implementations"]
+            .into_iter()
+            .map(String::from)
+            .chain(self.rules.iter().cloned())
+            .collect();
+        csv.push_str(&csv_line(&header));
+        for row in self.rows.iter() {
+            let fields: Vec<String> = [row.graph.clone(), row.implementations.join(", ")]
+                .into_iter()
+                .chain(row.cells.iter().map(Matrix::cell_text))
+                .collect();
+            csv.push_str(&csv_line(&fields));
+        }
+        csv
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+        markdown.push_str("| graph |");
+        for rule in self.rules.iter() {
+            markdown.push_str(&format!(" {} |", markdown_escape(rule)));
+        }
+        markdown.push_str("\n|---|");
+        markdown.push_str(&"---:|".repeat(self.rules.len()));
+        markdown.push('\n');
+        for row in self.rows.iter() {
+            markdown.push_str(&format!("| {} |", markdown_escape(&row_label(row))));
+            for cell in row.cells.iter() {
+                markdown.push_str(&format!(" {} |", Matrix::cell_text(cell)));
+            }
+            markdown.push('\n');
+        }
+        markdown
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut html = String::from(concat!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Verdict matrix</title>\n",
+            "<style>\n",
+            "table { border-collapse: collapse; font-family: sans-serif; }\n",
+            "th, td { border: 1px solid #999; padding: 2px 6px; }\n",
+            "td { text-align: right; }\n",
+            "td.graph { text-align: left; }\n",
+            ".pass { background: #cfc; }\n.fail { background: #fcc; }\n.error { background: #ccc; }\n",
+            "</style>\n</head>\n<body>\n<table>\n<tr><th>graph</th>",
+        ));
+        for rule in self.rules.iter() {
+            html.push_str(&format!("<th>{}</th>", html_escape(rule)));
+        }
+        html.push_str("</tr>\n");
+        for row in self.rows.iter() {
+            html.push_str(&format!(
+                "<tr><td class=\"graph\" title=\"{}\">{}</td>",
+                html_escape(&row.graph),
+                html_escape(&row_label(row))
+            ));
+            for cell in row.cells.iter() {
+                match cell {
+                    Some(cell) => html.push_str(&format!("<td class=\"{}\">{}</td>", cell.status.as_str(), cell)),
+                    None => html.push_str("<td></td>"),
+                }
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</table>\n</body>\n</html>\n");
+        html
+    }
+
+    /// Write matrix.csv, matrix.md and matrix.html in the output folder.
+    pub fn write(&self, output_folder: &Path) -> Result<()> {
+        for (name, content) in [
+            ("matrix.csv", self.to_csv()),
+            ("matrix.md", self.to_markdown()),
+            ("matrix.html", self.to_html()),
+        ] {
+            let path = output_folder.join(name);
+            std::fs::write(&path, content).map_err(|e| Error::io(path, e))?;
+        }
+        Ok(())
+    }
+}
+
+//implementations of the graph when they are known, else the graph
+fn row_label(row: &MatrixRow) -> String {
+    if row.implementations.is_empty() {
+        row.graph.clone()
+    } else {
+        row.implementations.join(", ")
+    }
+}
+
+fn csv_line(fields: &[String]) -> String {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        })
+        .collect();
+    format!("{}\n", fields.join(","))
+}
+
+fn markdown_escape(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use crate::{
+        error::Error,
+        graph::nodeid::NodeId,
+        report::Report,
+        verifier::result::{GraphResult, RuleResult, Transition},
+    };
+
+    use super::Matrix;
+
+    #[test]
+    fn verdict_matrix() {
+        let violations = vec![
+            Transition::new(&NodeId::new("0"), &NodeId::new("1"), "a/b"),
+            Transition::new(&NodeId::new("1"), &NodeId::new("1"), "a/c"),
+        ];
+        let first = GraphResult {
+            graph: "first.dot".to_string(),
+            implementations: vec!["open62541 v1.1.3".to_string(), "open62541 v1.1.4".to_string()],
+            rules: vec![
+                RuleResult::from_violations("hello", violations),
+                RuleResult::from_violations("close", Vec::new()),
+            ],
+            errors: Vec::new(),
+            warnings: Vec::new(),
+        };
+        let second = GraphResult {
+            graph: "second.dot".to_string(),
+            implementations: Vec::new(),
+            rules: vec![RuleResult::from_witness("cycle", None)],
+            errors: vec![Error::io("result/hello", io::Error::other("denied")).rule("hello", "second.dot")],
+            warnings: Vec::new(),
+        };
+        let matrix = Matrix::new(&Report::new(&[first, second], &[]));
+        assert_eq!(matrix.rules, vec!["hello", "close", "cycle"]);
+        assert_eq!(
+            matrix.to_csv(),
+            "graph,implementations,hello,close,cycle\n\
+             first.dot,\"open62541 v1.1.3, open62541 v1.1.4\",2,0,\n\
+             second.dot,,error,,fail\n"
+        );
+        assert_eq!(
+            matrix.to_markdown(),
+            "| graph | hello | close | cycle |\n\
+             |---|---:|---:|---:|\n\
+             | open62541 v1.1.3, open62541 v1.1.4 | 2 | 0 |  |\n\
+             | second.dot | error |  | fail |\n"
+        );
+        let html = matrix.to_html();
+        assert!(html.contains("<td class=\"fail\">2</td><td class=\"pass\">0</td><td></td>"));
+        assert!(html.contains("<td class=\"graph\" title=\"second.dot\">second.dot</td><td class=\"error\">error</td>"));
+    }
+}
@@ -0,0 +1,256 @@
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::BufWriter,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use crate::{
+    error::{Error, Result},
+    verifier::{
+        result::{GraphResult, RuleResult, Transition},
+        rules::Severity,
+        trace::Trace,
+    },
+};
+
+mod html;
+mod layout;
+pub mod matrix;
+pub mod outcome;
+
+/// Verdict of one rule on one graph in the report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Pass,
+    Fail,
+    /// the rule could not be applied on the graph
+    Error,
+}
+
+impl Status {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Status::Pass => "pass",
+            Status::Fail => "fail",
+            Status::Error => "error",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportEntry {
+    pub graph: String,
+    /// implementations sharing the Mealy machine of the graph
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub implementations: Vec<String>,
+    pub rule: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity: Option<Severity>,
+    pub status: Status,
+    pub violations: usize,
+    pub edges: Vec<Transition>,
+    pub counterexamples: Vec<PathBuf>,
+    /// shortest traces from the initial state to the violations
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub traces: Vec<Trace>,
+    /// transitions showing the rule holds, for the rules asserting the existence of a behaviour
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub witness: Vec<Transition>,
+    /// states violating the rule, for the rules on states
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub states: Vec<String>,
+    /// patterns of the rule matching no symbol of the graph and premises never occurring
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ReportEntry {
+    fn from_result(graph: &GraphResult, result: &RuleResult) -> Self {
+        ReportEntry {
+            graph: graph.graph.clone(),
+            implementations: graph.implementations.clone(),
+            rule: result.rule.clone(),
+            severity: result.severity,
+            status: if result.holds { Status::Pass } else { Status::Fail },
+            violations: result.violations.len(),
+            edges: result.violations.clone(),
+            counterexamples: result.counterexamples.clone(),
+            traces: result.traces.clone(),
+            witness: result.witness.clone(),
+            states: result.states.clone(),
+            warnings: result.warnings.clone(),
+            error: None,
+        }
+    }
+
+    fn from_error(graph: &GraphResult, rule: &str, error: &Error) -> Self {
+        ReportEntry {
+            graph: graph.graph.clone(),
+            implementations: graph.implementations.clone(),
+            rule: rule.to_string(),
+            severity: None,
+            status: Status::Error,
+            violations: 0,
+            edges: Vec::new(),
+            counterexamples: Vec::new(),
+            traces: Vec::new(),
+            witness: Vec::new(),
+            states: Vec::new(),
+            warnings: Vec::new(),
+            error: Some(error.to_string()),
+        }
+    }
+
+    /// Name of the graph for a reader: its implementations when they are known.
+    pub fn label(&self) -> String {
+        if self.implementations.is_empty() {
+            self.graph.clone()
+        } else {
+            self.implementations.join(", ")
+        }
+    }
+}
+
+/// Machine readable summary of a run, one entry per graph and rule.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub entries: Vec<ReportEntry>,
+    /// problems that are not tied to a graph and a rule, such as a rule file that could not be parsed
+    pub errors: Vec<String>,
+    /// problems of the graphs found when they were loaded, which did not prevent checking them
+    pub warnings: Vec<String>,
+}
+
+impl Report {
+    pub fn new(results: &[GraphResult], errors: &[Error]) -> Self {
+        let mut entries = Vec::new();
+        let mut other_errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        let mut warnings = Vec::new();
+        for graph_result in results {
+            for warning in graph_result.warnings.iter() {
+                warnings.push(format!("graph {}: {}", graph_result.graph, warning));
+            }
+            for rule_result in graph_result.rules.iter() {
+                entries.push(ReportEntry::from_result(graph_result, rule_result));
+            }
+            for error in graph_result.errors.iter() {
+                match error {
+                    Error::Rule { rule, source, .. } => {
+                        entries.push(ReportEntry::from_error(graph_result, rule, source))
+                    }
+                    _ => other_errors.push(error.to_string()),
+                }
+            }
+        }
+        Report {
+            entries,
+            errors: other_errors,
+            warnings,
+        }
+    }
+
+    /// One line summary: number of graphs and verdicts by status.
+    pub fn summary(&self) -> String {
+        let graphs: HashSet<&str> = self.entries.iter().map(|e| e.graph.as_str()).collect();
+        let count = |status: Status| self.entries.iter().filter(|e| e.status == status).count();
+        format!(
+            "{} graphs, {} verdicts: {} pass, {} fail, {} error",
+            graphs.len(),
+            self.entries.len(),
+            count(Status::Pass),
+            count(Status::Fail),
+            count(Status::Error) + self.errors.len()
+        )
+    }
+
+    /// One line per rule that does not hold, naming the implementations when they are known.
+    pub fn failures(&self) -> Vec<String> {
+        self.entries
+            .iter()
+            .filter_map(|e| match e.status {
+                Status::Pass => None,
+                Status::Fail => Some(format!("{} fails {}", e.label(), e.rule)),
+                Status::Error => Some(format!("{} could not be checked against {}", e.label(), e.rule)),
+            })
+            .collect()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("the report only holds serializable values")
+    }
+
+    pub fn write_json(&self, path: &Path) -> Result<()> {
+        let file = File::create(path).map_err(|e| Error::io(path, e))?;
+        serde_json::to_writer_pretty(BufWriter::new(file), self).map_err(|e| Error::io(path, e.into()))
+    }
+}
+
+pub(crate) fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io, path::PathBuf};
+
+    use crate::{
+        error::Error,
+        graph::nodeid::NodeId,
+        verifier::result::{GraphResult, RuleResult, Transition},
+    };
+
+    use super::{Report, Status};
+
+    #[test]
+    fn json_report() {
+        let violation = Transition::new(&NodeId::new("0"), &NodeId::new("1"), "a/b");
+        let mut failing = RuleResult::from_violations("failing", vec![violation]);
+        failing.add_counterexample(Some(PathBuf::from("result/graph/failing/ce.dot")));
+        let graph_result = GraphResult {
+            graph: "graph".to_string(),
+            implementations: vec!["open62541 v1.1.3".to_string(), "open62541 v1.1.4".to_string()],
+            rules: vec![RuleResult::from_violations("passing", Vec::new()), failing],
+            errors: vec![Error::io("result/graph/broken", io::Error::other("denied")).rule("broken", "graph")],
+            warnings: vec!["state 1 has no transition for b".to_string()],
+        };
+        let report = Report::new(&[graph_result], &[]);
+        let status: Vec<(&str, Status)> = report.entries.iter().map(|e| (e.rule.as_str(), e.status)).collect();
+        assert_eq!(
+            status,
+            vec![
+                ("passing", Status::Pass),
+                ("failing", Status::Fail),
+                ("broken", Status::Error)
+            ]
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        let failing = &json["entries"][1];
+        assert_eq!(failing["status"], "fail");
+        assert_eq!(failing["violations"], 1);
+        assert_eq!(failing["edges"][0]["source"], "0");
+        assert_eq!(failing["edges"][0]["destination"], "1");
+        assert_eq!(failing["edges"][0]["label"], "a/b");
+        assert_eq!(failing["counterexamples"][0], "result/graph/failing/ce.dot");
+        assert_eq!(json["entries"][2]["error"], "result/graph/broken: denied");
+        assert_eq!(json["warnings"][0], "graph graph: state 1 has no transition for b");
+        assert_eq!(report.summary(), "1 graphs, 3 verdicts: 1 pass, 1 fail, 1 error");
+        assert_eq!(failing["implementations"][1], "open62541 v1.1.4");
+        assert_eq!(
+            report.failures(),
+            vec![
+                "open62541 v1.1.3, open62541 v1.1.4 fails failing",
+                "open62541 v1.1.3, open62541 v1.1.4 could not be checked against broken"
+            ]
+        );
+    }
+}
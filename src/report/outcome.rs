@@ -0,0 +1,154 @@
+//! Outcome of a run, turned into the exit code of the program so a CI job can gate on the verdicts.
+use std::str::FromStr;
+
+use crate::{
+    error::{Error, Result},
+    verifier::{
+        result::{GraphResult, RuleResult},
+        rules::Severity,
+    },
+};
+
+/// The outcomes are ordered by gravity, the outcome of a run is the gravest one met.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Outcome {
+    /// every rule counted holds
+    Holds,
+    /// at least one rule counted does not hold
+    Violation,
+    /// a rule, model or configuration file is malformed
+    ParseError,
+    /// a file could not be read or written
+    IoError,
+}
+
+impl Outcome {
+    pub fn code(self) -> u8 {
+        match self {
+            Outcome::Holds => 0,
+            Outcome::Violation => 1,
+            Outcome::ParseError => 2,
+            Outcome::IoError => 3,
+        }
+    }
+
+    pub fn from_error(error: &Error) -> Self {
+        match error {
+            Error::Io { .. } => Outcome::IoError,
+            Error::Parse { .. } | Error::Graph { .. } | Error::Config { .. } => Outcome::ParseError,
+            Error::Rule { source, .. } => Outcome::from_error(source),
+        }
+    }
+
+    /// Outcome of the verdicts and of the errors of a run, only the rules selected by `fail_on` count as violations.
+    pub fn new(results: &[GraphResult], errors: &[Error], fail_on: &FailOn) -> Self {
+        let violation = results
+            .iter()
+            .flat_map(|r| r.rules.iter())
+            .any(|r| !r.holds && fail_on.counts(r));
+        let outcome = if violation { Outcome::Violation } else { Outcome::Holds };
+        errors
+            .iter()
+            .chain(results.iter().flat_map(|r| r.errors.iter()))
+            .map(Outcome::from_error)
+            .fold(outcome, Outcome::max)
+    }
+}
+
+/// Rules whose violations make the run fail, given by name or by severity. Every rule counts if none is given.
+#[derive(Debug, Default)]
+pub struct FailOn {
+    rules: Vec<String>,
+    severities: Vec<Severity>,
+}
+
+impl FailOn {
+    /// The values naming a severity select the rules of this severity, the other ones are rule names.
+    pub fn new(values: &[String]) -> Self {
+        let mut fail_on = FailOn::default();
+        for value in values {
+            match Severity::from_str(value) {
+                Ok(severity) => fail_on.severities.push(severity),
+                Err(_) => fail_on.rules.push(value.clone()),
+            }
+        }
+        fail_on
+    }
+
+    /// Check that every value is a severity or the name of one of the rules, a typo would select no rule.
+    pub fn check<'a>(&self, rules: impl IntoIterator<Item = &'a str>) -> Result<()> {
+        let rules: Vec<&str> = rules.into_iter().collect();
+        match self.rules.iter().find(|rule| !rules.contains(&rule.as_str())) {
+            Some(unknown) => Err(Error::config(
+                "--fail-on",
+                format!(
+                    "unknown rule or severity {}, the severities are low, medium and high",
+                    unknown
+                ),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    pub fn counts(&self, result: &RuleResult) -> bool {
+        (self.rules.is_empty() && self.severities.is_empty())
+            || self.rules.contains(&result.rule)
+            || result.severity.is_some_and(|s| self.severities.contains(&s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use crate::{
+        error::Error,
+        graph::nodeid::NodeId,
+        verifier::{
+            result::{GraphResult, RuleResult, Transition},
+            rules::Severity,
+        },
+    };
+
+    use super::{FailOn, Outcome};
+
+    #[test]
+    fn outcome() {
+        let violation = Transition::new(&NodeId::new("0"), &NodeId::new("1"), "a/b");
+        let mut failing = RuleResult::from_violations("failing", vec![violation]);
+        failing.severity = Some(Severity::Low);
+        let results = vec![GraphResult {
+            graph: "graph".to_string(),
+            implementations: Vec::new(),
+            rules: vec![RuleResult::from_violations("passing", Vec::new()), failing],
+            errors: Vec::new(),
+            warnings: Vec::new(),
+        }];
+        let fail_on = |values: &[&str]| FailOn::new(&values.iter().map(|v| v.to_string()).collect::<Vec<_>>());
+        assert_eq!(Outcome::new(&results, &[], &FailOn::default()), Outcome::Violation);
+        assert_eq!(
+            Outcome::new(&results, &[], &fail_on(&["passing", "high"])),
+            Outcome::Holds
+        );
+        assert_eq!(Outcome::new(&results, &[], &fail_on(&["low"])), Outcome::Violation);
+        assert_eq!(Outcome::new(&results, &[], &fail_on(&["failing"])), Outcome::Violation);
+
+        let rules = ["passing", "failing"];
+        assert!(fail_on(&["failing", "high"]).check(rules).is_ok());
+        let error = fail_on(&["hihg"]).check(rules).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "--fail-on: unknown rule or severity hihg, the severities are low, medium and high"
+        );
+        assert_eq!(Outcome::from_error(&error), Outcome::ParseError);
+
+        let parse_error = Error::parse(1, "no rule").locate("rules", 1);
+        assert_eq!(
+            Outcome::new(&results, &[parse_error], &fail_on(&["passing"])),
+            Outcome::ParseError
+        );
+        let io_error = Error::io("result", io::Error::other("denied")).rule("failing", "graph");
+        assert_eq!(Outcome::new(&[], &[io_error], &FailOn::default()), Outcome::IoError);
+        assert_eq!(Outcome::IoError.code(), 3);
+    }
+}
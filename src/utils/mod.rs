@@ -2,21 +2,19 @@ use std::{collections::HashSet, hash::Hash};
 
 use indexmap::IndexSet;
 
-pub(crate) mod output;
-pub(crate) mod reader;
+pub mod output;
+pub mod reader;
 pub(crate) fn unquote(s: &str) -> &str {
-    let start: usize;
-    let end: usize;
     let mut index = s.find("\"");
-    match index {
-        Some(s) => start = s + 1,
+    let start: usize = match index {
+        Some(s) => s + 1,
         None => return s,
-    }
+    };
     index = s[start..].find("\"");
-    match index {
-        Some(s) => end = s + start,
+    let end: usize = match index {
+        Some(s) => s + start,
         None => return s,
-    }
+    };
     &s[start..end]
 }
 
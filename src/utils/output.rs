@@ -1,23 +1,41 @@
 use std::collections::HashSet;
 use std::fs::{self, File};
-use std::io::Write;
-use std::path::PathBuf;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 
+use indexmap::IndexMap;
+
+use crate::error::{Error, Result};
+use crate::graph::dot;
 use crate::graph::edgemap::EdgeMap;
 use crate::graph::multi_edge::MultiEdge;
 use crate::graph::nodeid::NodeId;
 use crate::graph::Graph;
+use crate::verifier::result::{RuleResult, Transition};
+use crate::verifier::trace::Trace;
 
 pub(crate) fn write_files(
     graph: &Graph,
     nodes: HashSet<NodeId>,
     edges: HashSet<(&MultiEdge, Vec<usize>)>,
     output_folder: &mut PathBuf,
-) -> std::io::Result<()> {
+) -> std::io::Result<Option<PathBuf>> {
+    write_files_named(graph, nodes, edges, output_folder, "ce.dot")
+}
+
+///Same as write_files in the file `file_name` of the output folder.
+pub(crate) fn write_files_named(
+    graph: &Graph,
+    nodes: HashSet<NodeId>,
+    edges: HashSet<(&MultiEdge, Vec<usize>)>,
+    output_folder: &mut PathBuf,
+    file_name: &str,
+) -> std::io::Result<Option<PathBuf>> {
     if nodes.is_empty() && edges.is_empty() {
-        return Ok(());
+        return Ok(None);
     }
-    output_folder.push("ce.dot");
+    output_folder.push(file_name);
     let mut output = String::from("digraph \"Automata\" { \n");
     let mut file = match fs::OpenOptions::new().write(true).truncate(true).open(&output_folder) {
         Ok(f) => f,
@@ -36,8 +54,38 @@ pub(crate) fn write_files(
     }
     output.push_str("}\n");
     file.write_all(output.as_bytes())?;
+    let path = output_folder.clone();
     output_folder.pop();
-    Ok(())
+    Ok(Some(path))
+}
+
+///Same as write_files_named for the labels of the graph taken by the transitions.
+pub(crate) fn write_transitions(
+    graph: &Graph,
+    transitions: &[Transition],
+    output_folder: &mut PathBuf,
+    file_name: &str,
+) -> std::io::Result<Option<PathBuf>> {
+    let mut nodes = HashSet::new();
+    let mut edges: IndexMap<&MultiEdge, Vec<usize>> = IndexMap::new();
+    for transition in transitions {
+        let source = NodeId::from_dot(&transition.source);
+        let edge = graph
+            .get_outgoing_edges(&source)
+            .and_then(|edges| edges.get(&NodeId::from_dot(&transition.destination)));
+        if let Some(edge) = edge {
+            let labels = edge.get_labels();
+            if let Some(index) = labels.iter().position(|label| **label == *transition.label) {
+                nodes.insert(source);
+                nodes.insert(edge.get_dest().clone());
+                let indexes = edges.entry(edge).or_default();
+                if !indexes.contains(&index) {
+                    indexes.push(index);
+                }
+            }
+        }
+    }
+    write_files_named(graph, nodes, edges.into_iter().collect(), output_folder, file_name)
 }
 
 pub(crate) fn write_files_edge_map(
@@ -45,9 +93,9 @@ pub(crate) fn write_files_edge_map(
     nodes: HashSet<NodeId>,
     edges: EdgeMap,
     output_folder: &mut PathBuf,
-) -> std::io::Result<()> {
+) -> std::io::Result<Option<PathBuf>> {
     if nodes.is_empty() && edges.is_empty() {
-        return Ok(());
+        return Ok(None);
     }
     output_folder.push("ce.dot");
     let mut output = String::from("digraph \"Automata\" { \n");
@@ -63,6 +111,265 @@ pub(crate) fn write_files_edge_map(
     output.push_str(&edges.to_string());
     output.push_str("}\n");
     file.write_all(output.as_bytes())?;
+    let path = output_folder.clone();
+    output_folder.pop();
+    Ok(Some(path))
+}
+
+///Write the traces leading to the violations as text and json, one trace after the other.
+pub(crate) fn write_traces(traces: &[Trace], output_folder: &mut PathBuf) -> std::io::Result<Vec<PathBuf>> {
+    if traces.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut paths = Vec::with_capacity(2);
+    let text: Vec<String> = traces.iter().map(|trace| trace.to_string()).collect();
+    output_folder.push("traces.txt");
+    fs::write(&output_folder, text.join("\n"))?;
+    paths.push(output_folder.clone());
+    output_folder.pop();
+    output_folder.push("traces.json");
+    let json = serde_json::to_string_pretty(traces).map_err(std::io::Error::from)?;
+    fs::write(&output_folder, json)?;
+    paths.push(output_folder.clone());
+    output_folder.pop();
+    Ok(paths)
+}
+
+///Write the states violating a rule in `states.txt`, one per line.
+pub(crate) fn write_states(states: &[String], output_folder: &mut PathBuf) -> std::io::Result<Option<PathBuf>> {
+    if states.is_empty() {
+        return Ok(None);
+    }
+    output_folder.push("states.txt");
+    let mut text = states.join("\n");
+    text.push('\n');
+    fs::write(&output_folder, text)?;
+    let path = output_folder.clone();
+    output_folder.pop();
+    Ok(Some(path))
+}
+
+///Write a text in the file `file_name` of the output folder.
+pub(crate) fn write_text(file_name: &str, text: &str, output_folder: &mut PathBuf) -> std::io::Result<PathBuf> {
+    output_folder.push(file_name);
+    fs::write(&output_folder, text)?;
+    let path = output_folder.clone();
+    output_folder.pop();
+    Ok(path)
+}
+
+///Write the complete graph in `annotated.dot` of the output folder: the violating transitions of the rule in red,
+/// its witness in green and the states they go through filled, the violating states of the rules on states in red,
+/// with a legend naming the rule.
+pub(crate) fn write_annotated(
+    graph: &Graph,
+    result: &RuleResult,
+    output_folder: &mut PathBuf,
+) -> std::io::Result<PathBuf> {
+    let key = |t: &Transition| (t.source.clone(), t.destination.clone(), t.label.clone());
+    let violations: HashSet<_> = result.violations.iter().map(key).collect();
+    let witness: HashSet<_> = result.witness.iter().map(key).collect();
+    let legend = if result.holds && witness.is_empty() {
+        format!("rule {}: holds", result.rule)
+    } else if result.holds {
+        format!("rule {}: holds, witness in green", result.rule)
+    } else if !result.states.is_empty() {
+        format!(
+            "rule {}: does not hold, {} violating states in red",
+            result.rule,
+            result.states.len()
+        )
+    } else if violations.is_empty() {
+        format!("rule {}: does not hold", result.rule)
+    } else {
+        format!(
+            "rule {}: does not hold, {} violating transitions in red",
+            result.rule,
+            violations.len()
+        )
+    };
+    let mut output = String::from("digraph \"Automata\" { \n");
+    output.push_str(&format!("\t label=\"{}\";\n\t labelloc=t;\n", dot::escape(&legend)));
+    for node_id in graph.iter_node_id() {
+        //the id comes from the same graph hence it must be there
+        output.push_str(&graph.get_node(node_id).unwrap().to_string());
+    }
+    //states of the violations or of the witness, true for the states of a violation
+    let mut involved: IndexMap<String, bool> = IndexMap::new();
+    for edge in graph.iter_edges() {
+        for (index, label) in edge.get_label_iterator().enumerate() {
+            let transition = (
+                edge.get_source().to_string(),
+                edge.get_dest().to_string(),
+                label.to_string(),
+            );
+            let violation = violations.contains(&transition);
+            if violation {
+                output.push_str(&edge.to_string_label_with(index, "color=red, fontcolor=red, penwidth=2"));
+            } else if witness.contains(&transition) {
+                output.push_str(&edge.to_string_label_with(index, "color=green, fontcolor=green, penwidth=2"));
+            } else {
+                output.push_str(&edge.to_string_labels(vec![index].into_iter()).concat());
+                continue;
+            }
+            for state in [transition.0, transition.1] {
+                *involved.entry(state).or_default() |= violation;
+            }
+        }
+    }
+    for state in result.states.iter() {
+        involved.insert(state.clone(), true);
+    }
+    //a later statement on a node adds to its attributes
+    for (state, violation) in involved {
+        let color = if violation { "#ffcccc" } else { "#ccffcc" };
+        output.push_str(&format!(
+            "\t \"{}\" [style=filled, fillcolor=\"{}\"];\n",
+            dot::escape(&state),
+            color
+        ));
+    }
+    output.push_str("}\n");
+    output_folder.push("annotated.dot");
+    fs::write(&output_folder, output)?;
+    let path = output_folder.clone();
     output_folder.pop();
-    Ok(())
-}
\ No newline at end of file
+    Ok(path)
+}
+
+///Create a folder named after the current time, `result_2024-07-30_14-03-22` (UTC), with a number appended if it already exists.
+/// The folder is created before being returned, so two runs started in the same second do not share it.
+pub fn new_output_folder() -> Result<PathBuf> {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let base = format!("result_{}", timestamp(seconds));
+    let mut output_folder = PathBuf::from(&base);
+    let mut index = 1;
+    loop {
+        match fs::create_dir(&output_folder) {
+            Ok(()) => return Ok(output_folder),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                index += 1;
+                output_folder = PathBuf::from(format!("{}_{}", base, index));
+            }
+            Err(e) => return Err(Error::io(output_folder, e)),
+        }
+    }
+}
+
+//date and time of a number of seconds since the epoch, the date is converted from the days as in the proleptic Gregorian calendar
+fn timestamp(seconds: u64) -> String {
+    let days = (seconds / 86400) as i64;
+    let time = seconds % 86400;
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!(
+        "{:04}-{:02}-{:02}_{:02}-{:02}-{:02}",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+///Refuse to write in an output folder already holding results, unless `force` is set.
+pub fn check_output_folder(output_folder: &Path, force: bool) -> Result<()> {
+    if force || !output_folder.exists() {
+        return Ok(());
+    }
+    let is_empty = fs::read_dir(output_folder)
+        .map(|mut entries| entries.next().is_none())
+        .unwrap_or(false);
+    if is_empty {
+        return Ok(());
+    }
+    Err(Error::io(
+        output_folder,
+        std::io::Error::new(
+            ErrorKind::AlreadyExists,
+            "the output folder already holds results, use --force to overwrite them",
+        ),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        graph::{dot, nodeid::NodeId, Graph},
+        verifier::result::{RuleResult, Transition},
+    };
+
+    use super::{check_output_folder, timestamp, write_annotated};
+
+    #[test]
+    fn output_folder() {
+        assert_eq!(timestamp(0), "1970-01-01_00-00-00");
+        assert_eq!(timestamp(1722348202), "2024-07-30_14-03-22");
+        assert_eq!(timestamp(951782400), "2000-02-29_00-00-00");
+
+        let folder = std::env::temp_dir().join(format!("mealy_verifier_output_{}", std::process::id()));
+        std::fs::create_dir_all(&folder).unwrap();
+        assert!(check_output_folder(&folder, false).is_ok());
+        std::fs::write(folder.join("report.json"), "{}").unwrap();
+        let error = check_output_folder(&folder, false).unwrap_err();
+        assert!(error.to_string().ends_with("use --force to overwrite them"));
+        assert!(check_output_folder(&folder, true).is_ok());
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn annotated_graph() {
+        let graph = Graph::new(
+            r#"digraph "Automata" {
+            "0" [shape=ellipse];
+            "1" [shape=ellipse];
+            "0" -> "1" [label="hello/ack"];
+            "1" -> "1" [label="read/data"];
+            "1" -> "0" [label="close/ack"];
+            }"#,
+            false,
+        );
+        let violation = Transition::new(&NodeId::new("1"), &NodeId::new("1"), "read/data");
+        let result = RuleResult::from_violations("no_read", vec![violation]);
+        let mut folder = std::env::temp_dir().join(format!("mealy_verifier_annotated_{}", std::process::id()));
+        std::fs::create_dir_all(&folder).unwrap();
+        let path = write_annotated(&graph, &result, &mut folder).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_dir_all(&folder).unwrap();
+        assert!(content.contains("label=\"rule no_read: does not hold, 1 violating transitions in red\";"));
+        assert!(content.contains("\"1\"->\"1\" [label=\"read/data\"] [color=red, fontcolor=red, penwidth=2];"));
+        assert!(content.contains("\"0\"->\"1\" [label=\"hello/ack\"];"));
+        assert!(content.contains("\"1\" [style=filled, fillcolor=\"#ffcccc\"];"));
+        assert!(!content.contains("\"0\" [style=filled"));
+        //the annotated graph is a graph with the same transitions
+        let annotated = Graph::new(&content, false);
+        assert_eq!(annotated.iter_edges().len(), graph.iter_edges().len());
+
+        //quotes and backslashes in the state ids are escaped
+        let graph = Graph::new(r#"digraph { "q\"0\\" -> "q\"0\\" [label="read/data"] }"#, false);
+        let violation = Transition::new(&NodeId::new("q\"0\\"), &NodeId::new("q\"0\\"), "read/data");
+        let result = RuleResult::from_violations("no_read", vec![violation]);
+        std::fs::create_dir_all(&folder).unwrap();
+        let path = write_annotated(&graph, &result, &mut folder).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_dir_all(&folder).unwrap();
+        assert!(content.contains(r##""q\"0\\" [style=filled, fillcolor="#ffcccc"];"##));
+        let annotated = dot::parse(&content, "annotated.dot").unwrap();
+        assert_eq!(annotated.nodes.keys().collect::<Vec<_>>(), vec!["q\"0\\"]);
+    }
+}
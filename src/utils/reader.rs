@@ -4,19 +4,26 @@ use std::{
     io::{self, prelude::*},
 };
 
-pub(crate) trait Reader {
+pub trait Reader {
     fn read_line(&mut self) -> Option<&mut String>;
+    ///Number of the last line read, starting at 1.
+    fn line_number(&self) -> usize;
 }
 pub struct ReaderFile {
     reader: io::BufReader<File>,
     buffer: String,
+    line_number: usize,
 }
 impl ReaderFile {
     pub fn open(path: impl AsRef<std::path::Path>) -> io::Result<Self> {
         let file = File::open(path)?;
         let reader = io::BufReader::new(file);
         let buffer = String::with_capacity(1024);
-        Ok(Self { reader, buffer })
+        Ok(Self {
+            reader,
+            buffer,
+            line_number: 0,
+        })
     }
 }
 impl Reader for ReaderFile {
@@ -25,12 +32,17 @@ impl Reader for ReaderFile {
 
         let res = self.reader.read_line(&mut self.buffer);
         match res {
-            Ok(0) | Err(_) => {
-                return None;
+            Ok(0) | Err(_) => None,
+            Ok(_) => {
+                self.line_number += 1;
+                Some(&mut self.buffer)
             }
-            Ok(_) => Some(&mut self.buffer),
         }
     }
+
+    fn line_number(&self) -> usize {
+        self.line_number
+    }
 }
 #[cfg(test)]
 pub(crate) mod test_reader {
@@ -51,14 +63,6 @@ pub(crate) mod test_reader {
                 index: 0,
             })
         }
-        pub fn read_line(&mut self) -> Option<&mut String> {
-            if self.index >= self.lines.len() {
-                return None;
-            }
-            let to_return = &mut self.lines[self.index];
-            self.index += 1;
-            return Some(to_return);
-        }
     }
 
     impl Reader for TestReader {
@@ -68,7 +72,11 @@ pub(crate) mod test_reader {
             }
             let to_return = &mut self.lines[self.index];
             self.index += 1;
-            return Some(to_return);
+            Some(to_return)
+        }
+
+        fn line_number(&self) -> usize {
+            self.index
         }
     }
 }
@@ -0,0 +1,281 @@
+//! Generalized Büchi automaton accepting the words satisfying a formula in negative normal form,
+//! built by the tableau of Gerth, Peled, Vardi and Wolper.
+use std::collections::{BTreeSet, HashMap};
+
+use super::ltl::Nnf;
+
+/// State of the automaton. The letter read when entering the state has to satisfy its literals.
+#[derive(Debug)]
+pub(crate) struct BuchiState {
+    /// (atom, true if the atom holds) required by the state
+    pub(crate) literals: Vec<(usize, bool)>,
+    /// for every acceptance set, whether the state belongs to it
+    pub(crate) accepting: Vec<bool>,
+    pub(crate) successors: Vec<usize>,
+}
+
+impl BuchiState {
+    pub(crate) fn allows(&self, valuation: &[bool]) -> bool {
+        self.literals
+            .iter()
+            .all(|(atom, holds)| valuation.get(*atom).copied().unwrap_or(false) == *holds)
+    }
+}
+
+/// An infinite word is accepted if a run enters the initial states then visits every acceptance set infinitely often.
+#[derive(Debug)]
+pub(crate) struct Buchi {
+    pub(crate) states: Vec<BuchiState>,
+    pub(crate) initial: Vec<usize>,
+    /// number of acceptance sets, one per until of the formula
+    pub(crate) sets: usize,
+}
+
+//node of the tableau: incoming nodes (None for the initial node), formulas holding now, formulas holding next
+type Node = (BTreeSet<Option<usize>>, BTreeSet<Nnf>, BTreeSet<Nnf>);
+
+//node of the tableau being expanded
+#[derive(Debug, Clone)]
+struct Pending {
+    //None for the initial node
+    incoming: BTreeSet<Option<usize>>,
+    new: Vec<Nnf>,
+    old: BTreeSet<Nnf>,
+    next: BTreeSet<Nnf>,
+}
+
+impl Buchi {
+    pub(crate) fn new(formula: &Nnf) -> Self {
+        let mut nodes: Vec<Node> = Vec::new();
+        let mut index: HashMap<(BTreeSet<Nnf>, BTreeSet<Nnf>), usize> = HashMap::new();
+        let mut stack = vec![Pending {
+            incoming: BTreeSet::from([None]),
+            new: vec![formula.clone()],
+            old: BTreeSet::new(),
+            next: BTreeSet::new(),
+        }];
+        while let Some(mut node) = stack.pop() {
+            let formula = match node.new.pop() {
+                Some(formula) => formula,
+                None => {
+                    let key = (node.old, node.next);
+                    match index.get(&key) {
+                        Some(existing) => nodes[*existing].0.extend(node.incoming),
+                        None => {
+                            let id = nodes.len();
+                            stack.push(Pending {
+                                incoming: BTreeSet::from([Some(id)]),
+                                new: key.1.iter().cloned().collect(),
+                                old: BTreeSet::new(),
+                                next: BTreeSet::new(),
+                            });
+                            index.insert(key.clone(), id);
+                            nodes.push((node.incoming, key.0, key.1));
+                        }
+                    }
+                    continue;
+                }
+            };
+            if node.old.contains(&formula) {
+                stack.push(node);
+                continue;
+            }
+            match &formula {
+                //the node can not be satisfied
+                Nnf::False => continue,
+                Nnf::Literal(atom, holds) if node.old.contains(&Nnf::Literal(*atom, !holds)) => continue,
+                Nnf::True | Nnf::Literal(..) => {}
+                Nnf::And(a, b) => {
+                    node.new.push(*a.clone());
+                    node.new.push(*b.clone());
+                }
+                Nnf::Next(a) => {
+                    node.next.insert(*a.clone());
+                }
+                Nnf::Or(a, b) | Nnf::Until(a, b) | Nnf::Release(a, b) => {
+                    //a U b is b | (a & X(a U b)), a R b is (a & b) | (b & X(a R b))
+                    let mut other = node.clone();
+                    other.old.insert(formula.clone());
+                    match &formula {
+                        Nnf::Or(..) => {
+                            node.new.push(*a.clone());
+                            other.new.push(*b.clone());
+                        }
+                        Nnf::Until(..) => {
+                            node.new.push(*a.clone());
+                            node.next.insert(formula.clone());
+                            other.new.push(*b.clone());
+                        }
+                        _ => {
+                            node.new.push(*b.clone());
+                            node.next.insert(formula.clone());
+                            other.new.push(*a.clone());
+                            other.new.push(*b.clone());
+                        }
+                    }
+                    stack.push(other);
+                }
+            }
+            node.old.insert(formula);
+            stack.push(node);
+        }
+
+        let mut untils = BTreeSet::new();
+        collect_untils(formula, &mut untils);
+        let mut states: Vec<BuchiState> = nodes
+            .iter()
+            .map(|(_, old, _)| BuchiState {
+                literals: old
+                    .iter()
+                    .filter_map(|f| match f {
+                        Nnf::Literal(atom, holds) => Some((*atom, *holds)),
+                        _ => None,
+                    })
+                    .collect(),
+                //a run can not stay forever in the states waiting for the right side of an until
+                accepting: untils
+                    .iter()
+                    .map(|until| match until {
+                        Nnf::Until(_, b) => !old.contains(until) || old.contains(b),
+                        _ => true,
+                    })
+                    .collect(),
+                successors: Vec::new(),
+            })
+            .collect();
+        let mut initial = Vec::new();
+        for (id, (incoming, _, _)) in nodes.iter().enumerate() {
+            for from in incoming {
+                match from {
+                    Some(from) => states[*from].successors.push(id),
+                    None => initial.push(id),
+                }
+            }
+        }
+        Buchi {
+            states,
+            initial,
+            sets: untils.len(),
+        }
+    }
+}
+
+fn collect_untils(formula: &Nnf, untils: &mut BTreeSet<Nnf>) {
+    match formula {
+        Nnf::True | Nnf::False | Nnf::Literal(..) => {}
+        Nnf::Next(a) => collect_untils(a, untils),
+        Nnf::And(a, b) | Nnf::Or(a, b) | Nnf::Release(a, b) => {
+            collect_untils(a, untils);
+            collect_untils(b, untils);
+        }
+        Nnf::Until(a, b) => {
+            untils.insert(formula.clone());
+            collect_untils(a, untils);
+            collect_untils(b, untils);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::verifier::ltl;
+
+    use super::Buchi;
+
+    //tell if the automaton accepts the word prefix.cycle^ω, by looking for an accepting loop on the positions
+    fn accepts(buchi: &Buchi, prefix: &[&[bool]], cycle: &[&[bool]]) -> bool {
+        let letter = |position: usize| {
+            if position < prefix.len() {
+                prefix[position]
+            } else {
+                cycle[(position - prefix.len()) % cycle.len()]
+            }
+        };
+        //the word is folded on prefix.len() + cycle.len() positions
+        let positions = prefix.len() + cycle.len();
+        let following = |position: usize| {
+            if position + 1 == positions {
+                prefix.len()
+            } else {
+                position + 1
+            }
+        };
+        let mut reached: Vec<(usize, usize)> = buchi
+            .initial
+            .iter()
+            .filter(|q| buchi.states[**q].allows(letter(0)))
+            .map(|q| (*q, 0))
+            .collect();
+        let mut index = 0;
+        while index < reached.len() {
+            let (q, position) = reached[index];
+            for successor in buchi.states[q].successors.iter() {
+                let next = following(position);
+                if buchi.states[*successor].allows(letter(next)) && !reached.contains(&(*successor, next)) {
+                    reached.push((*successor, next));
+                }
+            }
+            index += 1;
+        }
+        //an accepting lasso goes through a state of the cycle part visiting every set
+        let successors = |(q, position): (usize, usize)| -> Vec<(usize, usize)> {
+            let next = following(position);
+            buchi.states[q]
+                .successors
+                .iter()
+                .filter(|s| buchi.states[**s].allows(letter(next)))
+                .map(|s| (*s, next))
+                .collect()
+        };
+        let reachable_from = |start: (usize, usize)| {
+            let mut seen = vec![start];
+            let mut index = 0;
+            while index < seen.len() {
+                for next in successors(seen[index]) {
+                    if !seen.contains(&next) {
+                        seen.push(next);
+                    }
+                }
+                index += 1;
+            }
+            seen
+        };
+        reached.iter().any(|start| {
+            let component: Vec<(usize, usize)> = reachable_from(*start)
+                .into_iter()
+                .filter(|s| successors(*s).iter().any(|n| reachable_from(*n).contains(start)))
+                .collect();
+            component.contains(start)
+                && (0..buchi.sets).all(|set| component.iter().any(|(q, _)| buchi.states[*q].accepting[set]))
+        })
+    }
+
+    #[test]
+    fn buchi_automaton() {
+        let formula = ltl::parse(r#"G("a/b" -> F "c/d")"#).unwrap();
+        let a: &[bool] = &[true, false];
+        let c: &[bool] = &[false, true];
+        let none: &[bool] = &[false, false];
+        let buchi = Buchi::new(&formula.nnf(true));
+        assert_eq!(buchi.sets, 1);
+        assert!(accepts(&buchi, &[a], &[c]));
+        assert!(accepts(&buchi, &[], &[none]));
+        assert!(!accepts(&buchi, &[a], &[none]));
+        assert!(accepts(&buchi, &[], &[a, none, c]));
+        let negation = Buchi::new(&formula.nnf(false));
+        assert!(accepts(&negation, &[a], &[none]));
+        assert!(!accepts(&negation, &[a], &[c]));
+
+        let formula = ltl::parse(r#"!"a/b" U "c/d""#).unwrap();
+        let buchi = Buchi::new(&formula.nnf(true));
+        assert!(accepts(&buchi, &[none, c], &[a]));
+        assert!(!accepts(&buchi, &[none, a, c], &[none]));
+        assert!(!accepts(&buchi, &[], &[none]));
+
+        let formula = ltl::parse(r#"X "a/b" & false R !"c/d""#).unwrap();
+        let buchi = Buchi::new(&formula.nnf(true));
+        assert!(accepts(&buchi, &[none], &[a]));
+        assert!(!accepts(&buchi, &[a], &[none]));
+        assert!(!accepts(&buchi, &[none, a], &[c]));
+    }
+}
@@ -0,0 +1,281 @@
+//! Computation tree logic over the states of a Mealy machine, such as `AG EF "disconnect/*"`.
+//! An event holds in a state when one of its outgoing transitions matches it.
+use indexmap::IndexMap;
+
+use super::temporal::{Connectives, Formula, Parser, Token};
+use crate::{
+    error::{Error, Result},
+    graph::{nodeid::NodeId, Graph},
+};
+
+const WORDS: [&str; 11] = ["true", "false", "EX", "AX", "EF", "AF", "EG", "AG", "E", "A", "U"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Ctl {
+    True,
+    False,
+    /// index of the event in the atoms of the formula
+    Atom(usize),
+    Not(Box<Ctl>),
+    And(Box<Ctl>, Box<Ctl>),
+    Or(Box<Ctl>, Box<Ctl>),
+    Implies(Box<Ctl>, Box<Ctl>),
+    Equiv(Box<Ctl>, Box<Ctl>),
+    /// some next state
+    ExistsNext(Box<Ctl>),
+    /// every next state
+    AllNext(Box<Ctl>),
+    /// some path, eventually
+    ExistsFinally(Box<Ctl>),
+    /// every path, eventually
+    AllFinally(Box<Ctl>),
+    /// some path, always
+    ExistsGlobally(Box<Ctl>),
+    /// every path, always
+    AllGlobally(Box<Ctl>),
+    ExistsUntil(Box<Ctl>, Box<Ctl>),
+    AllUntil(Box<Ctl>, Box<Ctl>),
+}
+
+impl Connectives for Ctl {
+    fn and(a: Self, b: Self) -> Self {
+        Ctl::And(Box::new(a), Box::new(b))
+    }
+
+    fn or(a: Self, b: Self) -> Self {
+        Ctl::Or(Box::new(a), Box::new(b))
+    }
+
+    fn implies(a: Self, b: Self) -> Self {
+        Ctl::Implies(Box::new(a), Box::new(b))
+    }
+
+    fn equiv(a: Self, b: Self) -> Self {
+        Ctl::Equiv(Box::new(a), Box::new(b))
+    }
+}
+
+//states of the Mealy machine with their successors,
+//a state without outgoing transition is its own successor so that every path is infinite
+struct Kripke {
+    successors: Vec<Vec<usize>>,
+    //for every state, whether each atom matches one of its outgoing transitions
+    atoms: Vec<Vec<bool>>,
+}
+
+/// Parse a CTL formula, the column of an error is the position in `text` starting at 1.
+pub(crate) fn parse(text: &str) -> Result<Formula<Ctl>> {
+    Formula::parse(text, &WORDS, unary)
+}
+
+impl Formula<Ctl> {
+    /// Tell for every state of the graph if the formula holds there, the states are in the order of the graph.
+    pub(crate) fn check(&self, graph: &Graph) -> IndexMap<NodeId, bool> {
+        let ids: IndexMap<&NodeId, usize> = graph.iter_node_id().enumerate().map(|(i, id)| (id, i)).collect();
+        let mut kripke = Kripke {
+            successors: Vec::with_capacity(ids.len()),
+            atoms: Vec::with_capacity(ids.len()),
+        };
+        for (id, index) in ids.iter() {
+            let mut successors: Vec<usize> = graph
+                .neighbors_edges_iterator(id)
+                .iter()
+                .filter_map(|(neighbor, _)| ids.get(neighbor).copied())
+                .collect();
+            if successors.is_empty() {
+                successors.push(*index);
+            }
+            successors.sort_unstable();
+            successors.dedup();
+            kripke.successors.push(successors);
+            let labels = graph.get_outgoing_labels(id);
+            kripke.atoms.push(
+                self.atoms
+                    .iter()
+                    .map(|atom| labels.iter().any(|label| atom.check(label)))
+                    .collect(),
+            );
+        }
+        let holds = kripke.states(&self.root);
+        ids.keys().map(|id| ((*id).clone(), holds[ids[id]])).collect()
+    }
+}
+
+impl Kripke {
+    //states where the formula holds, by fixpoint for the temporal operators
+    fn states(&self, formula: &Ctl) -> Vec<bool> {
+        let count = self.successors.len();
+        let map = |a: &Ctl, f: &dyn Fn(bool) -> bool| self.states(a).into_iter().map(f).collect();
+        let zip = |a: &Ctl, b: &Ctl, f: &dyn Fn(bool, bool) -> bool| {
+            let b = self.states(b);
+            self.states(a).into_iter().zip(b).map(|(a, b)| f(a, b)).collect()
+        };
+        match formula {
+            Ctl::True => vec![true; count],
+            Ctl::False => vec![false; count],
+            Ctl::Atom(atom) => self.atoms.iter().map(|atoms| atoms[*atom]).collect(),
+            Ctl::Not(a) => map(a, &|a| !a),
+            Ctl::And(a, b) => zip(a, b, &|a, b| a && b),
+            Ctl::Or(a, b) => zip(a, b, &|a, b| a || b),
+            Ctl::Implies(a, b) => zip(a, b, &|a, b| !a || b),
+            Ctl::Equiv(a, b) => zip(a, b, &|a, b| a == b),
+            Ctl::ExistsNext(a) => {
+                let a = self.states(a);
+                self.successors.iter().map(|s| s.iter().any(|n| a[*n])).collect()
+            }
+            Ctl::AllNext(a) => {
+                let a = self.states(a);
+                self.successors.iter().map(|s| s.iter().all(|n| a[*n])).collect()
+            }
+            Ctl::ExistsFinally(a) => self.until(&vec![true; count], self.states(a), false),
+            Ctl::AllFinally(a) => self.until(&vec![true; count], self.states(a), true),
+            Ctl::ExistsUntil(a, b) => self.until(&self.states(a), self.states(b), false),
+            Ctl::AllUntil(a, b) => self.until(&self.states(a), self.states(b), true),
+            Ctl::ExistsGlobally(a) => self.globally(self.states(a)),
+            //AG a is !EF !a
+            Ctl::AllGlobally(a) => {
+                let not_a = self.states(a).into_iter().map(|a| !a).collect();
+                self.until(&vec![true; count], not_a, false)
+                    .into_iter()
+                    .map(|ef| !ef)
+                    .collect()
+            }
+        }
+    }
+
+    //least fixpoint of b | (a & EX z), or of b | (a & AX z) for every path
+    fn until(&self, a: &[bool], b: Vec<bool>, every_path: bool) -> Vec<bool> {
+        let mut holds = b;
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (state, successors) in self.successors.iter().enumerate() {
+                if holds[state] || !a[state] {
+                    continue;
+                }
+                let next = if every_path {
+                    successors.iter().all(|n| holds[*n])
+                } else {
+                    successors.iter().any(|n| holds[*n])
+                };
+                if next {
+                    holds[state] = true;
+                    changed = true;
+                }
+            }
+        }
+        holds
+    }
+
+    //greatest fixpoint of a & EX z
+    fn globally(&self, a: Vec<bool>) -> Vec<bool> {
+        let mut holds = a;
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (state, successors) in self.successors.iter().enumerate() {
+                if holds[state] && !successors.iter().any(|n| holds[*n]) {
+                    holds[state] = false;
+                    changed = true;
+                }
+            }
+        }
+        holds
+    }
+}
+
+fn unary(parser: &mut Parser) -> Result<Ctl> {
+    let column = parser.column();
+    let next = |parser: &mut Parser| unary(parser).map(Box::new);
+    match parser.next()? {
+        Token::Not => Ok(Ctl::Not(next(parser)?)),
+        Token::Word("EX") => Ok(Ctl::ExistsNext(next(parser)?)),
+        Token::Word("AX") => Ok(Ctl::AllNext(next(parser)?)),
+        Token::Word("EF") => Ok(Ctl::ExistsFinally(next(parser)?)),
+        Token::Word("AF") => Ok(Ctl::AllFinally(next(parser)?)),
+        Token::Word("EG") => Ok(Ctl::ExistsGlobally(next(parser)?)),
+        Token::Word("AG") => Ok(Ctl::AllGlobally(next(parser)?)),
+        Token::Word(quantifier @ ("E" | "A")) => {
+            parser.expect(Token::OpenBracket, &format!("expected {}[a U b]", quantifier))?;
+            let a = Box::new(parser.formula(unary)?);
+            parser.expect(Token::Word("U"), &format!("expected {}[a U b]", quantifier))?;
+            let b = Box::new(parser.formula(unary)?);
+            parser.expect(Token::CloseBracket, "missing closing bracket")?;
+            match quantifier {
+                "E" => Ok(Ctl::ExistsUntil(a, b)),
+                _ => Ok(Ctl::AllUntil(a, b)),
+            }
+        }
+        Token::Word("true") => Ok(Ctl::True),
+        Token::Word("false") => Ok(Ctl::False),
+        Token::Atom(text) => Ok(Ctl::Atom(parser.atom(text, column)?)),
+        Token::Open => {
+            let formula = parser.formula(unary)?;
+            parser.expect(Token::Close, "missing closing parenthesis")?;
+            Ok(formula)
+        }
+        _ => Err(Error::parse(
+            column,
+            "expected an event, a unary operator or a parenthesis",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{error::Error, graph::Graph};
+
+    use super::{parse, Ctl};
+
+    #[test]
+    fn check_formula() {
+        let input = r#"digraph "Automata" {
+            "0" [shape=doublecircle];
+            "0" -> "1" [label="login/ok"];
+            "1" -> "2" [label="read/data"];
+            "1" -> "0" [label="logout/ok"];
+            "2" -> "2" [label="read/data"];
+            "0" -> "3" [label="crash/err"];
+        }"#;
+        //0 -> 1 -> 2 with a loop on 2, 1 -> 0, 0 -> 3 without outgoing transition
+        let graph = Graph::new(input, true);
+        let holding = |text: &str| -> Vec<String> {
+            let formula = parse(text).unwrap();
+            formula
+                .check(&graph)
+                .into_iter()
+                .filter(|(_, holds)| *holds)
+                .map(|(id, _)| id.to_string())
+                .collect()
+        };
+        assert_eq!(holding(r#""logout/*""#), vec!["1"]);
+        assert_eq!(holding(r#"EF "logout/*""#), vec!["0", "1"]);
+        assert_eq!(holding(r#"AG EF "logout/*""#), Vec::<String>::new());
+        assert_eq!(holding(r#"EX "read/*""#), vec!["0", "1", "2"]);
+        assert_eq!(holding(r#"AX "read/*""#), vec!["2"]);
+        assert_eq!(holding(r#"AF "read/*""#), vec!["1", "2"]);
+        assert_eq!(holding(r#"EG !"logout/*""#), vec!["0", "2", "3"]);
+        assert_eq!(holding(r#"E[!"crash/*" U "read/*"]"#), vec!["1", "2"]);
+        assert_eq!(holding(r#"A["login/*" | "logout/*" U "read/*"]"#), vec!["1", "2"]);
+        assert_eq!(holding(r#"!EX true -> false"#), vec!["0", "1", "2", "3"]);
+        assert!(parse(r#"AG("login/*" -> EX "logout/*")"#).is_ok());
+        assert_eq!(
+            parse(r#"E["a/b" U "c/d"]"#).unwrap().root,
+            Ctl::ExistsUntil(Box::new(Ctl::Atom(0)), Box::new(Ctl::Atom(1)))
+        );
+
+        let error = |text: &str| match parse(text) {
+            Err(Error::Parse { location, message }) => (location.column, message),
+            other => panic!("expected a parsing error, got {:?}", other),
+        };
+        assert_eq!(error(r#"E "a/b""#), (3, "expected E[a U b]".to_string()));
+        assert_eq!(error(r#"A["a/b" "c/d"]"#), (9, "expected A[a U b]".to_string()));
+        assert_eq!(
+            error(r#"G "a/b""#),
+            (
+                1,
+                "unknown operator G, events are written between double quotes".to_string()
+            )
+        );
+    }
+}
@@ -1,19 +1,22 @@
 use core::fmt::Debug;
+use std::collections::BTreeSet;
 use std::fmt::Display;
 use wildmatch::WildMatch;
 
+use crate::error::{Error, Result};
+
 #[derive(Debug)]
 pub(crate) struct Events {
     events: Vec<Event>,
 }
 impl Events {
-    pub(crate) fn from_str(events_str: &str) -> Self {
+    pub(crate) fn from_str(events_str: &str) -> Result<Self> {
         let events_str: Vec<&str> = events_str.split(';').map(|s| s.trim()).collect();
         let mut events: Vec<Event> = Vec::with_capacity(events_str.len());
         for event_str in events_str {
-            events.push(Event::new(event_str));
+            events.push(Event::new(event_str)?);
         }
-        Events { events }
+        Ok(Events { events })
     }
     pub(crate) fn empty() -> Self {
         Events { events: vec![] }
@@ -24,15 +27,15 @@ impl Events {
 
     pub(crate) fn check(&self, index: usize, event: &str) -> bool {
         match self.events.get(index) {
-            Some(self_event) => return self_event.check(event),
-            None => return true,
+            Some(self_event) => self_event.check(event),
+            None => true,
         }
     }
 
     pub(crate) fn check_input(&self, index: usize, event: &str) -> bool {
         match self.events.get(index) {
-            Some(self_event) => return self_event.check_input(event),
-            None => return true,
+            Some(self_event) => self_event.check_input(event),
+            None => true,
         }
     }
 
@@ -42,13 +45,23 @@ impl Events {
                 return true;
             }
         }
-        return false;
+        false
     }
 
     pub(crate) fn len(&self) -> usize {
         self.events.len()
     }
 
+    pub(crate) fn iter(&self) -> std::slice::Iter<'_, Event> {
+        self.events.iter()
+    }
+}
+
+impl Display for Events {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let events: Vec<String> = self.events.iter().map(|e| e.to_string()).collect();
+        write!(f, "{}", events.join(";"))
+    }
 }
 
 /// An event has one input and one output.
@@ -62,10 +75,13 @@ pub(crate) struct Event {
 }
 
 impl Event {
-    pub(crate) fn new(event_str: &str) -> Self {
+    pub(crate) fn new(event_str: &str) -> Result<Self> {
         let split_result: Vec<&str> = event_str.split("/").collect();
         if split_result.len() != 2 {
-            panic!("event parsing error {event_str}");
+            return Err(Error::parse(
+                0,
+                format!("event parsing error {event_str}, an event is written as input/output"),
+            ));
         }
         Self::from(split_result[0].trim(), split_result[1].trim())
     }
@@ -76,11 +92,11 @@ impl Event {
             output: Pattern::empty(),
         }
     }
-    pub(crate) fn from(input: &str, output: &str) -> Event {
-        Event {
-            input: Pattern::from_str(input),
-            output: Pattern::from_str(output),
-        }
+    pub(crate) fn from(input: &str, output: &str) -> Result<Event> {
+        Ok(Event {
+            input: Pattern::from_str(input)?,
+            output: Pattern::from_str(output)?,
+        })
     }
     /// NEED TO ADD PARTIAL MATCH
     pub(crate) fn check(&self, event_str: &str) -> bool {
@@ -88,23 +104,23 @@ impl Event {
         let event: Vec<&str> = event_str.split("/").map(|e| e.trim()).collect();
         let input = event[0];
         let output = event[1];
-        if self.input.check(input) && self.output.check(output) {
-            return true;
-        } else {
-            return false;
-        }
+        self.input.check(input) && self.output.check(output)
     }
 
-
     pub(crate) fn check_input(&self, event_str: &str) -> bool {
         let event: Vec<&str> = event_str.split("/").map(|e| e.trim()).collect();
         let input = event[0];
         self.input.check(input)
     }
+
+    /// The patterns of the input and of the output.
+    pub(crate) fn patterns(&self) -> (&Pattern, &Pattern) {
+        (&self.input, &self.output)
+    }
 }
 impl Display for Event {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}/{}", self.input.to_string(), self.output.to_string())
+        write!(f, "{}/{}", self.input, self.output)
     }
 }
 #[derive(Debug, Clone)]
@@ -154,10 +170,28 @@ impl Pattern {
                 return true;
             }
         }
-        return false;
+        false
+    }
+
+    /// The positive elements matching none of the symbols, the negative ones match nearly anything.
+    pub(crate) fn unmatched(&self, symbols: &BTreeSet<String>) -> Vec<String> {
+        self.elements
+            .iter()
+            .filter_map(|element| match element {
+                InnerPattern::Positive(pattern) if !symbols.iter().any(|s| pattern.check(s)) => {
+                    Some(pattern.to_string())
+                }
+                _ => None,
+            })
+            .collect()
     }
 
-    pub(crate) fn from_str(string: &str) -> Self
+    /// Tell if the pattern matches none of the symbols.
+    pub(crate) fn matches_none(&self, symbols: &BTreeSet<String>) -> bool {
+        !symbols.iter().any(|symbol| self.check(symbol))
+    }
+
+    pub(crate) fn from_str(string: &str) -> Result<Self>
     where
         Self: Sized,
     {
@@ -165,23 +199,20 @@ impl Pattern {
         let mut elements: Vec<InnerPattern> = Vec::new();
         for element in line {
             if element.starts_with("!(") {
-                elements.push(InnerPattern::Negatives(NegativesPattern::from_str(element)));
+                elements.push(InnerPattern::Negatives(NegativesPattern::from_str(element)?));
             } else if element.starts_with("!") {
                 elements.push(InnerPattern::Negative(NegativePattern::from_str(element)));
             } else {
                 elements.push(InnerPattern::Positive(PositivePattern::from_str(element)));
             }
         }
-        Self { elements }
+        Ok(Self { elements })
     }
 }
 impl Display for Pattern {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut output_string = String::new();
-        for i in self.elements.iter() {
-            output_string.push_str(&i.to_string());
-        }
-        write!(f, "{}", output_string)
+        let elements: Vec<String> = self.elements.iter().map(|e| e.to_string()).collect();
+        write!(f, "{}", elements.join("+"))
     }
 }
 ///Element of an input or output (transition)
@@ -205,7 +236,7 @@ impl PositivePattern {
 }
 impl Display for PositivePattern {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.inner_pattern.to_string())
+        write!(f, "{}", self.inner_pattern)
     }
 }
 #[derive(Debug, Clone)]
@@ -231,7 +262,7 @@ impl NegativePattern {
 }
 impl Display for NegativePattern {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.inner_pattern.to_string())
+        write!(f, "!{}", self.inner_pattern)
     }
 }
 #[derive(Debug, Clone)]
@@ -250,31 +281,34 @@ impl NegativesPattern {
         result
     }
 
-    fn from_str(string: &str) -> Self
+    fn from_str(string: &str) -> Result<Self>
     where
         Self: Sized,
     {
         let string = string.trim();
-        let index = string.find("!(").expect("failed to parse negative element pattern");
-        let index_end = string.find(")").expect("failed to parse negativeS element pattern");
+        let index = string.find("!(").unwrap_or(0);
+        let index_end = match string.find(")") {
+            Some(index_end) if index_end > index => index_end,
+            _ => {
+                return Err(Error::parse(
+                    0,
+                    format!("failed to parse negative pattern {string}, expected !(a#b)"),
+                ))
+            }
+        };
         let string = &string[index + 2..index_end];
         let line: Vec<&str> = string.split("#").map(|e| e.trim()).collect();
         let mut inner_patterns = Vec::new();
         for negative_pattern in line {
             inner_patterns.push(WildMatch::new(negative_pattern))
         }
-        Self {
-            inner_patterns: inner_patterns,
-        }
+        Ok(Self { inner_patterns })
     }
 }
 impl Display for NegativesPattern {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut output_string = String::new();
-        for i in self.inner_patterns.iter() {
-            output_string.push_str(&i.to_string());
-        }
-        write!(f, "{}", output_string)
+        let patterns: Vec<String> = self.inner_patterns.iter().map(|p| p.to_string()).collect();
+        write!(f, "!({})", patterns.join("#"))
     }
 }
 #[cfg(test)]
@@ -284,7 +318,7 @@ pub(crate) mod tests {
     #[test]
     fn test_event_check() {
         let event = String::from("a/b");
-        let event_to_test = Event::new(&event);
+        let event_to_test = Event::new(&event).unwrap();
         assert!(event_to_test.check(&event));
         let event = String::from("c/d");
         assert!(!event_to_test.check(&event));
@@ -293,7 +327,7 @@ pub(crate) mod tests {
         let event = String::from("c/b");
         assert!(!event_to_test.check(&event));
 
-        let event_to_test = Event::new("input/output1+output2+oupsi*+output1?");
+        let event_to_test = Event::new("input/output1+output2+oupsi*+output1?").unwrap();
         assert!(event_to_test.check("input/output1"));
         assert!(event_to_test.check("input/output2"));
         assert!(event_to_test.check("input/oupsi i cant't remember the output"));
@@ -339,7 +373,7 @@ pub(crate) mod tests {
         let respect_pattern = "zui";
         let does_not_respect_pattern_first = "b";
         let does_not_respect_pattern_second = "a";
-        let negative = NegativesPattern::from_str(pattern);
+        let negative = NegativesPattern::from_str(pattern).unwrap();
         assert!(negative.check(respect_pattern));
         assert!(!negative.check(does_not_respect_pattern_first));
         assert!(!negative.check(does_not_respect_pattern_second));
@@ -350,7 +384,7 @@ pub(crate) mod tests {
         let pattern = "b+c";
         let respect_pattern = "c";
         let does_not_respect_pattern = "z";
-        let pattern = Pattern::from_str(pattern);
+        let pattern = Pattern::from_str(pattern).unwrap();
         assert!(pattern.check(respect_pattern));
         let respect_pattern = "b";
         assert!(pattern.check(respect_pattern));
@@ -358,7 +392,7 @@ pub(crate) mod tests {
 
         let pattern = "!b+c";
         let respect_pattern = "sdlbsdvmklsdnklsdvnkl";
-        let pattern = Pattern::from_str(pattern);
+        let pattern = Pattern::from_str(pattern).unwrap();
         assert!(pattern.check(respect_pattern));
     }
 }
@@ -0,0 +1,115 @@
+//! Boolean formulas on the outgoing transitions of a state, such as `"open/*" -> "close/ok"`.
+//! An event holds in a state when one of its outgoing transitions matches it.
+use super::temporal::{Connectives, Formula, Parser, Token};
+use crate::error::{Error, Result};
+
+const WORDS: [&str; 2] = ["true", "false"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Invariant {
+    True,
+    False,
+    /// index of the event in the atoms of the formula
+    Atom(usize),
+    Not(Box<Invariant>),
+    And(Box<Invariant>, Box<Invariant>),
+    Or(Box<Invariant>, Box<Invariant>),
+    Implies(Box<Invariant>, Box<Invariant>),
+    Equiv(Box<Invariant>, Box<Invariant>),
+}
+
+impl Connectives for Invariant {
+    fn and(a: Self, b: Self) -> Self {
+        Invariant::And(Box::new(a), Box::new(b))
+    }
+
+    fn or(a: Self, b: Self) -> Self {
+        Invariant::Or(Box::new(a), Box::new(b))
+    }
+
+    fn implies(a: Self, b: Self) -> Self {
+        Invariant::Implies(Box::new(a), Box::new(b))
+    }
+
+    fn equiv(a: Self, b: Self) -> Self {
+        Invariant::Equiv(Box::new(a), Box::new(b))
+    }
+}
+
+/// Parse a state formula, the column of an error is the position in `text` starting at 1.
+pub(crate) fn parse(text: &str) -> Result<Formula<Invariant>> {
+    Formula::parse(text, &WORDS, unary)
+}
+
+impl Formula<Invariant> {
+    /// Tell if the formula holds in a state with the given outgoing labels.
+    pub(crate) fn holds<S: AsRef<str>>(&self, labels: &[S]) -> bool {
+        let atoms: Vec<bool> = self
+            .atoms
+            .iter()
+            .map(|atom| labels.iter().any(|label| atom.check(label.as_ref())))
+            .collect();
+        evaluate(&self.root, &atoms)
+    }
+}
+
+fn evaluate(formula: &Invariant, atoms: &[bool]) -> bool {
+    match formula {
+        Invariant::True => true,
+        Invariant::False => false,
+        Invariant::Atom(atom) => atoms[*atom],
+        Invariant::Not(a) => !evaluate(a, atoms),
+        Invariant::And(a, b) => evaluate(a, atoms) && evaluate(b, atoms),
+        Invariant::Or(a, b) => evaluate(a, atoms) || evaluate(b, atoms),
+        Invariant::Implies(a, b) => !evaluate(a, atoms) || evaluate(b, atoms),
+        Invariant::Equiv(a, b) => evaluate(a, atoms) == evaluate(b, atoms),
+    }
+}
+
+fn unary(parser: &mut Parser) -> Result<Invariant> {
+    let column = parser.column();
+    match parser.next()? {
+        Token::Not => Ok(Invariant::Not(Box::new(unary(parser)?))),
+        Token::Word("true") => Ok(Invariant::True),
+        Token::Word("false") => Ok(Invariant::False),
+        Token::Atom(text) => Ok(Invariant::Atom(parser.atom(text, column)?)),
+        Token::Open => {
+            let formula = parser.formula(unary)?;
+            parser.expect(Token::Close, "missing closing parenthesis")?;
+            Ok(formula)
+        }
+        _ => Err(Error::parse(column, "expected an event, a negation or a parenthesis")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+
+    use super::parse;
+
+    #[test]
+    fn state_formula() {
+        let formula = parse(r#""open/*" -> "close/ok" & !"close/nok""#).unwrap();
+        assert!(formula.holds(&["open/ok", "close/ok"]));
+        assert!(formula.holds(&["read/data"]));
+        assert!(!formula.holds(&["open/ok"]));
+        assert!(!formula.holds(&["open/ok", "close/ok", "close/nok"]));
+        let formula = parse(r#"!("X/OK" && "Y/OK") <-> true"#).unwrap();
+        assert!(formula.holds(&["X/OK", "Y/NOK"]));
+        assert!(!formula.holds(&["X/OK", "Y/OK"]));
+
+        let error = |text: &str| match parse(text) {
+            Err(Error::Parse { location, message }) => (location.column, message),
+            other => panic!("expected a parsing error, got {:?}", other),
+        };
+        assert_eq!(
+            error(r#"G "a/b""#),
+            (
+                1,
+                "unknown operator G, events are written between double quotes".to_string()
+            )
+        );
+        assert_eq!(error(r#""a/b" & "#), (9, "unexpected end of the formula".to_string()));
+    }
+}
@@ -0,0 +1,100 @@
+//! Checks of a rule against a Mealy machine, made before applying it.
+//! A pattern matching no symbol of the machine is most likely a typo, and a rule whose premise never occurs holds trivially:
+//! in both cases the verdict says little, hence they are reported as warnings.
+use std::collections::HashSet;
+
+use super::{
+    event::{Event, Pattern},
+    trace,
+};
+use crate::graph::{nodeid::NodeId, Graph};
+
+/// Warnings of a rule written with the events: the positive patterns matching no symbol of the machine.
+/// An event whose input and output both match nothing, such as `I/I`, is a placeholder meant to match no transition
+/// and is not reported, while a typo is on one side of an event.
+pub(crate) fn unmatched<'a>(graph: &Graph, events: impl IntoIterator<Item = &'a Event>) -> Vec<String> {
+    let (symbols_in, symbols_out) = (graph.get_inputs(), graph.get_outputs());
+    let (inputs, outputs): (Vec<&Pattern>, Vec<&Pattern>) = events
+        .into_iter()
+        .map(Event::patterns)
+        .filter(|(input, output)| !(input.matches_none(&symbols_in) && output.matches_none(&symbols_out)))
+        .unzip();
+    unmatched_patterns(graph, inputs, outputs)
+}
+
+/// The positive patterns of the inputs and outputs matching no input and no output of the machine, in the order given.
+pub(crate) fn unmatched_patterns<'a>(
+    graph: &Graph,
+    inputs: impl IntoIterator<Item = &'a Pattern>,
+    outputs: impl IntoIterator<Item = &'a Pattern>,
+) -> Vec<String> {
+    let mut warnings: Vec<String> = Vec::new();
+    let symbols = graph.get_inputs();
+    for pattern in inputs.into_iter().flat_map(|pattern| pattern.unmatched(&symbols)) {
+        warnings.push(format!("input pattern {} matches no input", pattern));
+    }
+    let symbols = graph.get_outputs();
+    for pattern in outputs.into_iter().flat_map(|pattern| pattern.unmatched(&symbols)) {
+        warnings.push(format!("output pattern {} matches no output", pattern));
+    }
+    let mut seen = HashSet::new();
+    warnings.retain(|warning| seen.insert(warning.clone()));
+    warnings
+}
+
+/// A warning when no transition reachable from the initial state matches, `what` tells which events were looked for.
+pub(crate) fn never_occurs(graph: &Graph, what: String, matches: impl Fn(&str) -> bool) -> Option<String> {
+    let occurs = reachable_labels(graph).iter().any(|label| matches(label));
+    (!occurs).then(|| format!("holds trivially, no reachable transition matches the {}", what))
+}
+
+//labels of the transitions leaving the states reachable from the initial state
+fn reachable_labels(graph: &Graph) -> Vec<String> {
+    let reachable: HashSet<NodeId> = trace::shortest_paths(graph).into_keys().collect();
+    graph
+        .iter_node_id()
+        .filter(|id| reachable.contains(*id))
+        .flat_map(|id| graph.get_outgoing_labels(id))
+        .map(|label| label.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{graph::Graph, verifier::rules::parse_rule_from_str};
+
+    #[test]
+    fn lint_rules() {
+        let input = r#"digraph "Automata" {
+            "0" [shape=doublecircle];
+            "0" -> "1" [label="hello/Ack"];
+            "1" -> "1" [label="open_secure_channel_request/OpnRepOK"];
+            "1" -> "0" [label="close/ok"];
+            "2" -> "2" [label="activate/ActOK"];
+        }"#;
+        //0 -hello-> 1 -close-> 0 with the channels opened on 1, 2 is not reachable
+        let graph = Graph::new(input, true);
+        let lint = |rule: &str| parse_rule_from_str(rule)[0].lint(&graph);
+        //a typo in the output
+        assert_eq!(
+            lint("OR:open\nI:open_secure_channel_request\nO:OpnRepKO\n:OR"),
+            vec!["output pattern OpnRepKO matches no output"]
+        );
+        //the action only occurs on an unreachable state
+        assert_eq!(
+            lint("CT:activate_after_open\nactivate/*\nopen_secure_channel_request/*|close/*\n:CT"),
+            vec!["holds trivially, no reachable transition matches the action activate/*"]
+        );
+        assert_eq!(
+            lint("RE:session\nI:hello/Ack+Nack\nA:!close/*\nR:close/*\n:RE"),
+            vec!["output pattern Nack matches no output"]
+        );
+        assert!(lint("RE:session\nI:hello/*\nA:*/*\nR:close/*\n:RE").is_empty());
+        //a placeholder event matching no transition on purpose
+        assert!(lint("CT:channel_then_close\nopen_secure_channel_request/* | I/I\nclose/*\n:CT").is_empty());
+        assert_eq!(
+            lint("RE:session\nI:activate/*\nA:*/*\nR:close/*\n:RE"),
+            vec!["holds trivially, no reachable transition matches the init event activate/*"]
+        );
+    }
+}
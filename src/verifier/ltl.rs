@@ -0,0 +1,216 @@
+//! Linear temporal logic over the events of a Mealy machine, such as `G("open/ok" -> F "close/ok")`.
+use super::temporal::{Connectives, Formula, Parser, Token};
+use crate::error::{Error, Result};
+
+const WORDS: [&str; 7] = ["true", "false", "X", "F", "G", "U", "R"];
+
+/// Formula as written in the rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Ltl {
+    True,
+    False,
+    /// index of the event in the atoms of the formula
+    Atom(usize),
+    Not(Box<Ltl>),
+    And(Box<Ltl>, Box<Ltl>),
+    Or(Box<Ltl>, Box<Ltl>),
+    Implies(Box<Ltl>, Box<Ltl>),
+    Equiv(Box<Ltl>, Box<Ltl>),
+    Next(Box<Ltl>),
+    Finally(Box<Ltl>),
+    Globally(Box<Ltl>),
+    Until(Box<Ltl>, Box<Ltl>),
+    Release(Box<Ltl>, Box<Ltl>),
+}
+
+impl Connectives for Ltl {
+    fn and(a: Self, b: Self) -> Self {
+        Ltl::And(Box::new(a), Box::new(b))
+    }
+
+    fn or(a: Self, b: Self) -> Self {
+        Ltl::Or(Box::new(a), Box::new(b))
+    }
+
+    fn implies(a: Self, b: Self) -> Self {
+        Ltl::Implies(Box::new(a), Box::new(b))
+    }
+
+    fn equiv(a: Self, b: Self) -> Self {
+        Ltl::Equiv(Box::new(a), Box::new(b))
+    }
+}
+
+/// Formula in negative normal form: the negations are on the atoms and only X, U and R are left.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(crate) enum Nnf {
+    True,
+    False,
+    /// index of the event, false for its negation
+    Literal(usize, bool),
+    And(Box<Nnf>, Box<Nnf>),
+    Or(Box<Nnf>, Box<Nnf>),
+    Next(Box<Nnf>),
+    Until(Box<Nnf>, Box<Nnf>),
+    Release(Box<Nnf>, Box<Nnf>),
+}
+
+/// Parse a LTL formula, the column of an error is the position in `text` starting at 1.
+pub(crate) fn parse(text: &str) -> Result<Formula<Ltl>> {
+    Formula::parse(text, &WORDS, binary)
+}
+
+impl Formula<Ltl> {
+    /// Negative normal form of the formula, or of its negation when `positive` is false.
+    pub(crate) fn nnf(&self, positive: bool) -> Nnf {
+        nnf(&self.root, positive)
+    }
+
+    /// Tell for every atom if the label matches its event.
+    pub(crate) fn valuation(&self, label: &str) -> Vec<bool> {
+        self.atoms.iter().map(|atom| atom.check(label)).collect()
+    }
+}
+
+fn nnf(formula: &Ltl, positive: bool) -> Nnf {
+    let pair = |a: &Ltl, b: &Ltl, positive_a: bool, positive_b: bool| {
+        (Box::new(nnf(a, positive_a)), Box::new(nnf(b, positive_b)))
+    };
+    match (formula, positive) {
+        (Ltl::True, true) | (Ltl::False, false) => Nnf::True,
+        (Ltl::True, false) | (Ltl::False, true) => Nnf::False,
+        (Ltl::Atom(index), _) => Nnf::Literal(*index, positive),
+        (Ltl::Not(a), _) => nnf(a, !positive),
+        (Ltl::And(a, b), true) | (Ltl::Or(a, b), false) => {
+            let (a, b) = pair(a, b, positive, positive);
+            Nnf::And(a, b)
+        }
+        (Ltl::Or(a, b), true) | (Ltl::And(a, b), false) => {
+            let (a, b) = pair(a, b, positive, positive);
+            Nnf::Or(a, b)
+        }
+        (Ltl::Implies(a, b), true) => {
+            let (a, b) = pair(a, b, false, true);
+            Nnf::Or(a, b)
+        }
+        (Ltl::Implies(a, b), false) => {
+            let (a, b) = pair(a, b, true, false);
+            Nnf::And(a, b)
+        }
+        (Ltl::Equiv(a, b), _) => {
+            //a <-> b is (a & b) | (!a & !b), its negation (a & !b) | (!a & b)
+            let (both, both_negated) = pair(a, b, true, positive);
+            let (negated, negated_both) = pair(a, b, false, !positive);
+            Nnf::Or(
+                Box::new(Nnf::And(both, both_negated)),
+                Box::new(Nnf::And(negated, negated_both)),
+            )
+        }
+        (Ltl::Next(a), _) => Nnf::Next(Box::new(nnf(a, positive))),
+        (Ltl::Finally(a), true) | (Ltl::Globally(a), false) => {
+            Nnf::Until(Box::new(Nnf::True), Box::new(nnf(a, positive)))
+        }
+        (Ltl::Globally(a), true) | (Ltl::Finally(a), false) => {
+            Nnf::Release(Box::new(Nnf::False), Box::new(nnf(a, positive)))
+        }
+        (Ltl::Until(a, b), true) | (Ltl::Release(a, b), false) => {
+            let (a, b) = pair(a, b, positive, positive);
+            Nnf::Until(a, b)
+        }
+        (Ltl::Release(a, b), true) | (Ltl::Until(a, b), false) => {
+            let (a, b) = pair(a, b, positive, positive);
+            Nnf::Release(a, b)
+        }
+    }
+}
+
+//the tightest binary operators, U and R
+fn binary(parser: &mut Parser) -> Result<Ltl> {
+    let formula = unary(parser)?;
+    if parser.accept(Token::Word("U")) {
+        return Ok(Ltl::Until(Box::new(formula), Box::new(binary(parser)?)));
+    }
+    if parser.accept(Token::Word("R")) {
+        return Ok(Ltl::Release(Box::new(formula), Box::new(binary(parser)?)));
+    }
+    Ok(formula)
+}
+
+fn unary(parser: &mut Parser) -> Result<Ltl> {
+    let column = parser.column();
+    match parser.next()? {
+        Token::Not => Ok(Ltl::Not(Box::new(unary(parser)?))),
+        Token::Word("X") => Ok(Ltl::Next(Box::new(unary(parser)?))),
+        Token::Word("F") => Ok(Ltl::Finally(Box::new(unary(parser)?))),
+        Token::Word("G") => Ok(Ltl::Globally(Box::new(unary(parser)?))),
+        Token::Word("true") => Ok(Ltl::True),
+        Token::Word("false") => Ok(Ltl::False),
+        Token::Atom(text) => Ok(Ltl::Atom(parser.atom(text, column)?)),
+        Token::Open => {
+            let formula = parser.formula(binary)?;
+            parser.expect(Token::Close, "missing closing parenthesis")?;
+            Ok(formula)
+        }
+        _ => Err(Error::parse(
+            column,
+            "expected an event, a unary operator or a parenthesis",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+
+    use super::{parse, Ltl, Nnf};
+
+    #[test]
+    fn parse_formula() {
+        let formula = parse(r#"G("open/ok" -> F "close/*") & !X "open/ok" U true"#).unwrap();
+        let atom = |index| Box::new(Ltl::Atom(index));
+        assert_eq!(
+            formula.root,
+            Ltl::And(
+                Box::new(Ltl::Globally(Box::new(Ltl::Implies(
+                    atom(0),
+                    Box::new(Ltl::Finally(atom(1)))
+                )))),
+                Box::new(Ltl::Until(
+                    Box::new(Ltl::Not(Box::new(Ltl::Next(atom(0))))),
+                    Box::new(Ltl::True)
+                ))
+            )
+        );
+        assert_eq!(formula.atoms.len(), 2);
+        assert_eq!(formula.valuation("close/ok"), vec![false, true]);
+        //the negation of G a is true U !a
+        assert_eq!(
+            parse(r#"G "a/b""#).unwrap().nnf(false),
+            Nnf::Until(Box::new(Nnf::True), Box::new(Nnf::Literal(0, false)))
+        );
+
+        let error = |text: &str| match parse(text) {
+            Err(Error::Parse { location, message }) => (location.column, message),
+            other => panic!("expected a parsing error, got {:?}", other),
+        };
+        assert_eq!(error(r#"G ("a/b" "#), (10, "missing closing parenthesis".to_string()));
+        assert_eq!(
+            error(r#"G a/b"#),
+            (
+                3,
+                "unknown operator a, events are written between double quotes".to_string()
+            )
+        );
+        assert_eq!(
+            error(r#"F "a/b" )"#),
+            (9, "unexpected token after the end of the formula".to_string())
+        );
+        assert_eq!(
+            error(r#"F "ab""#),
+            (
+                4,
+                "event parsing error ab, an event is written as input/output".to_string()
+            )
+        );
+    }
+}
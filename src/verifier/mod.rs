@@ -1,56 +1,223 @@
-use std::{fs, path::PathBuf};
+use std::{
+    fs, io,
+    path::{Component, Path, PathBuf},
+};
 
-use crate::{cli::Args, graph::Graph};
+use crate::{
+    cli::Args,
+    error::Error,
+    graph::{self, Graph, LoadOptions},
+    utils::output,
+};
 
-use self::rules::Rule;
+use self::{result::GraphResult, rules::Rule};
 
+pub(crate) mod buchi;
+pub(crate) mod ctl;
 pub(crate) mod event;
+pub(crate) mod invariant;
+pub(crate) mod lint;
+pub(crate) mod ltl;
 pub(crate) mod premise;
-pub(crate) mod rules;
+pub(crate) mod regular;
+pub mod result;
+pub mod rules;
+pub(crate) mod temporal;
+pub mod trace;
 
-pub(crate) struct Verifier {
+pub struct Verifier {
     pub(crate) rules: Vec<Box<dyn Rule>>,
     pub(crate) graphs: Vec<Graph>,
     pub(crate) output_folder: PathBuf,
+    /// write the whole graph annotated with the verdict of each rule
+    pub(crate) annotate: bool,
 }
 
 impl Verifier {
-    pub(crate) fn from_args(args: Args) -> Self {
-        if args.graphs.is_empty(){
-            panic!("dot file is required.")
+    ///Verifier checking every rule against every graph, the counterexamples are written in the output folder.
+    pub fn new(rules: Vec<Box<dyn Rule>>, graphs: Vec<Graph>, output_folder: PathBuf) -> Self {
+        Verifier {
+            rules,
+            graphs,
+            output_folder,
+            annotate: false,
         }
-        let rules = rules::parse_file(&args.rules);
+    }
+
+    ///Also write the whole graph with the transitions reported by each rule highlighted, in `annotated.dot`.
+    pub fn set_annotate(&mut self, annotate: bool) {
+        self.annotate = annotate;
+    }
+
+    ///Build the verifier from the command line arguments, the models of the directories given are looked for recursively.
+    /// The rules and graphs that could not be loaded are left out and their errors returned.
+    pub fn from_args(args: Args) -> (Self, Vec<Error>) {
+        let (rules, mut errors) = rules::parse_file(&args.rules);
+        let options = LoadOptions {
+            format: args.format,
+            io_separator: args.io_separator,
+            initial_state: args.initial_state,
+            validation: args.validation,
+            default_output: args.default_output,
+        };
         let mut graphs: Vec<Graph> = Vec::with_capacity(args.graphs.len());
-        for path_to_graph_file in args.graphs {
-            graphs.push(Graph::new_file(&path_to_graph_file));
+        for path in args.graphs {
+            let paths = if Path::new(&path).is_dir() {
+                match graph::find_models(Path::new(&path), options.format) {
+                    Ok(models) => models.iter().map(|m| m.to_string_lossy().to_string()).collect(),
+                    Err(e) => {
+                        errors.push(e);
+                        continue;
+                    }
+                }
+            } else {
+                vec![path]
+            };
+            for path_to_graph_file in paths {
+                let added = Graph::load(&path_to_graph_file, &options)
+                    .and_then(|graph| graph::add_graph(&mut graphs, graph, &path_to_graph_file));
+                if let Err(e) = added {
+                    errors.push(e);
+                }
+            }
         }
         let output_folder = match args.output_folder {
-            None => {
-                let result_folder_number = rand::random::<u8>();
-                println!("Output folder is  result_{}", result_folder_number);
-                PathBuf::from(format!("result_{}", result_folder_number))
-            }
+            None => match output::new_output_folder() {
+                Ok(output_folder) => {
+                    println!("Output folder is  {}", output_folder.display());
+                    output_folder
+                }
+                //without a folder for their results, the graphs are not verified
+                Err(e) => {
+                    errors.push(e);
+                    return (Verifier::new(rules, Vec::new(), PathBuf::new()), errors);
+                }
+            },
             Some(p) => p,
         };
-        Verifier {
-            rules,
-            graphs,
-            output_folder,
-        }
+        let mut verifier = Verifier::new(rules, graphs, output_folder);
+        verifier.set_annotate(args.annotate);
+        (verifier, errors)
+    }
+
+    pub fn get_rules(&self) -> &[Box<dyn Rule>] {
+        &self.rules
+    }
+
+    pub fn get_graphs(&self) -> &[Graph] {
+        &self.graphs
+    }
+
+    pub fn get_output_folder(&self) -> &PathBuf {
+        &self.output_folder
     }
 
-    pub(crate) fn apply(&mut self) {
+    ///Apply every rule on every graph and return the verdicts, graph by graph.
+    /// A rule failing on a graph is reported in the graph result and the other rules are still applied.
+    pub fn apply(&mut self) -> Vec<GraphResult> {
+        let mut results = Vec::with_capacity(self.graphs.len());
         for graph in self.graphs.iter() {
-            self.output_folder.push(graph.get_name());
+            //the name of the graph is a relative path, hence the folder is joined rather than pushed and popped
+            let graph_folder = self.output_folder.join(graph.get_name());
+            let mut graph_result = GraphResult {
+                graph: graph.get_name().to_string(),
+                implementations: graph.get_implementations().to_vec(),
+                rules: Vec::with_capacity(self.rules.len()),
+                errors: Vec::new(),
+                warnings: graph.get_warnings().to_vec(),
+            };
             // println!("graph: {}",graph.get_name());
-            if let Err(e) = fs::create_dir_all(&self.output_folder) {
-                panic!("failed to create output directory due to :{}", e.to_string());
+            if let Err(e) = fs::create_dir_all(&graph_folder) {
+                //no rule can be applied, each of them is reported as failing
+                for r in self.rules.iter() {
+                    let error = io::Error::new(e.kind(), e.to_string());
+                    let error = Error::io(graph_folder.clone(), error);
+                    graph_result.errors.push(error.rule(r.get_name(), graph.get_name()));
+                }
+                results.push(graph_result);
+                continue;
             }
             for r in self.rules.iter_mut() {
                 // println!("rule:{},",r.get_name());
-                r.apply(graph, &mut self.output_folder);
+                //the results of a previous run forced into the same folder would be mixed with the new ones,
+                //the folder of the rule is only removed when it is a sub folder of the graph folder
+                let rule_folder = graph_folder.join(r.get_name());
+                let mut components = Path::new(r.get_name()).components();
+                if !matches!(
+                    (components.next(), components.next()),
+                    (Some(Component::Normal(_)), None)
+                ) {
+                    let error = io::Error::new(io::ErrorKind::InvalidInput, "the rule name is not a folder name");
+                    let error = Error::io(rule_folder, error);
+                    graph_result.errors.push(error.rule(r.get_name(), graph.get_name()));
+                    continue;
+                }
+                if rule_folder.exists() {
+                    if let Err(e) = fs::remove_dir_all(&rule_folder) {
+                        let error = Error::io(rule_folder, e);
+                        graph_result.errors.push(error.rule(r.get_name(), graph.get_name()));
+                        continue;
+                    }
+                }
+                //a rule failing may not restore the folder, hence each rule gets its own copy
+                let mut output_folder = graph_folder.clone();
+                match r.apply(graph, &mut output_folder) {
+                    Ok(mut result) => {
+                        result.severity = r.get_severity();
+                        result.warnings = r.lint(graph);
+                        if self.annotate {
+                            let mut output_folder = graph_folder.join(r.get_name());
+                            let annotated = fs::create_dir_all(&output_folder)
+                                .and_then(|_| output::write_annotated(graph, &result, &mut output_folder));
+                            match annotated {
+                                Ok(path) => result.add_counterexample(Some(path)),
+                                Err(e) => graph_result
+                                    .errors
+                                    .push(Error::io(output_folder, e).rule(r.get_name(), graph.get_name())),
+                            }
+                        }
+                        graph_result.rules.push(result)
+                    }
+                    Err(e) => graph_result.errors.push(e.rule(r.get_name(), graph.get_name())),
+                }
             }
-            self.output_folder.pop();
+            results.push(graph_result);
+        }
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{Graph, LoadOptions};
+
+    use super::{rules::parse_rule_from_str, Verifier};
+
+    #[test]
+    fn graph_folders() {
+        let folder = std::env::temp_dir().join(format!("mealy_verifier_folders_{}", std::process::id()));
+        let mut graphs = Vec::new();
+        for model in ["first", "second"] {
+            let path = folder.join("models").join(model).join("automata.dot");
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, "digraph { s0 -> s0 [label=\"a/b\"] }").unwrap();
+            graphs.push(Graph::load(&path.to_string_lossy(), &LoadOptions::default()).unwrap());
+        }
+        let rules = parse_rule_from_str("OR:output\nI:a\nO:b\n:OR");
+        let output_folder = folder.join("result");
+        let mut verifier = Verifier::new(rules, graphs, output_folder.clone());
+        let results = verifier.apply();
+        //the folders of the graphs are the paths of their files, side by side in the output folder
+        assert_eq!(verifier.get_output_folder(), &output_folder);
+        for result in results.iter() {
+            assert!(result.graph.ends_with("/automata.dot"));
+            assert!(output_folder.join(&result.graph).join("output").is_dir());
         }
+        //a second run in the same folder does not keep the files of the first one
+        let stale = output_folder.join(&results[0].graph).join("output").join("ce.dot");
+        std::fs::write(&stale, "digraph {}").unwrap();
+        verifier.apply();
+        assert!(!stale.exists());
+        std::fs::remove_dir_all(&folder).unwrap();
     }
 }
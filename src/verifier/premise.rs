@@ -1,4 +1,5 @@
 use super::event::{Event, Events};
+use crate::error::{Error, Result};
 
 #[derive(Debug)]
 pub(crate) struct Premise {
@@ -7,21 +8,29 @@ pub(crate) struct Premise {
 }
 
 impl Premise {
-    pub(crate) fn new(premise_str: &str) -> Self {
+    pub(crate) fn new(premise_str: &str) -> Result<Self> {
         let split: Vec<&str> = premise_str.split('|').collect();
-        let event = Event::new(split[0]);
-        let counter_event = Events::from_str(split[1]);
-        Premise { event, counter_event }
+        if split.len() != 2 {
+            return Err(Error::parse(
+                0,
+                format!("failed to parse premise {premise_str}, expected event|counter events"),
+            ));
+        }
+        let event = Event::new(split[0].trim())?;
+        let counter_event = Events::from_str(split[1])?;
+        Ok(Premise { event, counter_event })
     }
 
-
     pub(crate) fn check_counter_event(&self, event_str: &str) -> bool {
-        return self.counter_event.check_all(event_str);
+        self.counter_event.check_all(event_str)
     }
 
-
     pub(crate) fn check_event(&self, event_str: &str) -> bool {
         self.event.check(event_str)
     }
 
+    /// The event of the premise followed by its counter events.
+    pub(crate) fn events(&self) -> impl Iterator<Item = &Event> {
+        std::iter::once(&self.event).chain(self.counter_event.iter())
+    }
 }
@@ -0,0 +1,387 @@
+//! Regular expressions over the events of a Mealy machine, such as `"hello/Ack" . (!"open*/*")* . "read_req/*"`.
+//! They are compiled into the position automaton of Glushkov, which has no empty transition.
+use std::collections::VecDeque;
+
+use super::{
+    event::Event,
+    temporal::{Parser, Token},
+};
+use crate::error::{Error, Result};
+
+//bound on the repetitions written with braces, the automaton grows with them
+const MAX_REPETITIONS: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Regex {
+    /// the empty word
+    Empty,
+    /// index of the event in the atoms of the expression, false for the transitions not matching it
+    Atom(usize, bool),
+    Concatenation(Box<Regex>, Box<Regex>),
+    Alternation(Box<Regex>, Box<Regex>),
+    Star(Box<Regex>),
+}
+
+/// An expression with the automaton recognizing it.
+/// The states of the automaton are the initial state 0 and the positions of the atoms in the expression,
+/// a transition enters a position when its label matches the atom written there.
+#[derive(Debug)]
+pub(crate) struct Expression {
+    pub(crate) atoms: Vec<Event>,
+    //atom read when entering each state, None for the initial state
+    symbols: Vec<Option<(usize, bool)>>,
+    follow: Vec<Vec<usize>>,
+    accepting: Vec<bool>,
+    //number of transitions from each state to an accepting one
+    distances: Vec<usize>,
+}
+
+impl Expression {
+    /// Parse an expression, the column of an error is the position in `text` starting at 1.
+    pub(crate) fn parse(text: &str) -> Result<Self> {
+        let mut parser = Parser::new(text, &[])?;
+        let root = alternation(&mut parser)?;
+        let atoms = parser.finish()?;
+        let mut symbols = vec![None];
+        let mut follow = vec![Vec::new()];
+        let (nullable, first, last) = positions(&root, &mut symbols, &mut follow);
+        if nullable {
+            return Err(Error::parse(1, "the expression matches the empty trace"));
+        }
+        follow[0] = first;
+        for successors in follow.iter_mut() {
+            successors.sort_unstable();
+            successors.dedup();
+        }
+        let mut accepting = vec![false; symbols.len()];
+        for position in last {
+            accepting[position] = true;
+        }
+        let distances = distances(&follow, &accepting);
+        Ok(Expression {
+            atoms,
+            symbols,
+            follow,
+            accepting,
+            distances,
+        })
+    }
+
+    /// States of the automaton before any transition.
+    pub(crate) fn initial(&self) -> Vec<usize> {
+        vec![0]
+    }
+
+    /// States reached from `states` by a transition with the given label, sorted.
+    pub(crate) fn step(&self, states: &[usize], label: &str) -> Vec<usize> {
+        let valuation: Vec<bool> = self.atoms.iter().map(|atom| atom.check(label)).collect();
+        let mut next: Vec<usize> = states
+            .iter()
+            .flat_map(|state| self.follow[*state].iter().copied())
+            .filter(|position| self.symbols[*position].is_some_and(|(atom, holds)| valuation[atom] == holds))
+            .collect();
+        next.sort_unstable();
+        next.dedup();
+        next
+    }
+
+    /// Tell if a word leading to these states matches the expression.
+    pub(crate) fn accepts(&self, states: &[usize]) -> bool {
+        states.iter().any(|state| self.accepting[*state])
+    }
+
+    /// Number of transitions still needed to match the expression from these states.
+    pub(crate) fn distance(&self, states: &[usize]) -> usize {
+        states
+            .iter()
+            .map(|state| self.distances[*state])
+            .min()
+            .unwrap_or(usize::MAX)
+    }
+
+    /// Events bringing these states closer to a match, as written in the expression.
+    pub(crate) fn expected(&self, states: &[usize]) -> Vec<String> {
+        let distance = self.distance(states);
+        let mut expected: Vec<String> = Vec::new();
+        for state in states.iter().filter(|state| self.distances[**state] == distance) {
+            for position in self.follow[*state].iter() {
+                if self.distances[*position] + 1 != distance {
+                    continue;
+                }
+                if let Some((atom, holds)) = self.symbols[*position] {
+                    let text = format!("{}\"{}\"", if holds { "" } else { "!" }, self.atoms[atom]);
+                    if !expected.contains(&text) {
+                        expected.push(text);
+                    }
+                }
+            }
+        }
+        expected
+    }
+}
+
+//number the atoms of the expression as positions and fill the positions following each one,
+//gives whether the expression matches the empty word, its first and its last positions
+fn positions(
+    regex: &Regex,
+    symbols: &mut Vec<Option<(usize, bool)>>,
+    follow: &mut Vec<Vec<usize>>,
+) -> (bool, Vec<usize>, Vec<usize>) {
+    match regex {
+        Regex::Empty => (true, Vec::new(), Vec::new()),
+        Regex::Atom(atom, holds) => {
+            let position = symbols.len();
+            symbols.push(Some((*atom, *holds)));
+            follow.push(Vec::new());
+            (false, vec![position], vec![position])
+        }
+        Regex::Concatenation(a, b) => {
+            let (nullable_a, first_a, last_a) = positions(a, symbols, follow);
+            let (nullable_b, first_b, last_b) = positions(b, symbols, follow);
+            for position in last_a.iter() {
+                follow[*position].extend(first_b.iter().copied());
+            }
+            let mut first = first_a;
+            if nullable_a {
+                first.extend(first_b);
+            }
+            let mut last = last_b;
+            if nullable_b {
+                last.extend(last_a);
+            }
+            (nullable_a && nullable_b, first, last)
+        }
+        Regex::Alternation(a, b) => {
+            let (nullable_a, mut first, mut last) = positions(a, symbols, follow);
+            let (nullable_b, first_b, last_b) = positions(b, symbols, follow);
+            first.extend(first_b);
+            last.extend(last_b);
+            (nullable_a || nullable_b, first, last)
+        }
+        Regex::Star(a) => {
+            let (_, first, last) = positions(a, symbols, follow);
+            for position in last.iter() {
+                follow[*position].extend(first.iter().copied());
+            }
+            (true, first, last)
+        }
+    }
+}
+
+//breadth first search from the accepting states on the reversed transitions
+fn distances(follow: &[Vec<usize>], accepting: &[bool]) -> Vec<usize> {
+    let mut previous = vec![Vec::new(); follow.len()];
+    for (state, successors) in follow.iter().enumerate() {
+        for successor in successors {
+            previous[*successor].push(state);
+        }
+    }
+    let mut distances = vec![usize::MAX; follow.len()];
+    let mut queue = VecDeque::new();
+    for (state, accepting) in accepting.iter().enumerate() {
+        if *accepting {
+            distances[state] = 0;
+            queue.push_back(state);
+        }
+    }
+    while let Some(state) = queue.pop_front() {
+        for predecessor in previous[state].iter() {
+            if distances[*predecessor] == usize::MAX {
+                distances[*predecessor] = distances[state] + 1;
+                queue.push_back(*predecessor);
+            }
+        }
+    }
+    distances
+}
+
+//alternatives separated by |
+fn alternation(parser: &mut Parser) -> Result<Regex> {
+    let mut regex = concatenation(parser)?;
+    while parser.accept(Token::Or) {
+        regex = Regex::Alternation(Box::new(regex), Box::new(concatenation(parser)?));
+    }
+    Ok(regex)
+}
+
+//factors separated by a dot or written one after the other
+fn concatenation(parser: &mut Parser) -> Result<Regex> {
+    let mut regex = repetition(parser)?;
+    loop {
+        let dot = parser.accept(Token::Dot);
+        if !dot && !matches!(parser.peek(), Some(Token::Atom(_) | Token::Not | Token::Open)) {
+            return Ok(regex);
+        }
+        regex = Regex::Concatenation(Box::new(regex), Box::new(repetition(parser)?));
+    }
+}
+
+//a factor followed by *, +, ? or a number of repetitions between braces
+fn repetition(parser: &mut Parser) -> Result<Regex> {
+    let mut regex = factor(parser)?;
+    loop {
+        let column = parser.column();
+        regex = if parser.accept(Token::Star) {
+            Regex::Star(Box::new(regex))
+        } else if parser.accept(Token::Plus) {
+            Regex::Concatenation(Box::new(regex.clone()), Box::new(Regex::Star(Box::new(regex))))
+        } else if parser.accept(Token::Question) {
+            Regex::Alternation(Box::new(regex), Box::new(Regex::Empty))
+        } else if parser.accept(Token::OpenBrace) {
+            let (min, max) = bounds(parser, column)?;
+            repeat(&regex, min, max)
+        } else {
+            return Ok(regex);
+        };
+    }
+}
+
+//{n}, {n,} or {n,m} once the opening brace is read
+fn bounds(parser: &mut Parser, column: usize) -> Result<(usize, Option<usize>)> {
+    let number = |parser: &mut Parser| {
+        let column = parser.column();
+        match parser.next()? {
+            Token::Number(number) if number <= MAX_REPETITIONS => Ok(number),
+            Token::Number(_) => Err(Error::parse(
+                column,
+                format!("at most {} repetitions are allowed", MAX_REPETITIONS),
+            )),
+            _ => Err(Error::parse(column, "expected a number of repetitions")),
+        }
+    };
+    let min = number(parser)?;
+    let max = if parser.accept(Token::Comma) {
+        match parser.peek() {
+            Some(Token::Number(_)) => Some(number(parser)?),
+            _ => None,
+        }
+    } else {
+        Some(min)
+    };
+    parser.expect(Token::CloseBrace, "missing closing brace")?;
+    if max.is_some_and(|max| max < min) {
+        return Err(Error::parse(
+            column,
+            "the maximum number of repetitions is lower than the minimum",
+        ));
+    }
+    Ok((min, max))
+}
+
+//regex repeated between min and max times, without bound when max is None
+fn repeat(regex: &Regex, min: usize, max: Option<usize>) -> Regex {
+    let concatenation = |a: Regex, b: Regex| match (a, b) {
+        (Regex::Empty, b) => b,
+        (a, Regex::Empty) => a,
+        (a, b) => Regex::Concatenation(Box::new(a), Box::new(b)),
+    };
+    //the optional repetitions are nested, (r(r)?)? rather than r?r?, so that a word has one way to match them
+    let optional = match max {
+        None => Regex::Star(Box::new(regex.clone())),
+        Some(max) => (min..max).fold(Regex::Empty, |tail, _| {
+            Regex::Alternation(Box::new(concatenation(regex.clone(), tail)), Box::new(Regex::Empty))
+        }),
+    };
+    (0..min).fold(optional, |tail, _| concatenation(regex.clone(), tail))
+}
+
+fn factor(parser: &mut Parser) -> Result<Regex> {
+    let column = parser.column();
+    match parser.next()? {
+        Token::Atom(text) => Ok(Regex::Atom(parser.atom(text, column)?, true)),
+        Token::Not => {
+            let column = parser.column();
+            match parser.next()? {
+                Token::Atom(text) => Ok(Regex::Atom(parser.atom(text, column)?, false)),
+                _ => Err(Error::parse(column, "a negation applies to an event")),
+            }
+        }
+        Token::Open => {
+            let regex = alternation(parser)?;
+            parser.expect(Token::Close, "missing closing parenthesis")?;
+            Ok(regex)
+        }
+        _ => Err(Error::parse(
+            column,
+            "expected an event, a negated event or a parenthesis",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+
+    use super::Expression;
+
+    //tell if the sequence of labels matches the expression
+    fn matches(expression: &Expression, labels: &[&str]) -> bool {
+        let states = labels
+            .iter()
+            .fold(expression.initial(), |states, label| expression.step(&states, label));
+        expression.accepts(&states)
+    }
+
+    #[test]
+    fn regular_expression() {
+        let expression = Expression::parse(r#""hello/Ack" . (!"open*/*")* . "read_req/ReadRepOK""#).unwrap();
+        assert_eq!(expression.atoms.len(), 3);
+        assert!(matches(&expression, &["hello/Ack", "read_req/ReadRepOK"]));
+        assert!(matches(
+            &expression,
+            &["hello/Ack", "close/ok", "ping/pong", "read_req/ReadRepOK"]
+        ));
+        assert!(!matches(
+            &expression,
+            &["hello/Ack", "open_session/ok", "read_req/ReadRepOK"]
+        ));
+        assert!(!matches(&expression, &["hello/Ack"]));
+        assert_eq!(expression.distance(&expression.initial()), 2);
+        assert_eq!(expression.expected(&expression.initial()), vec!["\"hello/Ack\""]);
+
+        let expression = Expression::parse(r#"("a/*" | "b/*"){2,3} "c/*"+"#).unwrap();
+        assert!(!matches(&expression, &["a/x", "c/x"]));
+        assert!(matches(&expression, &["a/x", "b/x", "c/x"]));
+        assert!(matches(&expression, &["a/x", "b/x", "a/x", "c/x", "c/x"]));
+        assert!(!matches(&expression, &["a/x", "b/x", "a/x", "b/x", "c/x"]));
+        let expression = Expression::parse(r#""a/*"{2,} "b/*"?"#).unwrap();
+        assert!(!matches(&expression, &["a/x"]));
+        assert!(matches(&expression, &["a/x", "a/x", "a/x", "b/x"]));
+        //the same event written twice is one atom
+        let expression = Expression::parse(r#""a/b" "a/b"?"#).unwrap();
+        assert_eq!(expression.atoms.len(), 1);
+        assert!(matches(&expression, &["a/b", "a/b"]));
+        assert!(!matches(&expression, &["a/b", "a/b", "a/b"]));
+
+        let error = |text: &str| match Expression::parse(text) {
+            Err(Error::Parse { location, message }) => (location.column, message),
+            other => panic!("expected a parsing error, got {:?}", other),
+        };
+        assert_eq!(
+            error(r#""a/b"*"#),
+            (1, "the expression matches the empty trace".to_string())
+        );
+        assert_eq!(
+            error(r#""a/b"{3,2}"#),
+            (
+                6,
+                "the maximum number of repetitions is lower than the minimum".to_string()
+            )
+        );
+        assert_eq!(
+            error(r#""a/b" . !("c/d")"#),
+            (10, "a negation applies to an event".to_string())
+        );
+        assert_eq!(
+            error(r#"("a/b" | "c/d""#),
+            (15, "missing closing parenthesis".to_string())
+        );
+        assert_eq!(
+            error(r#""a/b" a/b"#),
+            (
+                7,
+                "unknown operator a, events are written between double quotes".to_string()
+            )
+        );
+    }
+}
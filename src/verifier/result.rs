@@ -0,0 +1,179 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::{
+    error::Error,
+    graph::{edgemap::EdgeMap, prelude::*},
+};
+
+use super::{rules::Severity, trace::Trace};
+
+/// A transition of the Mealy machine reported by a rule.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub struct Transition {
+    pub source: String,
+    pub destination: String,
+    pub label: String,
+}
+
+impl Transition {
+    pub fn new(source: &NodeId, destination: &NodeId, label: &str) -> Self {
+        Transition {
+            source: source.to_string(),
+            destination: destination.to_string(),
+            label: label.to_string(),
+        }
+    }
+
+    /// Transitions of a multiedge selected by the indexes of their labels.
+    pub(crate) fn from_indexes(edge: &MultiEdge, indexes: &[usize]) -> Vec<Self> {
+        let labels = edge.get_labels();
+        indexes
+            .iter()
+            .filter_map(|index| labels.get(*index))
+            .map(|label| Transition::new(edge.get_source(), edge.get_dest(), label))
+            .collect()
+    }
+}
+
+impl std::fmt::Display for Transition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} -> {} [{}]", self.source, self.destination, self.label)
+    }
+}
+
+/// Verdict of one rule on one graph.
+#[derive(Debug, Clone)]
+pub struct RuleResult {
+    pub rule: String,
+    pub holds: bool,
+    /// transitions violating the rule
+    pub violations: Vec<Transition>,
+    /// files written in the output folder for this rule
+    pub counterexamples: Vec<PathBuf>,
+    /// shortest traces from the initial state to the violations, for the rules able to give them
+    pub traces: Vec<Trace>,
+    /// transitions showing the rule holds, for the rules asserting the existence of a behaviour
+    pub witness: Vec<Transition>,
+    /// states violating the rule, for the rules on states
+    pub states: Vec<String>,
+    /// severity given in the header of the rule
+    pub severity: Option<Severity>,
+    /// patterns matching no symbol of the graph and premises never occurring, the verdict is still given
+    pub warnings: Vec<String>,
+}
+
+impl RuleResult {
+    /// Build the verdict from the violating transitions, the rule holds when there is none.
+    pub fn from_violations(rule: &str, mut violations: Vec<Transition>) -> Self {
+        violations.sort();
+        violations.dedup();
+        RuleResult {
+            rule: rule.to_string(),
+            holds: violations.is_empty(),
+            violations,
+            counterexamples: Vec::new(),
+            traces: Vec::new(),
+            witness: Vec::new(),
+            states: Vec::new(),
+            severity: None,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Build the verdict from a witness of the rule, the rule holds when there is one.
+    pub(crate) fn from_witness(rule: &str, witness: Option<Vec<Transition>>) -> Self {
+        RuleResult {
+            rule: rule.to_string(),
+            holds: witness.is_some(),
+            violations: Vec::new(),
+            counterexamples: Vec::new(),
+            traces: Vec::new(),
+            witness: witness.unwrap_or_default(),
+            states: Vec::new(),
+            severity: None,
+            warnings: Vec::new(),
+        }
+    }
+
+    pub(crate) fn from_edges<'a>(rule: &str, edges: impl IntoIterator<Item = &'a (&'a MultiEdge, Vec<usize>)>) -> Self {
+        let violations = edges
+            .into_iter()
+            .flat_map(|(edge, indexes)| Transition::from_indexes(edge, indexes))
+            .collect();
+        Self::from_violations(rule, violations)
+    }
+
+    pub(crate) fn from_edge_map(rule: &str, edges: &EdgeMap) -> Self {
+        let violations = edges
+            .values()
+            .flat_map(|edges| edges.values())
+            .flat_map(|edge| {
+                edge.get_label_iterator()
+                    .map(|label| Transition::new(edge.get_source(), edge.get_dest(), label))
+            })
+            .collect();
+        Self::from_violations(rule, violations)
+    }
+
+    /// Build the verdict from the states violating the rule, the rule holds when there is none.
+    pub(crate) fn from_states(rule: &str, states: Vec<String>) -> Self {
+        let mut result = Self::from_violations(rule, Vec::new());
+        result.holds = states.is_empty();
+        result.states = states;
+        result
+    }
+
+    /// Keep the traces and the files they were written in.
+    pub(crate) fn add_traces(&mut self, traces: Vec<Trace>, paths: Vec<PathBuf>) {
+        self.traces = traces;
+        self.counterexamples.extend(paths);
+    }
+
+    pub(crate) fn add_counterexample(&mut self, path: Option<PathBuf>) {
+        if let Some(path) = path {
+            self.counterexamples.push(path);
+        }
+    }
+}
+
+/// Verdicts of every rule on one graph.
+#[derive(Debug)]
+pub struct GraphResult {
+    pub graph: String,
+    /// implementations sharing the Mealy machine, empty when they are not known
+    pub implementations: Vec<String>,
+    pub rules: Vec<RuleResult>,
+    /// rules that could not be applied on the graph
+    pub errors: Vec<Error>,
+    /// problems of the Mealy machine found when it was loaded, the rules are still applied
+    pub warnings: Vec<String>,
+}
+
+impl GraphResult {
+    pub fn holds(&self) -> bool {
+        self.errors.is_empty() && self.rules.iter().all(|r| r.holds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{multi_edge::MultiEdge, nodeid::NodeId};
+
+    use super::{RuleResult, Transition};
+
+    #[test]
+    fn result_from_edges() {
+        let edge = MultiEdge::new(r#""0" -> "1" [fontsize=5, label="a/b", color=black];"#);
+        let edges = [(&edge, vec![0])];
+        let result = RuleResult::from_edges("test", edges.iter());
+        assert!(!result.holds);
+        assert_eq!(
+            result.violations,
+            vec![Transition::new(&NodeId::new("0"), &NodeId::new("1"), "a/b")]
+        );
+        let result = RuleResult::from_violations("test", Vec::new());
+        assert!(result.holds);
+    }
+}
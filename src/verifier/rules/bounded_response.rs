@@ -0,0 +1,249 @@
+use crate::{
+    error::{column, Error, Result},
+    utils,
+    verifier::{
+        event::Events,
+        lint,
+        result::Transition,
+        trace::{self, Trace},
+    },
+};
+
+use super::{Graph, Rule, RuleResult};
+#[cfg(test)]
+use std::any::Any;
+
+/// After a trigger event, a response event has to come within a bound of transitions on every path,
+/// unless an abort event comes first.
+#[derive(Debug)]
+pub(crate) struct BoundedResponse {
+    name: String,
+    trigger: Events,
+    response: Events,
+    abort: Option<Events>,
+    bound: usize,
+}
+
+impl BoundedResponse {
+    fn abort(&self, event_str: &str) -> bool {
+        match &self.abort {
+            Some(abort) => abort.check_all(event_str),
+            None => false,
+        }
+    }
+
+    //state after a transition: the number of transitions left to see the response, None when no trigger is pending.
+    //true when the transition uses the last one, or leads to a state without outgoing transition
+    fn update_state(&self, pending: Option<usize>, event_str: &str, deadlock: bool) -> (bool, Option<usize>) {
+        let pending = match pending {
+            Some(_) if self.response.check_all(event_str) || self.abort(event_str) => None,
+            Some(1) => return (true, None),
+            Some(left) => Some(left - 1),
+            None => None,
+        };
+        //a new trigger does not postpone the response to an earlier one
+        let pending = if self.trigger.check_all(event_str) {
+            Some(pending.unwrap_or(self.bound))
+        } else {
+            pending
+        };
+        (deadlock && pending.is_some(), pending)
+    }
+
+    fn shortest_traces(&self, graph: &Graph) -> Vec<Trace> {
+        trace::shortest_traces(graph, None, |pending, neighbor_id, label| {
+            let deadlock = graph.neighbors_edges_iterator(neighbor_id).is_empty();
+            match self.update_state(*pending, label, deadlock) {
+                (true, _) => (true, None),
+                (false, pending) => (false, Some(pending)),
+            }
+        })
+    }
+}
+
+impl Rule for BoundedResponse {
+    fn from_reader(reader: &mut dyn crate::utils::reader::Reader, name: String) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let mut trigger: Option<Events> = None;
+        let mut response: Option<Events> = None;
+        let mut abort: Option<Events> = None;
+        let mut bound: Option<usize> = None;
+        while let Some(raw_line) = reader.read_line() {
+            let line = raw_line.trim_start();
+            if line.starts_with("T:") || line.starts_with("R:") || line.starts_with("A:") || line.starts_with("N:") {
+                let split_line: Vec<_> = line.split(":").collect();
+                if split_line.len() != 2 {
+                    return Err(Error::parse(
+                        column(raw_line, line),
+                        format!("failed to parse BoundedResponse events: {}", line.trim()),
+                    ));
+                }
+                let value = split_line[1].trim();
+                if line.starts_with("N:") {
+                    bound = match value.parse() {
+                        Ok(bound) if bound > 0 => Some(bound),
+                        _ => {
+                            return Err(Error::parse(
+                                column(raw_line, value),
+                                format!("the bound is a positive number of transitions, not {}", value),
+                            ))
+                        }
+                    };
+                    continue;
+                }
+                let events = Events::from_str(value).map_err(|e| e.at_column(column(raw_line, value)))?;
+                if line.starts_with("T:") {
+                    trigger = Some(events);
+                } else if line.starts_with("R:") {
+                    response = Some(events);
+                } else {
+                    abort = Some(events);
+                }
+            } else if line.starts_with(":BR") {
+                break;
+            } else {
+                return Err(Error::parse(
+                    column(raw_line, line),
+                    format!("failed to parse until Rule:{}", line.trim()),
+                ));
+            }
+        }
+        let trigger = match trigger {
+            Some(trigger) => trigger,
+            None => {
+                return Err(Error::parse(
+                    0,
+                    "you must specify trigger event for bounded response rule",
+                ))
+            }
+        };
+        let response = match response {
+            Some(response) => response,
+            None => {
+                return Err(Error::parse(
+                    0,
+                    "you must specify response event for bounded response rule",
+                ))
+            }
+        };
+        let bound = match bound {
+            Some(bound) => bound,
+            None => return Err(Error::parse(0, "you must specify the bound for bounded response rule")),
+        };
+        Ok(BoundedResponse {
+            name,
+            trigger,
+            response,
+            abort,
+            bound,
+        })
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn lint(&self, graph: &Graph) -> Vec<String> {
+        let abort = self.abort.iter().flat_map(Events::iter);
+        let mut warnings = lint::unmatched(graph, self.trigger.iter().chain(self.response.iter()).chain(abort));
+        let trigger = format!("trigger {}", self.trigger);
+        warnings.extend(lint::never_occurs(graph, trigger, |label| {
+            self.trigger.check_all(label)
+        }));
+        warnings
+    }
+
+    fn apply(&mut self, graph: &super::Graph, output_folder: &mut std::path::PathBuf) -> Result<RuleResult> {
+        if graph.get_root().is_none() {
+            return Err(Error::graph(graph.get_name(), "no starting state on the mealy machine"));
+        }
+        let traces = self.shortest_traces(graph);
+        let violations = traces.iter().map(|trace| trace.violation().clone()).collect();
+        let mut result = RuleResult::from_violations(self.get_name(), violations);
+        output_folder.push(self.get_name());
+        if let Err(e) = std::fs::create_dir_all(&output_folder) {
+            return Err(Error::io(output_folder.clone(), e));
+        }
+        //the paths from the trigger to the transition exceeding the bound
+        let transitions: Vec<Transition> = traces.iter().flat_map(|t| t.transitions.iter().cloned()).collect();
+        match utils::output::write_transitions(graph, &transitions, output_folder, "ce.dot") {
+            Ok(path) => result.add_counterexample(path),
+            Err(e) => return Err(Error::io(output_folder.clone(), e)),
+        }
+        match utils::output::write_traces(&traces, output_folder) {
+            Ok(paths) => result.add_traces(traces, paths),
+            Err(e) => return Err(Error::io(output_folder.clone(), e)),
+        }
+        output_folder.pop();
+        Ok(result)
+    }
+
+    #[cfg(test)]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        graph::Graph,
+        utils::reader::test_reader::TestReader,
+        verifier::rules::{parse_reader, parse_rule_from_str},
+    };
+
+    use super::BoundedResponse;
+
+    #[test]
+    fn test_bounded_response() {
+        let input = r#"digraph "Automata" {
+            "0" [shape=doublecircle];
+            "0" -> "1" [label="open/OPEN_SUCCESS"];
+            "1" -> "2" [label="data/ok"];
+            "1" -> "3" [label="bye/DISCONNECT"];
+            "2" -> "0" [label="close/CH_CLOSE"];
+            "2" -> "4" [label="data/ok"];
+            "4" -> "0" [label="close/CH_CLOSE"];
+        }"#;
+        //0 -open-> 1 -data-> 2 -close-> 0, 2 -data-> 4 -close-> 0, 1 -bye-> 3 without outgoing transition
+        let graph = Graph::new(input, true);
+        let traces = |rule: &str| {
+            let rule = parse_rule_from_str(rule);
+            let rule = rule[0]
+                .as_any()
+                .downcast_ref::<BoundedResponse>()
+                .expect("expect BR rule");
+            rule.shortest_traces(&graph)
+        };
+        let rule = |bound: usize, abort: &str| {
+            format!(
+                "BR:close\nT: open/OPEN_SUCCESS\nR: */CH_CLOSE\n{}N: {}\n:BR",
+                abort, bound
+            )
+        };
+        //the second data exceeds the bound
+        let found = traces(&rule(2, "A: */DISCONNECT\n"));
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].inputs, vec!["open", "data", "data"]);
+        assert!(traces(&rule(3, "A: */DISCONNECT\n")).is_empty());
+        //without abort event, the disconnection never closes the channel
+        let found = traces(&rule(3, ""));
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].inputs, vec!["open", "bye"]);
+
+        let rules = "BR:zero\nT: a/b\nR: c/d\nN: 0\n:BR\nBR:no_response\nT: a/b\nN: 2\n:BR";
+        let mut reader = TestReader::from_text(rules).unwrap();
+        let (rules, errors) = parse_reader(&mut reader, "rules");
+        assert!(rules.is_empty());
+        let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "rules:4:4: the bound is a positive number of transitions, not 0",
+                "rules:9:1: you must specify response event for bounded response rule",
+            ]
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::HashSet;
+
+use super::{Rule, RuleResult};
+use crate::{
+    error::{Error, Result},
+    graph::{multi_edge::MultiEdge, nodeid::NodeId, Graph},
+    utils::{self, reader::Reader},
+    verifier::{
+        ctl::{self, Ctl},
+        lint,
+        temporal::Formula,
+        trace,
+    },
+};
+#[cfg(test)]
+use std::any::Any;
+
+#[derive(Debug)]
+pub(crate) struct BranchingTemporal {
+    pub(crate) formula: Formula<Ctl>,
+    pub(crate) name: String,
+}
+
+impl BranchingTemporal {
+    //states reachable from the initial state where the formula does not hold, none if it holds in the initial state
+    fn inner_apply(&self, graph: &Graph) -> Result<Vec<NodeId>> {
+        let root = match graph.get_root() {
+            Some(root) => root,
+            None => return Err(Error::graph(graph.get_name(), "no starting state on the mealy machine")),
+        };
+        let holds = self.formula.check(graph);
+        if holds.get(root).copied().unwrap_or(false) {
+            return Ok(Vec::new());
+        }
+        let reachable = trace::shortest_paths(graph);
+        Ok(holds
+            .into_iter()
+            .filter(|(id, holds)| !holds && reachable.contains_key(id))
+            .map(|(id, _)| id)
+            .collect())
+    }
+}
+
+impl Rule for BranchingTemporal {
+    fn from_reader(reader: &mut dyn Reader, name: String) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let formula = super::read_formula(reader, "CTL", ctl::parse)?;
+        Ok(BranchingTemporal { formula, name })
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn lint(&self, graph: &Graph) -> Vec<String> {
+        lint::unmatched(graph, self.formula.atoms.iter())
+    }
+
+    fn apply(&mut self, graph: &Graph, output_folder: &mut std::path::PathBuf) -> Result<RuleResult> {
+        let states = self.inner_apply(graph)?;
+        let mut result = RuleResult::from_states(self.get_name(), states.iter().map(|s| s.to_string()).collect());
+        output_folder.push(self.get_name());
+        if let Err(e) = std::fs::create_dir_all(&output_folder) {
+            return Err(Error::io(output_folder.clone(), e));
+        }
+        //the violating states with the transitions between them
+        let nodes: HashSet<NodeId> = states.into_iter().collect();
+        let edges: HashSet<(&MultiEdge, Vec<usize>)> = graph
+            .iter_edges()
+            .into_iter()
+            .filter(|edge| nodes.contains(edge.get_source()) && nodes.contains(edge.get_dest()))
+            .map(|edge| (edge, (0..edge.get_labels().len()).collect()))
+            .collect();
+        match utils::output::write_files(graph, nodes, edges, output_folder) {
+            Ok(path) => result.add_counterexample(path),
+            Err(e) => return Err(Error::io(output_folder.clone(), e)),
+        }
+        match utils::output::write_states(&result.states, output_folder) {
+            Ok(path) => result.add_counterexample(path),
+            Err(e) => return Err(Error::io(output_folder.clone(), e)),
+        }
+        output_folder.pop();
+        Ok(result)
+    }
+
+    #[cfg(test)]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        graph::Graph,
+        utils::reader::test_reader::TestReader,
+        verifier::rules::{parse_reader, parse_rule_from_str},
+    };
+
+    use super::BranchingTemporal;
+
+    #[test]
+    fn test_branching_temporal() {
+        let input = r#"digraph "Automata" {
+            "0" [shape=doublecircle];
+            "0" -> "1" [label="auth/ok"];
+            "0" -> "0" [label="disconnect/ok"];
+            "1" -> "2" [label="read/data"];
+            "1" -> "0" [label="disconnect/ok"];
+            "2" -> "2" [label="read/data"];
+            "2" -> "3" [label="reset/none"];
+        }"#;
+        //  0 ─auth─▶ 1 ─read─▶ 2 ─reset─▶ 3
+        //  ▲         │         ↺ read
+        //  └disconnect┘
+        //disconnect loop on 0
+        let graph = Graph::new(input, true);
+        let states = |rule: &str| {
+            let rule = parse_rule_from_str(rule);
+            let rule = rule[0]
+                .as_any()
+                .downcast_ref::<BranchingTemporal>()
+                .expect("expect CTL rule");
+            let states: Vec<String> = rule
+                .inner_apply(&graph)
+                .unwrap()
+                .iter()
+                .map(|s| s.to_string())
+                .collect();
+            states
+        };
+        //once the client read, it can not disconnect anymore, hence no state able to read can disconnect forever
+        assert_eq!(
+            states("CTL:disconnect_possible\nAG EF \"disconnect/*\"\n:CTL"),
+            vec!["0", "1", "2", "3"]
+        );
+        assert!(states("CTL:auth_possible\nEX \"auth/*\"\n:CTL").is_empty());
+        assert_eq!(
+            states("CTL:read_after_auth:high\nAG(\"read/*\" -> AX \"read/*\")\n:CTL"),
+            vec!["0", "1", "2"]
+        );
+
+        let rules = "CTL:unknown\n  AG \"a/b\" W\n:CTL\nCTL:bracket\nE[\"a/b\" U \"c/d\"\n:CTL";
+        let mut reader = TestReader::from_text(rules).unwrap();
+        let (rules, errors) = parse_reader(&mut reader, "rules");
+        assert!(rules.is_empty());
+        let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "rules:2:10: unknown operator W, events are written between double quotes",
+                "rules:5:16: missing closing bracket",
+            ]
+        );
+    }
+}
@@ -1,12 +1,17 @@
 use super::super::event::Event;
 use super::super::premise::Premise;
 use super::Rule;
+use crate::error::{column, Error, Result};
 use crate::graph::edgemap::EdgeMap;
 use crate::graph::element::{Element, Elements};
 use crate::graph::node::Node;
 use crate::graph::prelude::*;
+use crate::utils;
+use crate::verifier::lint;
+use crate::verifier::result::{RuleResult, Transition};
+use crate::verifier::trace::{self, Trace};
 use indexmap::IndexSet;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs::{self, File};
 use std::io::Write;
 use std::path::PathBuf;
@@ -14,6 +19,11 @@ use std::rc::Rc;
 
 #[cfg(test)]
 use std::any::Any;
+
+//node, neighbors to explore, label used to reach the node, state of the premises
+type ExecutionStack<'a> = Vec<(NodeId, Vec<(NodeId, &'a MultiEdge)>, Option<Rc<str>>, (usize, bool))>;
+//node, state of the premises
+type BackwardState = (NodeId, usize, bool);
 #[derive(Debug)]
 pub(crate) struct Conditional {
     name: String,
@@ -22,19 +32,20 @@ pub(crate) struct Conditional {
 }
 
 impl Rule for Conditional {
-    fn from_reader(reader: &mut dyn crate::utils::reader::Reader, name: String) -> std::io::Result<Self>
+    fn from_reader(reader: &mut dyn crate::utils::reader::Reader, name: String) -> Result<Self>
     where
         Self: Sized,
     {
         let mut rule = Self::empty();
         rule.name = name;
         while let Some(line) = reader.read_line() {
+            let trimmed = line.trim();
             if line.starts_with(":CT") {
                 break;
             } else if line.contains('|') {
-                rule.add_premise(&line);
+                rule.add_premise(line).map_err(|e| e.at_column(column(line, trimmed)))?;
             } else {
-                rule.add_action(&line);
+                rule.add_action(line).map_err(|e| e.at_column(column(line, trimmed)))?;
             }
         }
         Ok(rule)
@@ -44,17 +55,46 @@ impl Rule for Conditional {
         &self.name
     }
 
-    fn apply(&mut self, graph: &Graph, output_folder: &mut PathBuf) {
+    fn lint(&self, graph: &Graph) -> Vec<String> {
+        let premises = self.premises.iter().flat_map(Premise::events);
+        let mut warnings = lint::unmatched(graph, std::iter::once(&self.action).chain(premises));
+        let action = format!("action {}", self.action);
+        warnings.extend(lint::never_occurs(graph, action, |label| self.action.check(label)));
+        warnings
+    }
+
+    fn apply(&mut self, graph: &Graph, output_folder: &mut PathBuf) -> Result<RuleResult> {
         let action_node = self.find_node_action(graph);
         output_folder.push(self.get_name());
         if let Err(e) = fs::create_dir_all(&output_folder) {
-            panic!("failed to create output directory due to :{}", e.to_string());
+            return Err(Error::io(output_folder.clone(), e));
         }
+        let mut violations = Vec::new();
+        let mut counterexamples = Vec::new();
+        let mut traces = Vec::new();
+        let paths_from_root = trace::shortest_paths(graph);
         for (index, node_id) in action_node.iter().enumerate() {
-            let (nodes, edges) = self.inner_apply(graph, &node_id);
-            self.write_file(nodes, edges, output_folder, index, &node_id).unwrap();
+            let (nodes, edges) = self.inner_apply(graph, node_id);
+            if !nodes.is_empty() && !edges.is_empty() {
+                violations.extend(self.action_transitions(graph, node_id));
+                traces.extend(self.shortest_traces(graph, node_id, &paths_from_root));
+            }
+            match self.write_file(nodes, edges, output_folder, index, node_id) {
+                Ok(path) => counterexamples.push(path),
+                Err(e) => return Err(Error::io(output_folder.clone(), e)),
+            }
+        }
+        let mut result = RuleResult::from_violations(self.get_name(), violations);
+        for path in counterexamples {
+            result.add_counterexample(path);
+        }
+        traces.sort_by(|a: &Trace, b: &Trace| a.violation().cmp(b.violation()));
+        match utils::output::write_traces(&traces, output_folder) {
+            Ok(paths) => result.add_traces(traces, paths),
+            Err(e) => return Err(Error::io(output_folder.clone(), e)),
         }
         output_folder.pop();
+        Ok(result)
     }
 
     #[cfg(test)]
@@ -72,12 +112,14 @@ impl Conditional {
         }
     }
 
-    fn add_premise(&mut self, premise_str: &str) {
-        self.premises.push(Premise::new(premise_str));
+    fn add_premise(&mut self, premise_str: &str) -> Result<()> {
+        self.premises.push(Premise::new(premise_str)?);
+        Ok(())
     }
 
-    fn add_action(&mut self, action_str: &str) {
-        self.action = Event::new(action_str);
+    fn add_action(&mut self, action_str: &str) -> Result<()> {
+        self.action = Event::new(action_str)?;
+        Ok(())
     }
 
     fn find_node_action(&self, graph: &Graph) -> Vec<NodeId> {
@@ -90,22 +132,105 @@ impl Conditional {
         result
     }
 
+    //transitions matching the action from a node
+    fn action_transitions(&self, graph: &Graph, node_id: &NodeId) -> Vec<Transition> {
+        let mut result = Vec::new();
+        for (_, edge) in graph.neighbors_edges_iterator(node_id) {
+            for label in edge.get_label_iterator() {
+                if self.action.check(label) {
+                    result.push(Transition::new(edge.get_source(), edge.get_dest(), label));
+                }
+            }
+        }
+        result
+    }
+
+    //Shortest traces to the actions of a node violating the rule.
+    //The premises are checked backward from the action node as in inner_apply,
+    //the backward path is then completed by the shortest path from the root.
+    fn shortest_traces(
+        &self,
+        graph: &Graph,
+        action_node_id: &NodeId,
+        paths_from_root: &HashMap<NodeId, Vec<Transition>>,
+    ) -> Vec<Trace> {
+        let start = (action_node_id.clone(), self.premises.len(), false);
+        //state of the backward search -> next state toward the action node
+        let mut next: HashMap<BackwardState, Option<(BackwardState, Transition)>> = HashMap::new();
+        let mut queue = VecDeque::new();
+        let mut best: Option<(usize, BackwardState)> = None;
+        next.insert(start.clone(), None);
+        queue.push_back((start, 0));
+        while let Some((current, depth)) = queue.pop_front() {
+            if best.as_ref().is_some_and(|(length, _)| depth >= *length) {
+                break;
+            }
+            let (node_id, state_index, state_counter_event) = &current;
+            let prefix_length = if *state_index == 0 && *state_counter_event {
+                paths_from_root.get(node_id).map(|path| path.len())
+            } else if graph.is_starting_node(node_id) && (*state_index != 0 || *state_counter_event) {
+                Some(0)
+            } else {
+                None
+            };
+            if let Some(prefix_length) = prefix_length {
+                if best.as_ref().is_none_or(|(length, _)| depth + prefix_length < *length) {
+                    best = Some((depth + prefix_length, current.clone()));
+                }
+            }
+            let mut neighbors = graph.neighbors_tranposed_edges(node_id);
+            neighbors.sort_by_key(|(a, _)| a.to_string());
+            for (neighbor_id, edge) in neighbors {
+                for label in edge.get_label_iterator() {
+                    let (new_state_index, mut new_state_ce) = self.check_label_on_state(*state_index, label);
+                    new_state_ce = new_state_ce || *state_counter_event;
+                    let neighbor_state = (neighbor_id.clone(), new_state_index, new_state_ce);
+                    if (new_state_index != 0 || new_state_ce) && !next.contains_key(&neighbor_state) {
+                        let transition = Transition::new(&neighbor_id, node_id, label);
+                        next.insert(neighbor_state.clone(), Some((current.clone(), transition)));
+                        queue.push_back((neighbor_state, depth + 1));
+                    }
+                }
+            }
+        }
+        let Some((_, mut current)) = best else {
+            return Vec::new();
+        };
+        let mut path = if current.1 == 0 && current.2 {
+            paths_from_root[&current.0].clone()
+        } else {
+            Vec::new()
+        };
+        while let Some(Some((next_state, transition))) = next.get(&current) {
+            path.push(transition.clone());
+            current = next_state.clone();
+        }
+        self.action_transitions(graph, action_node_id)
+            .into_iter()
+            .map(|action| {
+                let mut transitions = path.clone();
+                transitions.push(action);
+                Trace::new(transitions)
+            })
+            .collect()
+    }
+
     fn add_nodes_and_edges(
         graph: &Graph,
         path_node: &IndexSet<(NodeId, usize, bool, Option<Rc<str>>)>,
         output_node_id: &mut HashSet<(NodeId, usize, bool)>,
         output_node: &mut HashSet<Node>,
         output_edges: &mut EdgeMap,
-        extra_node:Option<(NodeId, usize, bool,Rc<str>)>,
+        extra_node: Option<(NodeId, usize, bool, Rc<str>)>,
     ) {
         for chunk in path_node.iter().collect::<Vec<_>>().windows(2) {
             if chunk.len() == 2 {
                 //build nodes
                 let (node_id_to_add, index_to_add, bool_counter_event_to_add, _) = &chunk[0];
-                let from = Self::node_add_label(&graph, &node_id_to_add, *index_to_add, *bool_counter_event_to_add);
+                let from = Self::node_add_label(graph, node_id_to_add, *index_to_add, *bool_counter_event_to_add);
                 output_node_id.insert((node_id_to_add.clone(), *index_to_add, *bool_counter_event_to_add));
                 let (node_id_to_add, index_to_add, bool_counter_event_to_add, label) = &chunk[1];
-                let to = Self::node_add_label(&graph, &node_id_to_add, *index_to_add, *bool_counter_event_to_add);
+                let to = Self::node_add_label(graph, node_id_to_add, *index_to_add, *bool_counter_event_to_add);
                 output_node_id.insert((node_id_to_add.clone(), *index_to_add, *bool_counter_event_to_add));
 
                 let from_id = from.get_node_id().clone();
@@ -117,81 +242,92 @@ impl Conditional {
                 output_node.insert(to);
 
                 //add the corresponding edge
-                let edge = MultiEdge::from(from_id, to_id, &label, Elements::default_edge(&label));
+                let edge = MultiEdge::from(from_id, to_id, label, Elements::default_edge(label));
                 output_edges.add_edge(&edge);
             }
         }
-        if let Some((to_id,index,bool_counter_event,label)) =extra_node{
-            let (from_id,from_index,from_bool_counter_event,_) =path_node.last().unwrap();
-            let to_id=Self::make_node_id(&to_id,index,bool_counter_event);
-            let from_id=Self::make_node_id(from_id,*from_index,*from_bool_counter_event);
+        if let Some((to_id, index, bool_counter_event, label)) = extra_node {
+            let (from_id, from_index, from_bool_counter_event, _) = path_node.last().unwrap();
+            let to_id = Self::make_node_id(&to_id, index, bool_counter_event);
+            let from_id = Self::make_node_id(from_id, *from_index, *from_bool_counter_event);
             let edge = MultiEdge::from(from_id.clone(), to_id, &label, Elements::default_edge(&label));
             output_edges.add_edge(&edge);
         }
     }
 
-    fn inner_apply_add_cycle(&self,graph:&Graph,action_node_id: &NodeId,mut output_node:HashSet<Node>,mut output_node_id:HashSet<(NodeId, usize, bool)>,mut output_edges:EdgeMap)->(HashSet<Node>, EdgeMap) {
+    fn inner_apply_add_cycle(
+        &self,
+        graph: &Graph,
+        action_node_id: &NodeId,
+        mut output_node: HashSet<Node>,
+        mut output_node_id: HashSet<(NodeId, usize, bool)>,
+        mut output_edges: EdgeMap,
+    ) -> (HashSet<Node>, EdgeMap) {
         let mut visited: HashSet<(NodeId, usize, bool)> = HashSet::new();
-        let mut execution_stack: Vec<(NodeId, Vec<(NodeId, &MultiEdge)>, Option<Rc<str>>, (usize, bool))> = Vec::new();
+        let mut execution_stack: ExecutionStack = Vec::new();
         let mut path_node: IndexSet<(NodeId, usize, bool, Option<Rc<str>>)> = IndexSet::new();
         let neighbors = graph.neighbors_tranposed_edges(action_node_id);
         execution_stack.push((action_node_id.clone(), neighbors, None, (self.premises.len(), false)));
         while let Some((node_id, neighbors, from_label, (state_index, state_counter_event))) =
-        execution_stack.last_mut()
-    {
-        path_node.insert((node_id.clone(), *state_index, *state_counter_event, from_label.clone()));
-        visited.insert((node_id.clone(), *state_index, *state_counter_event));
-        //explore neighbors
-        if let Some((neighbor_id, edge_to_reach_neighbor_id)) = neighbors.pop() {
-            //we check the transition to neighbors to see how the state evolved
-
-            let state_index_copy = state_index.clone();
-            let state_counter_event_copy = state_counter_event.clone();
-            for label in edge_to_reach_neighbor_id.get_label_iterator() {
-                let (new_state_index, mut new_state_ce) = self.check_label_on_state(state_index_copy, label);
-                new_state_ce = new_state_ce || state_counter_event_copy;
-                if visited.contains(&(neighbor_id.clone(), new_state_index, new_state_ce))
-                    && output_node_id.contains(&(neighbor_id.clone(), new_state_index, new_state_ce))
-                    && !self.action.check(label)
-                {
-                    let mut extra_node=None;
-                    if !path_node.insert((neighbor_id.clone(), new_state_index, new_state_ce, Some(label.clone()))){
-                        extra_node=Some((neighbor_id.clone(), new_state_index, new_state_ce, label.clone()));
+            execution_stack.last_mut()
+        {
+            path_node.insert((node_id.clone(), *state_index, *state_counter_event, from_label.clone()));
+            visited.insert((node_id.clone(), *state_index, *state_counter_event));
+            //explore neighbors
+            if let Some((neighbor_id, edge_to_reach_neighbor_id)) = neighbors.pop() {
+                //we check the transition to neighbors to see how the state evolved
+
+                let state_index_copy = *state_index;
+                let state_counter_event_copy = *state_counter_event;
+                for label in edge_to_reach_neighbor_id.get_label_iterator() {
+                    let (new_state_index, mut new_state_ce) = self.check_label_on_state(state_index_copy, label);
+                    new_state_ce = new_state_ce || state_counter_event_copy;
+                    if visited.contains(&(neighbor_id.clone(), new_state_index, new_state_ce))
+                        && output_node_id.contains(&(neighbor_id.clone(), new_state_index, new_state_ce))
+                        && !self.action.check(label)
+                    {
+                        let mut extra_node = None;
+                        if !path_node.insert((neighbor_id.clone(), new_state_index, new_state_ce, Some(label.clone())))
+                        {
+                            extra_node = Some((neighbor_id.clone(), new_state_index, new_state_ce, label.clone()));
+                        }
+                        Self::add_nodes_and_edges(
+                            graph,
+                            &path_node,
+                            &mut output_node_id,
+                            &mut output_node,
+                            &mut output_edges,
+                            extra_node,
+                        );
+                        path_node.pop();
+                    }
+                    if !visited.contains(&(neighbor_id.clone(), new_state_index, new_state_ce))
+                        && (new_state_index != 0 || new_state_ce)
+                    {
+                        let neighbors = graph.neighbors_tranposed_edges(&neighbor_id);
+                        execution_stack.push((
+                            neighbor_id.clone(),
+                            neighbors,
+                            Some(label.clone()),
+                            (new_state_index, new_state_ce),
+                        ));
                     }
-                    Self::add_nodes_and_edges(
-                        graph,
-                        &path_node,
-                        &mut output_node_id,
-                        &mut output_node,
-                        &mut output_edges,
-                        extra_node,
-                    );
-                    path_node.pop();
-                   
-                }
-                if !visited.contains(&(neighbor_id.clone(), new_state_index, new_state_ce))
-                    && (new_state_index != 0 || new_state_ce)
-                {
-                    let neighbors = graph.neighbors_tranposed_edges(&neighbor_id);
-                    execution_stack.push((
-                        neighbor_id.clone(),
-                        neighbors,
-                        Some(label.clone()),
-                        (new_state_index, new_state_ce),
-                    ));
                 }
+            } else {
+                path_node.pop();
+                execution_stack.pop();
             }
-        } else {
-            path_node.pop();
-            execution_stack.pop();
         }
-    }
-    (output_node, output_edges)
+        (output_node, output_edges)
     }
 
-    fn inner_apply_preleminary(&self, graph: &Graph, action_node_id: &NodeId) -> (HashSet<Node>,HashSet<(NodeId, usize, bool)>, EdgeMap) {
+    fn inner_apply_preleminary(
+        &self,
+        graph: &Graph,
+        action_node_id: &NodeId,
+    ) -> (HashSet<Node>, HashSet<(NodeId, usize, bool)>, EdgeMap) {
         let mut visited: HashSet<(NodeId, usize, bool)> = HashSet::new();
-        let mut execution_stack: Vec<(NodeId, Vec<(NodeId, &MultiEdge)>, Option<Rc<str>>, (usize, bool))> = Vec::new();
+        let mut execution_stack: ExecutionStack = Vec::new();
         let mut path_node: IndexSet<(NodeId, usize, bool, Option<Rc<str>>)> = IndexSet::new();
         let mut output_node: HashSet<Node> = HashSet::new();
         let mut output_node_id: HashSet<(NodeId, usize, bool)> = HashSet::new();
@@ -209,9 +345,7 @@ impl Conditional {
             //if the state is 0 and there no issue then from this state on the path the rule is mandatory true.
             path_node.insert((node_id.clone(), *state_index, *state_counter_event, from_label.clone()));
             visited.insert((node_id.clone(), *state_index, *state_counter_event));
-            if (graph.is_starting_node(node_id) && (*state_index != 0 || *state_counter_event))
-                || (*state_index == 0 && *state_counter_event)
-            {
+            if (*state_counter_event || *state_index != 0) && (*state_index == 0 || graph.is_starting_node(node_id)) {
                 Self::add_nodes_and_edges(
                     graph,
                     &path_node,
@@ -225,8 +359,8 @@ impl Conditional {
             if let Some((neighbor_id, edge_to_reach_neighbor_id)) = neighbors.pop() {
                 //we check the transition to neighbors to see how the state evolved
 
-                let state_index_copy = state_index.clone();
-                let state_counter_event_copy = state_counter_event.clone();
+                let state_index_copy = *state_index;
+                let state_counter_event_copy = *state_counter_event;
                 for label in edge_to_reach_neighbor_id.get_label_iterator() {
                     let (new_state_index, mut new_state_ce) = self.check_label_on_state(state_index_copy, label);
                     new_state_ce = new_state_ce || state_counter_event_copy;
@@ -262,11 +396,11 @@ impl Conditional {
                 execution_stack.pop();
             }
         }
-        (output_node,output_node_id,output_edges)
+        (output_node, output_node_id, output_edges)
     }
 
-    fn inner_apply(&self, graph: &Graph, action_node_id: &NodeId) -> (HashSet<Node>, EdgeMap){
-        let (nodes,nodes_id,edges)=self.inner_apply_preleminary(graph,action_node_id);
+    fn inner_apply(&self, graph: &Graph, action_node_id: &NodeId) -> (HashSet<Node>, EdgeMap) {
+        let (nodes, nodes_id, edges) = self.inner_apply_preleminary(graph, action_node_id);
         self.inner_apply_add_cycle(graph, action_node_id, nodes, nodes_id, edges)
     }
 
@@ -279,7 +413,7 @@ impl Conditional {
         if self.premises[index - 1].check_event(label) {
             return (index - 1, bool_ret);
         }
-        return (index, bool_ret);
+        (index, bool_ret)
     }
 
     //return true if an effective counter event has been found
@@ -304,9 +438,9 @@ impl Conditional {
         output_folder: &mut PathBuf,
         index: usize,
         action_node_id: &NodeId,
-    ) -> std::io::Result<()> {
+    ) -> std::io::Result<Option<PathBuf>> {
         if nodes.is_empty() || edges.is_empty() {
-            return Ok(());
+            return Ok(None);
         }
         output_folder.push(format!("{index}_ce.dot"));
         let mut output = String::from("digraph \"Automata\" { \n");
@@ -316,21 +450,28 @@ impl Conditional {
         };
         let action_node_id_str = format!("{}_{}_{}", action_node_id, self.premises.len(), false);
         for mut node in nodes.into_iter() {
-            let node_line;
             if node.get_node_id() == &action_node_id_str {
                 node.add_element(Element::new_color("color=red").unwrap());
             }
-            node_line = node.to_string();
+            let node_line = node.to_string();
             output.push_str(&node_line)
         }
         let edges_tranposed = edges.transpose();
         output.push_str(&edges_tranposed.to_string());
-        let action_edge=format!("\t\"{}_{}_false\"->\"{}_{}_false\"[color=red,label=\"{}\",fontsize=5];",action_node_id,self.premises.len(),action_node_id,self.premises.len(),self.action);
+        let action_edge = format!(
+            "\t\"{}_{}_false\"->\"{}_{}_false\"[color=red,label=\"{}\",fontsize=5];",
+            action_node_id,
+            self.premises.len(),
+            action_node_id,
+            self.premises.len(),
+            self.action
+        );
         output.push_str(&action_edge);
         output.push_str("}\n");
         file.write_all(output.as_bytes())?;
+        let path = output_folder.clone();
         output_folder.pop();
-        Ok(())
+        Ok(Some(path))
     }
 
     //add label for the node. Because in the output we want several node to have the same name.
@@ -346,10 +487,10 @@ impl Conditional {
         panic!("failed to retrieve a node, in conditional");
     }
 
-    fn make_node_id_string(node_id:&NodeId,index:usize,counter_event: bool)->String{
+    fn make_node_id_string(node_id: &NodeId, index: usize, counter_event: bool) -> String {
         format!("{}_{}_{}", node_id, index, counter_event)
     }
-    fn make_node_id(node_id:&NodeId,index:usize,counter_event: bool)->NodeId{
+    fn make_node_id(node_id: &NodeId, index: usize, counter_event: bool) -> NodeId {
         NodeId::new(&format!("{}_{}_{}", node_id, index, counter_event))
     }
 }
@@ -364,7 +505,7 @@ mod test {
         verifier::rules::parse_rule_from_str,
     };
 
-    use super::{Conditional, Graph, NodeId};
+    use super::{trace, Conditional, Graph, NodeId};
 
     #[test]
     fn conditional_test() {
@@ -474,7 +615,7 @@ mod test {
         "0_2_false"->"1_2_false" [fontsize=5,label="osef/osef"];
         "0_2_false"->"1_2_false" [fontsize=5,label="p1/p1"];
         "b1_1_true"->"b2_2_true" [fontsize=5,label="p2/p2"];"#;
-        let edges_expected = edges_expected.split(";\n").into_iter().map(|e| e.trim());
+        let edges_expected = edges_expected.split(";\n").map(|e| e.trim());
         let mut expected_edge_map = EdgeMap::new();
         for edge_str in edges_expected {
             expected_edge_map.add_edge_from_str(edge_str);
@@ -516,12 +657,7 @@ mod test {
             .expect("expect conditional rule");
         let (nodes, edges) = rule.inner_apply(&graph, &NodeId::new("4"));
         let edges = edges.transpose();
-        let node_ids = vec![
-            "0_1_false",
-            "1_1_false",
-            "4_1_false",
-            "0bis_1_false",
-        ];
+        let node_ids = ["0_1_false", "1_1_false", "4_1_false", "0bis_1_false"];
         let node_ids: Vec<NodeId> = node_ids.iter().map(|e| NodeId::new(e)).collect();
         let nodes: HashMap<NodeId, &Node> = nodes.iter().map(|n| (n.get_node_id().clone(), n)).collect();
         for node_id in node_ids.iter() {
@@ -534,7 +670,7 @@ mod test {
 	    "0_1_false"->"1_1_false" [fontsize=5,label="open_secure_channel_request / OpnRepOK,"];
 	    "0_1_false"->"0_1_false" [fontsize=5,label="open_secure_channel_request / OpnRepOK,"];
 	    "0bis_1_false"->"0_1_false" [fontsize=5,label="u / u"];"#;
-        let edges_expected = edges_expected.split(";\n").into_iter().map(|e| e.trim());
+        let edges_expected = edges_expected.split(";\n").map(|e| e.trim());
         let mut expected_edge_map = EdgeMap::new();
         for edge_str in edges_expected {
             expected_edge_map.add_edge_from_str(edge_str);
@@ -543,5 +679,16 @@ mod test {
         assert_eq!(expected_edge_map, edges);
         println!("{edges}");
 
+        let paths_from_root = trace::shortest_paths(&graph);
+        let traces = rule.shortest_traces(&graph, &NodeId::new("4"), &paths_from_root);
+        let inputs: Vec<Vec<String>> = traces.iter().map(|t| t.inputs.clone()).collect();
+        assert_eq!(
+            inputs,
+            vec![
+                vec!["open_secure_channel_request", "create_session", "read_req"],
+                vec!["open_secure_channel_request", "create_session", "write_req"]
+            ]
+        );
+        assert_eq!(traces[0].outputs, vec!["OpnRepOK,", "CreSesResOK,", "ReadRepOK,"]);
     }
 }
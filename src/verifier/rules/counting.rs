@@ -0,0 +1,271 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::{
+    error::{column, Error, Result},
+    graph::nodeid::NodeId,
+    utils,
+    verifier::{
+        event::Events,
+        lint,
+        result::Transition,
+        trace::{self, path_to, sorted_neighbors, Parents, Trace},
+    },
+};
+
+use super::{Graph, Rule, RuleResult};
+#[cfg(test)]
+use std::any::Any;
+
+/// The events matching a pattern are counted along the paths from the initial state, the count going back to zero
+/// on a reset event, and may not exceed a limit.
+#[derive(Debug)]
+pub(crate) struct Counting {
+    name: String,
+    counted: Events,
+    reset: Option<Events>,
+    limit: usize,
+}
+
+impl Counting {
+    fn reset(&self, event_str: &str) -> bool {
+        match &self.reset {
+            Some(reset) => reset.check_all(event_str),
+            None => false,
+        }
+    }
+
+    fn counted(&self, event_str: &str) -> bool {
+        !self.reset(event_str) && self.counted.check_all(event_str)
+    }
+
+    fn shortest_traces(&self, graph: &Graph) -> Vec<Trace> {
+        trace::shortest_traces(graph, 0, |count, _, label| {
+            if self.reset(label) {
+                (false, Some(0))
+            } else if !self.counted(label) {
+                (false, Some(*count))
+            } else if *count == self.limit {
+                (true, None)
+            } else {
+                (false, Some(count + 1))
+            }
+        })
+    }
+
+    //shortest path from a state to another one without reset event, empty when they are the same state
+    fn path_without_reset(&self, graph: &Graph, from: &NodeId, to: &NodeId) -> Option<Vec<Transition>> {
+        let mut parents: Parents<NodeId> = HashMap::new();
+        parents.insert(from.clone(), None);
+        let mut queue = VecDeque::from([from.clone()]);
+        while let Some(node_id) = queue.pop_front() {
+            if node_id == *to {
+                return Some(path_to(&parents, &node_id));
+            }
+            for (neighbor_id, edge) in sorted_neighbors(graph, &node_id) {
+                if parents.contains_key(&neighbor_id) {
+                    continue;
+                }
+                if let Some(label) = edge.get_label_iterator().find(|label| !self.reset(label)) {
+                    let transition = Transition::new(&node_id, &neighbor_id, label);
+                    parents.insert(neighbor_id.clone(), Some((node_id.clone(), transition)));
+                    queue.push_back(neighbor_id);
+                }
+            }
+        }
+        None
+    }
+
+    //a cycle without reset event going through a counted event, as the transitions leading to it and the cycle,
+    //the one closest to the initial state
+    fn unbounded_cycle(&self, graph: &Graph) -> Option<(Vec<Transition>, Vec<Transition>)> {
+        let mut paths: Vec<(NodeId, Vec<Transition>)> = trace::shortest_paths(graph).into_iter().collect();
+        paths.sort_by_key(|(node_id, path)| (path.len(), node_id.to_string()));
+        for (node_id, prefix) in paths {
+            for (neighbor_id, edge) in sorted_neighbors(graph, &node_id) {
+                for label in edge.get_label_iterator().filter(|label| self.counted(label)) {
+                    if let Some(back) = self.path_without_reset(graph, &neighbor_id, &node_id) {
+                        let mut cycle = vec![Transition::new(&node_id, &neighbor_id, label)];
+                        cycle.extend(back);
+                        return Some((prefix, cycle));
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+impl Rule for Counting {
+    fn from_reader(reader: &mut dyn crate::utils::reader::Reader, name: String) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let mut counted: Option<Events> = None;
+        let mut reset: Option<Events> = None;
+        let mut limit: Option<usize> = None;
+        while let Some(raw_line) = reader.read_line() {
+            let line = raw_line.trim_start();
+            if line.starts_with("C:") || line.starts_with("R:") || line.starts_with("N:") {
+                let split_line: Vec<_> = line.split(":").collect();
+                if split_line.len() != 2 {
+                    return Err(Error::parse(
+                        column(raw_line, line),
+                        format!("failed to parse Counting events: {}", line.trim()),
+                    ));
+                }
+                let value = split_line[1].trim();
+                if line.starts_with("N:") {
+                    limit = match value.parse() {
+                        Ok(limit) => Some(limit),
+                        Err(_) => {
+                            return Err(Error::parse(
+                                column(raw_line, value),
+                                format!("the limit is a number of events, not {}", value),
+                            ))
+                        }
+                    };
+                    continue;
+                }
+                let events = Events::from_str(value).map_err(|e| e.at_column(column(raw_line, value)))?;
+                if line.starts_with("C:") {
+                    counted = Some(events);
+                } else {
+                    reset = Some(events);
+                }
+            } else if line.starts_with(":CNT") {
+                break;
+            } else {
+                return Err(Error::parse(
+                    column(raw_line, line),
+                    format!("failed to parse until Rule:{}", line.trim()),
+                ));
+            }
+        }
+        let counted = match counted {
+            Some(counted) => counted,
+            None => return Err(Error::parse(0, "you must specify counted event for counting rule")),
+        };
+        let limit = match limit {
+            Some(limit) => limit,
+            None => return Err(Error::parse(0, "you must specify the limit for counting rule")),
+        };
+        Ok(Counting {
+            name,
+            counted,
+            reset,
+            limit,
+        })
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn lint(&self, graph: &Graph) -> Vec<String> {
+        let reset = self.reset.iter().flat_map(Events::iter);
+        let mut warnings = lint::unmatched(graph, self.counted.iter().chain(reset));
+        let counted = format!("counted event {}", self.counted);
+        warnings.extend(lint::never_occurs(graph, counted, |label| self.counted(label)));
+        warnings
+    }
+
+    fn apply(&mut self, graph: &super::Graph, output_folder: &mut std::path::PathBuf) -> Result<RuleResult> {
+        if graph.get_root().is_none() {
+            return Err(Error::graph(graph.get_name(), "no starting state on the mealy machine"));
+        }
+        let mut traces = self.shortest_traces(graph);
+        let violations = traces.iter().map(|trace| trace.violation().clone()).collect();
+        let mut result = RuleResult::from_violations(self.get_name(), violations);
+        //the count is unbounded, the cycle can be repeated as many times as wanted
+        if let Some((prefix, cycle)) = self.unbounded_cycle(graph) {
+            traces.push(Trace::lasso(prefix, cycle));
+        }
+        output_folder.push(self.get_name());
+        if let Err(e) = std::fs::create_dir_all(&output_folder) {
+            return Err(Error::io(output_folder.clone(), e));
+        }
+        let transitions: Vec<Transition> = traces.iter().flat_map(|t| t.transitions.iter().cloned()).collect();
+        match utils::output::write_transitions(graph, &transitions, output_folder, "ce.dot") {
+            Ok(path) => result.add_counterexample(path),
+            Err(e) => return Err(Error::io(output_folder.clone(), e)),
+        }
+        match utils::output::write_traces(&traces, output_folder) {
+            Ok(paths) => result.add_traces(traces, paths),
+            Err(e) => return Err(Error::io(output_folder.clone(), e)),
+        }
+        output_folder.pop();
+        Ok(result)
+    }
+
+    #[cfg(test)]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        graph::Graph,
+        utils::reader::test_reader::TestReader,
+        verifier::rules::{parse_reader, parse_rule_from_str},
+    };
+
+    use super::Counting;
+
+    #[test]
+    fn test_counting() {
+        let input = r#"digraph "Automata" {
+            "0" [shape=doublecircle];
+            "0" -> "1" [label="CH_OPEN/CH_OPEN_SUCCESS"];
+            "1" -> "2" [label="CH_OPEN/CH_OPEN_SUCCESS"];
+            "1" -> "1" [label="ping/pong"];
+            "2" -> "0" [label="CH_CLOSE/ok"];
+        }"#;
+        //0 -open-> 1 -open-> 2 -close-> 0 with a ping loop on 1
+        let graph = Graph::new(input, true);
+        let counting = |rule: &str| {
+            let rule = parse_rule_from_str(rule);
+            let rule = rule[0].as_any().downcast_ref::<Counting>().expect("expect CNT rule");
+            let traces: Vec<Vec<String>> = rule
+                .shortest_traces(&graph)
+                .into_iter()
+                .map(|trace| trace.inputs)
+                .collect();
+            (traces, rule.unbounded_cycle(&graph))
+        };
+        let (traces, unbounded) = counting("CNT:open\nC: */CH_OPEN_SUCCESS\nR: CH_CLOSE/*\nN: 2\n:CNT");
+        assert!(traces.is_empty());
+        assert!(unbounded.is_none());
+        let (traces, unbounded) = counting("CNT:open\nC: */CH_OPEN_SUCCESS\nR: CH_CLOSE/*\nN: 1\n:CNT");
+        assert_eq!(traces, vec![vec!["CH_OPEN", "CH_OPEN"]]);
+        assert!(unbounded.is_none());
+
+        //without reset, the channels are opened again and again
+        let (traces, unbounded) = counting("CNT:open\nC: */CH_OPEN_SUCCESS\nN: 3\n:CNT");
+        assert_eq!(
+            traces,
+            vec![vec!["CH_OPEN", "CH_OPEN", "CH_CLOSE", "CH_OPEN", "CH_OPEN"]]
+        );
+        let (prefix, cycle) = unbounded.unwrap();
+        assert!(prefix.is_empty());
+        let cycle: Vec<String> = cycle.iter().map(|t| t.label.clone()).collect();
+        assert_eq!(
+            cycle,
+            vec!["CH_OPEN/CH_OPEN_SUCCESS", "CH_OPEN/CH_OPEN_SUCCESS", "CH_CLOSE/ok"]
+        );
+
+        let rules = "CNT:limit\nC: a/b\nN: many\n:CNT\nCNT:no_event\nN: 2\n:CNT";
+        let mut reader = TestReader::from_text(rules).unwrap();
+        let (rules, errors) = parse_reader(&mut reader, "rules");
+        assert!(rules.is_empty());
+        let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "rules:3:4: the limit is a number of events, not many",
+                "rules:7:1: you must specify counted event for counting rule",
+            ]
+        );
+    }
+}
@@ -1,13 +1,18 @@
-use std::{
-    collections::HashMap,
-    fs::{self},
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use super::{Rule, RuleResult};
+use crate::{
+    error::{column, Error, Result},
+    graph::{multi_edge::MultiEdge, nodeid::NodeId, Graph},
+    utils::{self, reader::Reader},
+    verifier::{event::Events, lint, result::Transition},
 };
-
-use super::{Cycle, Rule};
-use crate::{graph::Graph, utils::reader::Reader, verifier::event::Events};
 #[cfg(test)]
 use std::any::Any;
 
+//state of the search (node, index in the cycle) -> previous state, edge and label index taken from it
+type Parents<'a> = HashMap<(NodeId, usize), ((NodeId, usize), &'a MultiEdge, usize)>;
+
 #[derive(Debug)]
 pub(crate) struct ExpectedCycle {
     pub(crate) cycle: Events,
@@ -15,42 +20,61 @@ pub(crate) struct ExpectedCycle {
 }
 
 impl ExpectedCycle {
-    fn check(&self, cycle: &Cycle) -> bool {
-        let mut ret;
-        let labels = cycle.get_labels();
-        if labels.len() != self.cycle.len() {
-            return false;
+    //first cycle of the graph following the events of the rule, as a list of edges with the index of the label
+    fn inner_apply<'a>(&self, graph: &'a Graph) -> Option<Vec<(&'a MultiEdge, usize)>> {
+        for node_id in graph.iter_node_id() {
+            if let Some(cycle) = self.find_cycle(graph, node_id) {
+                return Some(cycle);
+            }
         }
+        None
+    }
 
-        for (index, edge_labels) in labels.into_iter().enumerate() {
-            ret = false;
-            for label in edge_labels {
-                if self.cycle.check(index, &label) {
-                    ret = true;
+    //breadth first search of a path from start back to start where the n-th transition matches the n-th event
+    fn find_cycle<'a>(&self, graph: &'a Graph, start: &NodeId) -> Option<Vec<(&'a MultiEdge, usize)>> {
+        let cycle_len = self.cycle.len();
+        if cycle_len == 0 {
+            return None;
+        }
+        let mut parents: Parents = HashMap::new();
+        let mut queue = VecDeque::new();
+        queue.push_back((start.clone(), 0));
+        while let Some((node_id, index)) = queue.pop_front() {
+            for (dest_node_id, edge) in graph.neighbors_edges_iterator(&node_id) {
+                for (label_index, label) in edge.get_label_iterator().enumerate() {
+                    //the index increases along a path, hence the start state can not be reached again
+                    let next = (dest_node_id.clone(), index + 1);
+                    if !self.cycle.check(index, label)
+                        || parents.contains_key(&next)
+                        || (next.1 == cycle_len && &dest_node_id != start)
+                    {
+                        continue;
+                    }
+                    parents.insert(next.clone(), ((node_id.clone(), index), edge, label_index));
+                    if next.1 == cycle_len {
+                        return Some(Self::rebuild_cycle(&parents, next));
+                    }
+                    queue.push_back(next);
                 }
             }
-            if !ret {
-                return false;
-            }
         }
-        true
+        None
     }
 
-    fn inner_apply(&self, cycles: &HashMap<super::NodeId, Vec<super::Cycle>>) -> bool {
-        //check cycle
-        for cycle_ in cycles.values() {
-            for cycle in cycle_ {
-                if self.check(cycle) {
-                    return true;
-                }
-            }
+    fn rebuild_cycle<'a>(parents: &Parents<'a>, end: (NodeId, usize)) -> Vec<(&'a MultiEdge, usize)> {
+        let mut cycle = Vec::new();
+        let mut current = end;
+        while let Some((previous, edge, label_index)) = parents.get(&current) {
+            cycle.push((*edge, *label_index));
+            current = previous.clone();
         }
-        return false;
+        cycle.reverse();
+        cycle
     }
 }
 
 impl Rule for ExpectedCycle {
-    fn from_reader(reader: &mut dyn Reader, name: String) -> std::io::Result<Self>
+    fn from_reader(reader: &mut dyn Reader, name: String) -> Result<Self>
     where
         Self: Sized,
     {
@@ -59,14 +83,17 @@ impl Rule for ExpectedCycle {
             if line.starts_with(":EC") {
                 break;
             } else if line.contains('/') {
-                cycle = Some(Events::from_str(line));
+                cycle = Some(Events::from_str(line).map_err(|e| e.at_column(column(line, line.trim())))?);
             } else {
-                panic!("failed to parse expected cycle rule");
+                return Err(Error::parse(
+                    column(line, line.trim()),
+                    format!("failed to parse expected cycle rule: {}", line.trim()),
+                ));
             }
         }
         let cycle = match cycle {
             Some(c) => c,
-            None => panic!("failed to parse expected cycle rule"),
+            None => return Err(Error::parse(0, "no cycle given for expected cycle rule")),
         };
         Ok(ExpectedCycle { cycle, name })
     }
@@ -75,30 +102,44 @@ impl Rule for ExpectedCycle {
         &self.name
     }
 
-    fn apply(
-        &mut self,
-        _graph: &Graph,
-        cycles: &HashMap<super::NodeId, Vec<super::Cycle>>,
-        output_folder: &mut std::path::PathBuf,
-    ) {
+    fn lint(&self, graph: &Graph) -> Vec<String> {
+        lint::unmatched(graph, self.cycle.iter())
+    }
+
+    fn apply(&mut self, graph: &Graph, output_folder: &mut std::path::PathBuf) -> Result<RuleResult> {
+        let cycle = self.inner_apply(graph);
+        let witness = cycle.as_ref().map(|cycle| {
+            cycle
+                .iter()
+                .map(|(edge, label_index)| {
+                    let label = &edge.get_labels()[*label_index];
+                    Transition::new(edge.get_source(), edge.get_dest(), label)
+                })
+                .collect()
+        });
+        let mut result = RuleResult::from_witness(self.get_name(), witness);
         output_folder.push(self.get_name());
-        if let Err(e) = fs::create_dir_all(&output_folder) {
-            panic!("failed to create output directory due to :{}", e.to_string());
+        if let Err(e) = std::fs::create_dir_all(&output_folder) {
+            return Err(Error::io(output_folder.clone(), e));
         }
-        if self.inner_apply(cycles) {
-            output_folder.push("true".to_string());
-        } else {
-            output_folder.push("false".to_string());
+        if let Some(cycle) = cycle {
+            let mut nodes = HashSet::new();
+            let mut edges: HashMap<&MultiEdge, Vec<usize>> = HashMap::new();
+            for (edge, label_index) in cycle {
+                nodes.insert(edge.get_source().clone());
+                nodes.insert(edge.get_dest().clone());
+                edges.entry(edge).or_default().push(label_index);
+            }
+            let edges = edges.into_iter().collect();
+            match utils::output::write_files_named(graph, nodes, edges, output_folder, "witness.dot") {
+                Ok(path) => result.add_counterexample(path),
+                Err(e) => return Err(Error::io(output_folder.clone(), e)),
+            }
         }
-
-        let _file = match fs::OpenOptions::new().write(true).truncate(true).open(&output_folder) {
-            Ok(f) => f,
-            Err(_) => fs::File::create(&output_folder)
-                .expect(&format!("failse to create output of rule :{}", self.get_name())),
-        };
-        output_folder.pop();
         output_folder.pop();
+        Ok(result)
     }
+
     #[cfg(test)]
     fn as_any(&self) -> &dyn Any {
         self
@@ -108,14 +149,13 @@ impl Rule for ExpectedCycle {
 #[cfg(test)]
 mod tests {
     //https://dot-to-ascii.ggerganov.com/
-    use crate::algorithm::cycle::Cycle;
     use crate::graph::Graph;
 
     use super::ExpectedCycle;
 
     #[test]
     fn test_expected_cycle() {
-        let input = r#"digraph "Automata" { 
+        let input = r#"digraph "Automata" {
             "0" [shape=ellipse, style=filed, fillcolor=white, URL="0"];
             "1" [shape=ellipse, style=filed, fillcolor=white, URL="1"];
             "2" [shape=ellipse, style=filed, fillcolor=white, URL="456"];
@@ -126,7 +166,7 @@ mod tests {
             "1" -> "2" [fontsize=5, label="1/2", color=black];
             "1" -> "3" [fontsize=5, label="1/3", color=black];
             "3" -> "0" [fontsize=5, label="3/0", color=black];
-            
+
         }"#;
         //                       3/0
         //                ┌───────────────┐
@@ -151,7 +191,6 @@ mod tests {
         //              └──────┘
         let graph: Graph = Graph::new(input, false);
 
-        let cycles = Cycle::all_simple_cycles_with_edges(&graph);
         let rule_text = r#"EC:test
         0/1;1/2;2/0
         :EC"#;
@@ -161,7 +200,12 @@ mod tests {
             .downcast_ref::<ExpectedCycle>()
             .expect("expect ExpectedCycle rule");
 
-        assert!(rule.inner_apply(&cycles));
+        let cycle = rule.inner_apply(&graph).expect("the cycle exists");
+        let labels: Vec<String> = cycle
+            .iter()
+            .map(|(edge, index)| edge.get_labels()[*index].to_string())
+            .collect();
+        assert_eq!(labels, vec!["0/1", "1/2", "2/0"]);
 
         let rule_text = r#"EC:test
         0/1;1/2;2/d
@@ -172,6 +216,6 @@ mod tests {
             .downcast_ref::<ExpectedCycle>()
             .expect("expect ExpectedCycle rule");
 
-        assert!(!rule.inner_apply(&cycles));
+        assert!(rule.inner_apply(&graph).is_none());
     }
 }
@@ -3,9 +3,17 @@ use std::collections::HashSet;
 #[cfg(test)]
 use std::any::Any;
 
-use crate::{utils, verifier::event::Events};
+use crate::{
+    error::{column, Error, Result},
+    utils,
+    verifier::{
+        event::Events,
+        lint,
+        trace::{self, Trace},
+    },
+};
 
-use super::{Graph, MultiEdge, NodeId, Rule};
+use super::{Graph, MultiEdge, NodeId, Rule, RuleResult};
 #[derive(Debug)]
 pub(crate) struct ExpectedTransitionIndex {
     name: String,
@@ -16,6 +24,19 @@ impl ExpectedTransitionIndex {
     fn match_inner_event(&self, event: &str) -> bool {
         self.event.check_all(event)
     }
+    fn shortest_traces(&self, graph: &Graph) -> Vec<Trace> {
+        let sink_nodes = graph.get_sink_state_set();
+        trace::shortest_traces(graph, 0, |depth, neighbor_id, label| {
+            let error = *depth == self.index && !sink_nodes.contains(neighbor_id) && !self.match_inner_event(label);
+            let next_depth = *depth + 1;
+            if next_depth <= self.index {
+                (error, Some(next_depth))
+            } else {
+                (error, None)
+            }
+        })
+    }
+
     fn inner_apply<'a>(&'a self, graph: &'a Graph) -> (HashSet<NodeId>, HashSet<(&'a MultiEdge, Vec<usize>)>) {
         let sink_nodes: HashSet<&NodeId> = graph.get_sink_state_set();
         let mut seen: HashSet<(NodeId, usize)> = HashSet::new();
@@ -26,7 +47,7 @@ impl ExpectedTransitionIndex {
         let neighbors = graph.neighbors_edges_iterator(root_node);
         execution_stack.push((root_node.clone(), neighbors, 0));
         while let Some((node_id, neighbors, depth)) = execution_stack.last_mut() {
-            seen.insert((node_id.clone(), depth.clone()));
+            seen.insert((node_id.clone(), *depth));
             let next_depth = *depth + 1;
             if let Some((neighbor_id, edge)) = neighbors.pop() {
                 if *depth == self.index && !sink_nodes.contains(&neighbor_id) {
@@ -55,57 +76,69 @@ impl ExpectedTransitionIndex {
 }
 
 impl Rule for ExpectedTransitionIndex {
-    fn from_reader(reader: &mut dyn crate::utils::reader::Reader, name: String) -> std::io::Result<Self>
+    fn from_reader(reader: &mut dyn crate::utils::reader::Reader, name: String) -> Result<Self>
     where
         Self: Sized,
     {
         let mut event = None;
         let mut index = None;
         while let Some(line) = reader.read_line() {
+            let value = line.trim();
             if line.contains("/") {
-                event = Some(Events::from_str(line));
+                event = Some(Events::from_str(line).map_err(|e| e.at_column(column(line, value)))?);
             } else if line.starts_with(":ETI") {
                 break;
             } else {
-                index = Some(
-                    line.trim()
-                        .parse::<usize>()
-                        .expect("failed to parse index of ExpectedTransitionIndex rule due to :{line}"),
-                );
+                match value.parse::<usize>() {
+                    Ok(i) => index = Some(i),
+                    Err(e) => {
+                        return Err(Error::parse(
+                            column(line, value),
+                            format!("failed to parse index of ExpectedTransitionIndex rule {value}: {e}"),
+                        ))
+                    }
+                }
             }
         }
-        Ok(ExpectedTransitionIndex {
-            event: event.expect("failed to parse ExpectedTransition index"),
-            index: index.expect("failed to parse ExpectedTransition index"),
-            name,
-        })
+        let event = match event {
+            Some(event) => event,
+            None => return Err(Error::parse(0, "no expected event for ExpectedTransitionIndex rule")),
+        };
+        let index = match index {
+            Some(index) => index,
+            None => return Err(Error::parse(0, "no index for ExpectedTransitionIndex rule")),
+        };
+        Ok(ExpectedTransitionIndex { event, index, name })
     }
 
     fn get_name(&self) -> &str {
         &self.name
     }
 
-    fn apply(
-        &mut self,
-        graph: &super::Graph,
-        output_folder: &mut std::path::PathBuf,
-    ) {
+    fn lint(&self, graph: &Graph) -> Vec<String> {
+        lint::unmatched(graph, self.event.iter())
+    }
+
+    fn apply(&mut self, graph: &super::Graph, output_folder: &mut std::path::PathBuf) -> Result<RuleResult> {
         let (nodes, edges) = self.inner_apply(graph);
+        let mut result = RuleResult::from_edges(self.get_name(), edges.iter());
         output_folder.push(self.get_name());
         // println!("graph: {}",graph.get_name());
         if let Err(e) = std::fs::create_dir_all(&output_folder) {
-            panic!("failed to create output directory due to :{}", e.to_string());
+            return Err(Error::io(output_folder.clone(), e));
         }
 
-        let ret = utils::output::write_files(graph, nodes, edges, output_folder);
-        if let Err(e) = ret {
-            panic!(
-                "unable to output the result of rule {} due to {}",
-                self.get_name(),
-                e.to_string()
-            );
+        match utils::output::write_files(graph, nodes, edges, output_folder) {
+            Ok(path) => result.add_counterexample(path),
+            Err(e) => return Err(Error::io(output_folder.clone(), e)),
+        }
+        let traces = self.shortest_traces(graph);
+        match utils::output::write_traces(&traces, output_folder) {
+            Ok(paths) => result.add_traces(traces, paths),
+            Err(e) => return Err(Error::io(output_folder.clone(), e)),
         }
         output_folder.pop();
+        Ok(result)
     }
 
     #[cfg(test)]
@@ -173,7 +206,7 @@ mod test {
         assert!(nodes.contains(&NodeId::new("2")));
         let edges_expected = r#"
         "1"->"2" [fontsize=5,label="1/nok"];"#;
-        let edges_expected = edges_expected.split(";\n").into_iter().map(|e| e.trim());
+        let edges_expected = edges_expected.split(";\n").map(|e| e.trim());
         let mut expected_edge_map = EdgeMap::new();
         for edge_str in edges_expected {
             expected_edge_map.add_edge_from_str(edge_str);
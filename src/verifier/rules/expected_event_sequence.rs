@@ -1,11 +1,16 @@
 use std::collections::HashSet;
 
 use crate::{
+    error::{column, Error, Result},
     utils,
-    verifier::event::{Event, Events},
+    verifier::{
+        event::{Event, Events},
+        lint,
+        trace::{self, Trace},
+    },
 };
 
-use super::{Graph, MultiEdge, NodeId, Rule};
+use super::{Graph, MultiEdge, NodeId, Rule, RuleResult};
 #[cfg(test)]
 use std::any::Any;
 
@@ -20,7 +25,7 @@ enum State {
 pub(crate) struct ExpectedTransitionSequence {
     name: String,
     events_sequence: Events,
-    ignore_events:Option<Events>,
+    ignore_events: Option<Events>,
     init_events: Option<Events>,
     end_events: Option<Events>,
     init_state: State,
@@ -35,9 +40,9 @@ impl ExpectedTransitionSequence {
     }
 
     fn updating_index(&self, index: usize, event_str: &str, state: &State) -> (usize, bool, State) {
-        if let Some(ignore)=&self.ignore_events{
-            if ignore.check_all(event_str){
-               return  (index, false, *state);
+        if let Some(ignore) = &self.ignore_events {
+            if ignore.check_all(event_str) {
+                return (index, false, *state);
             }
         }
         match state {
@@ -81,17 +86,33 @@ impl ExpectedTransitionSequence {
         }
     }
 
+    fn shortest_traces(&self, graph: &Graph) -> Vec<Trace> {
+        let sink_nodes = graph.get_sink_state_set();
+        let init = (0, self.init_state);
+        trace::shortest_traces(graph, init, |(index_on_sequence, state), neighbor_id, label| {
+            if sink_nodes.contains(neighbor_id) {
+                return (false, None);
+            }
+            let (new_sequence_index, error, new_state) = self.updating_index(*index_on_sequence, label, state);
+            if error {
+                (true, None)
+            } else {
+                (false, Some((new_sequence_index, new_state)))
+            }
+        })
+    }
+
     fn inner_apply<'a>(&'a self, graph: &'a Graph) -> (HashSet<NodeId>, HashSet<(&'a MultiEdge, Vec<usize>)>) {
-        let sink_node: HashSet<NodeId> = graph.get_sink_state().iter().map(|n| n.clone()).collect();
+        let sink_node: HashSet<NodeId> = graph.get_sink_state().iter().cloned().collect();
         let mut nodes: HashSet<NodeId> = HashSet::new();
         let mut seen: HashSet<(NodeId, usize, State)> = HashSet::new();
         let mut edges = HashSet::new();
         let root_node = graph.get_root().expect("could not be reached on root graph");
         let mut execution_stack = Vec::new();
         let neighbors = graph.neighbors_edges_iterator(root_node);
-        execution_stack.push((root_node.clone(), neighbors, 0, self.init_state.clone()));
+        execution_stack.push((root_node.clone(), neighbors, 0, self.init_state));
         while let Some((node_id, neighbors, index_on_sequence, state)) = execution_stack.last_mut() {
-            seen.insert((node_id.clone(), index_on_sequence.clone(), state.clone()));
+            seen.insert((node_id.clone(), *index_on_sequence, *state));
             if let Some((neighbor_id, edge)) = neighbors.pop() {
                 if sink_node.contains(&neighbor_id) {
                     continue;
@@ -126,40 +147,42 @@ impl ExpectedTransitionSequence {
 }
 
 impl Rule for ExpectedTransitionSequence {
-    fn from_reader(reader: &mut dyn crate::utils::reader::Reader, name: String) -> std::io::Result<Self>
+    fn from_reader(reader: &mut dyn crate::utils::reader::Reader, name: String) -> Result<Self>
     where
         Self: Sized,
     {
         let mut events_sequence = Events::empty();
         let mut init_events = None;
         let mut end_events = None;
-        let mut ignore_events=None;
-        while let Some(line) = reader.read_line() {
-            let line = line.trim();
-            if line.starts_with("I:") {
-                let split_line: Vec<_> = line.split(":").collect();
-                if split_line.len() != 2 {
-                    panic!("no authorized event for RestrictedEvents rule");
-                }
-                init_events = Some(Events::from_str(split_line[1].trim()));
-            } else if line.starts_with("E:") {
+        let mut ignore_events = None;
+        while let Some(raw_line) = reader.read_line() {
+            let line = raw_line.trim();
+            if line.starts_with("I:") || line.starts_with("E:") || line.starts_with("Ig:") {
                 let split_line: Vec<_> = line.split(":").collect();
                 if split_line.len() != 2 {
-                    panic!("no authorized event for RestrictedEvents rule");
+                    return Err(Error::parse(
+                        column(raw_line, line),
+                        format!("failed to parse ExpectedTransitionSequence events: {}", line),
+                    ));
                 }
-                end_events = Some(Events::from_str(split_line[1].trim()));
-            } else if line.starts_with("Ig:") {
-                let split_line: Vec<_> = line.split(":").collect();
-                if split_line.len() != 2 {
-                    panic!("no authorized event for RestrictedEvents rule");
+                let value = split_line[1].trim();
+                let events = Events::from_str(value).map_err(|e| e.at_column(column(raw_line, value)))?;
+                if line.starts_with("I:") {
+                    init_events = Some(events);
+                } else if line.starts_with("E:") {
+                    end_events = Some(events);
+                } else {
+                    ignore_events = Some(events);
                 }
-                ignore_events = Some(Events::from_str(split_line[1].trim()));
-            }else if line.contains("/") {
-                events_sequence.push(Event::new(line));
+            } else if line.contains("/") {
+                events_sequence.push(Event::new(line).map_err(|e| e.at_column(column(raw_line, line)))?);
             } else if line.starts_with(":ETS") {
                 break;
             } else {
-                panic!("failed to parse ExpectedTransitionSequence: {}", line);
+                return Err(Error::parse(
+                    column(raw_line, line),
+                    format!("failed to parse ExpectedTransitionSequence: {}", line),
+                ));
             }
         }
         let init_state = match init_events {
@@ -180,27 +203,37 @@ impl Rule for ExpectedTransitionSequence {
         &self.name
     }
 
-    fn apply(
-        &mut self,
-        graph: &super::Graph,
-        output_folder: &mut std::path::PathBuf,
-    ) {
+    fn lint(&self, graph: &Graph) -> Vec<String> {
+        let optional = [&self.ignore_events, &self.init_events, &self.end_events];
+        let optional = optional.into_iter().flatten().flat_map(Events::iter);
+        let mut warnings = lint::unmatched(graph, self.events_sequence.iter().chain(optional));
+        if let Some(init) = &self.init_events {
+            let what = format!("init event {}", init);
+            warnings.extend(lint::never_occurs(graph, what, |label| init.check_all(label)));
+        }
+        warnings
+    }
+
+    fn apply(&mut self, graph: &super::Graph, output_folder: &mut std::path::PathBuf) -> Result<RuleResult> {
         let (nodes, edges) = self.inner_apply(graph);
+        let mut result = RuleResult::from_edges(self.get_name(), edges.iter());
         output_folder.push(self.get_name());
         // println!("graph: {}",graph.get_name());
         if let Err(e) = std::fs::create_dir_all(&output_folder) {
-            panic!("failed to create output directory due to :{}", e.to_string());
+            return Err(Error::io(output_folder.clone(), e));
         }
 
-        let ret = utils::output::write_files(graph, nodes, edges, output_folder);
-        if let Err(e) = ret {
-            panic!(
-                "unable to output the result of rule {} due to {}",
-                self.get_name(),
-                e.to_string()
-            );
+        match utils::output::write_files(graph, nodes, edges, output_folder) {
+            Ok(path) => result.add_counterexample(path),
+            Err(e) => return Err(Error::io(output_folder.clone(), e)),
+        }
+        let traces = self.shortest_traces(graph);
+        match utils::output::write_traces(&traces, output_folder) {
+            Ok(paths) => result.add_traces(traces, paths),
+            Err(e) => return Err(Error::io(output_folder.clone(), e)),
         }
         output_folder.pop();
+        Ok(result)
     }
 
     #[cfg(test)]
@@ -290,7 +323,7 @@ mod test {
         let edges_expected = r#"
         "1"->"2" [fontsize=5,label="i1/no1"];
         "3"->"4" [fontsize=5, label="i2/no2"];"#;
-        let edges_expected = edges_expected.split(";\n").into_iter().map(|e| e.trim());
+        let edges_expected = edges_expected.split(";\n").map(|e| e.trim());
         let mut expected_edge_map = EdgeMap::new();
         for edge_str in edges_expected {
             expected_edge_map.add_edge_from_str(edge_str);
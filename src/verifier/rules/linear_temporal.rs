@@ -0,0 +1,444 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use super::{Rule, RuleResult};
+use crate::{
+    error::{Error, Result},
+    graph::{multi_edge::MultiEdge, nodeid::NodeId, Graph},
+    utils::{self, reader::Reader},
+    verifier::{
+        buchi::Buchi,
+        lint,
+        ltl::{self, Ltl},
+        result::Transition,
+        temporal::Formula,
+        trace::{sorted_neighbors, Trace},
+    },
+};
+#[cfg(test)]
+use std::any::Any;
+
+//transition of the product: destination and transition of the Mealy machine as an edge with the index of the label,
+//None when the Mealy machine stays in a state without outgoing transition
+type ProductEdge<'a> = (usize, Option<(&'a MultiEdge, usize)>);
+//transitions leading to the cycle and transitions of the cycle, as edges with the index of the label
+type Lasso<'a> = (Vec<(&'a MultiEdge, usize)>, Vec<(&'a MultiEdge, usize)>);
+
+/// Product of the Mealy machine and of the Büchi automaton of the negation of the formula.
+/// A state is a state of the Mealy machine with the state of the automaton entered by the transition leading to it,
+/// the first state is the initial state of the Mealy machine before any transition.
+#[derive(Debug)]
+struct Product<'a> {
+    states: Vec<(NodeId, Option<usize>)>,
+    edges: Vec<Vec<ProductEdge<'a>>>,
+    index: HashMap<(NodeId, Option<usize>), usize>,
+}
+
+impl<'a> Product<'a> {
+    fn new(graph: &'a Graph, formula: &Formula<Ltl>, buchi: &Buchi, root: &NodeId) -> Self {
+        let mut product = Product {
+            states: Vec::new(),
+            edges: Vec::new(),
+            index: HashMap::new(),
+        };
+        product.add((root.clone(), None));
+        let mut current = 0;
+        while current < product.states.len() {
+            let (node_id, state) = product.states[current].clone();
+            let successors = match state {
+                Some(state) => &buchi.states[state].successors,
+                None => &buchi.initial,
+            };
+            let mut edges = Vec::new();
+            let neighbors = sorted_neighbors(graph, &node_id);
+            if neighbors.is_empty() {
+                //the Mealy machine stays forever in a state without outgoing transition, no event matches there
+                let valuation = vec![false; formula.atoms.len()];
+                for successor in successors {
+                    if buchi.states[*successor].allows(&valuation) {
+                        edges.push((product.add((node_id.clone(), Some(*successor))), None));
+                    }
+                }
+            }
+            for (neighbor_id, edge) in neighbors {
+                for (label_index, label) in edge.get_label_iterator().enumerate() {
+                    let valuation = formula.valuation(label);
+                    for successor in successors {
+                        if buchi.states[*successor].allows(&valuation) {
+                            let next = product.add((neighbor_id.clone(), Some(*successor)));
+                            edges.push((next, Some((edge, label_index))));
+                        }
+                    }
+                }
+            }
+            product.edges.push(edges);
+            current += 1;
+        }
+        product
+    }
+
+    fn add(&mut self, state: (NodeId, Option<usize>)) -> usize {
+        if let Some(index) = self.index.get(&state) {
+            return *index;
+        }
+        self.states.push(state.clone());
+        self.index.insert(state, self.states.len() - 1);
+        self.states.len() - 1
+    }
+
+    //strongly connected components by the algorithm of Tarjan, without recursion
+    fn components(&self) -> Vec<Vec<usize>> {
+        let count = self.states.len();
+        let mut index = vec![usize::MAX; count];
+        let mut lowlink = vec![0; count];
+        let mut on_stack = vec![false; count];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut counter = 0;
+        for start in 0..count {
+            if index[start] != usize::MAX {
+                continue;
+            }
+            let mut calls = vec![(start, 0)];
+            index[start] = counter;
+            lowlink[start] = counter;
+            counter += 1;
+            stack.push(start);
+            on_stack[start] = true;
+            while let Some((state, next_edge)) = calls.last_mut() {
+                let state = *state;
+                if let Some((next, _)) = self.edges[state].get(*next_edge) {
+                    *next_edge += 1;
+                    if index[*next] == usize::MAX {
+                        index[*next] = counter;
+                        lowlink[*next] = counter;
+                        counter += 1;
+                        stack.push(*next);
+                        on_stack[*next] = true;
+                        calls.push((*next, 0));
+                    } else if on_stack[*next] {
+                        lowlink[state] = lowlink[state].min(index[*next]);
+                    }
+                    continue;
+                }
+                calls.pop();
+                if let Some((caller, _)) = calls.last() {
+                    lowlink[*caller] = lowlink[*caller].min(lowlink[state]);
+                }
+                if lowlink[state] == index[state] {
+                    let mut component = Vec::new();
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == state {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+        components
+    }
+
+    //shortest path of at least one transition from `from` to a state matching `target` within the allowed states
+    fn path(&self, from: usize, allowed: &[bool], target: impl Fn(usize) -> bool) -> Vec<ProductEdge<'a>> {
+        let mut parents: HashMap<usize, ProductEdge<'a>> = HashMap::new();
+        let mut queue = VecDeque::from([from]);
+        while let Some(state) = queue.pop_front() {
+            for (next, transition) in self.edges[state].iter() {
+                if !allowed[*next] || parents.contains_key(next) {
+                    continue;
+                }
+                parents.insert(*next, (state, *transition));
+                if target(*next) {
+                    let mut path = Vec::new();
+                    let mut current = *next;
+                    loop {
+                        let (previous, transition) = parents[&current];
+                        path.push((current, transition));
+                        current = previous;
+                        if current == from {
+                            break;
+                        }
+                    }
+                    path.reverse();
+                    return path;
+                }
+                queue.push_back(*next);
+            }
+        }
+        Vec::new()
+    }
+
+    //accepting lasso as the path from the first state to the cycle and the cycle, None if there is no accepting run
+    fn accepting_lasso(&self, buchi: &Buchi) -> Option<(Vec<ProductEdge<'a>>, Vec<ProductEdge<'a>>)> {
+        let accepting = |state: usize, set: usize| {
+            self.states[state]
+                .1
+                .is_some_and(|buchi_state| buchi.states[buchi_state].accepting[set])
+        };
+        //the distance from the first state gives the shortest prefix
+        let mut distances = vec![usize::MAX; self.states.len()];
+        distances[0] = 0;
+        let mut queue = VecDeque::from([0]);
+        while let Some(state) = queue.pop_front() {
+            for (next, _) in self.edges[state].iter() {
+                if distances[*next] == usize::MAX {
+                    distances[*next] = distances[state] + 1;
+                    queue.push_back(*next);
+                }
+            }
+        }
+        //the accepting component closest to the first state, entered by its closest state
+        let (entry, component) = self
+            .components()
+            .into_iter()
+            .filter(|component| {
+                let cyclic = component.len() > 1 || self.edges[component[0]].iter().any(|(n, _)| *n == component[0]);
+                cyclic && (0..buchi.sets).all(|set| component.iter().any(|state| accepting(*state, set)))
+            })
+            .filter_map(|component| {
+                let entry = component
+                    .iter()
+                    .copied()
+                    .min_by_key(|state| (distances[*state], *state))?;
+                Some((entry, component))
+            })
+            .min_by_key(|(entry, _)| (distances[*entry], *entry))?;
+        let prefix = if entry == 0 {
+            Vec::new()
+        } else {
+            self.path(0, &vec![true; self.states.len()], |state| state == entry)
+        };
+
+        let mut within = vec![false; self.states.len()];
+        for state in component {
+            within[state] = true;
+        }
+        let mut cycle: Vec<ProductEdge<'a>> = Vec::new();
+        let mut current = entry;
+        for set in 0..buchi.sets {
+            let visited = accepting(entry, set) || cycle.iter().any(|(state, _)| accepting(*state, set));
+            if !visited {
+                cycle.extend(self.path(current, &within, |state| accepting(state, set)));
+                current = cycle.last().map_or(entry, |(state, _)| *state);
+            }
+        }
+        if current != entry || cycle.is_empty() {
+            cycle.extend(self.path(current, &within, |state| state == entry));
+        }
+        Some((prefix, cycle))
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct LinearTemporal {
+    pub(crate) formula: Formula<Ltl>,
+    pub(crate) name: String,
+}
+
+impl LinearTemporal {
+    //infinite run of the Mealy machine violating the formula, as the transitions leading to a cycle and the cycle
+    fn inner_apply<'a>(&self, graph: &'a Graph) -> Result<Option<Lasso<'a>>> {
+        let root = match graph.get_root() {
+            Some(root) => root,
+            None => return Err(Error::graph(graph.get_name(), "no starting state on the mealy machine")),
+        };
+        let buchi = Buchi::new(&self.formula.nnf(false));
+        let product = Product::new(graph, &self.formula, &buchi, root);
+        let transitions = |path: Vec<ProductEdge<'a>>| path.into_iter().filter_map(|(_, t)| t).collect();
+        Ok(product
+            .accepting_lasso(&buchi)
+            .map(|(prefix, cycle)| (transitions(prefix), transitions(cycle))))
+    }
+}
+
+impl Rule for LinearTemporal {
+    fn from_reader(reader: &mut dyn Reader, name: String) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let formula = super::read_formula(reader, "LTL", ltl::parse)?;
+        Ok(LinearTemporal { formula, name })
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn lint(&self, graph: &Graph) -> Vec<String> {
+        lint::unmatched(graph, self.formula.atoms.iter())
+    }
+
+    fn apply(&mut self, graph: &Graph, output_folder: &mut std::path::PathBuf) -> Result<RuleResult> {
+        let lasso = self.inner_apply(graph)?;
+        let to_transitions = |edges: &[(&MultiEdge, usize)]| -> Vec<Transition> {
+            edges
+                .iter()
+                .map(|(edge, label_index)| {
+                    Transition::new(edge.get_source(), edge.get_dest(), &edge.get_labels()[*label_index])
+                })
+                .collect()
+        };
+        let mut result = match &lasso {
+            Some((prefix, cycle)) => {
+                let mut result = RuleResult::from_violations(
+                    self.get_name(),
+                    to_transitions(prefix)
+                        .into_iter()
+                        .chain(to_transitions(cycle))
+                        .collect(),
+                );
+                //the run may stop in the initial state without any transition
+                result.holds = false;
+                result
+            }
+            None => RuleResult::from_violations(self.get_name(), Vec::new()),
+        };
+        output_folder.push(self.get_name());
+        if let Err(e) = std::fs::create_dir_all(&output_folder) {
+            return Err(Error::io(output_folder.clone(), e));
+        }
+        if let Some((prefix, cycle)) = lasso {
+            let mut nodes = HashSet::new();
+            let mut edges: HashMap<&MultiEdge, Vec<usize>> = HashMap::new();
+            for (edge, label_index) in prefix.iter().chain(cycle.iter()) {
+                nodes.insert(edge.get_source().clone());
+                nodes.insert(edge.get_dest().clone());
+                let indexes = edges.entry(edge).or_default();
+                if !indexes.contains(label_index) {
+                    indexes.push(*label_index);
+                }
+            }
+            let edges = edges.into_iter().collect();
+            match utils::output::write_files(graph, nodes, edges, output_folder) {
+                Ok(path) => result.add_counterexample(path),
+                Err(e) => return Err(Error::io(output_folder.clone(), e)),
+            }
+            let traces = vec![Trace::lasso(to_transitions(&prefix), to_transitions(&cycle))];
+            match utils::output::write_traces(&traces, output_folder) {
+                Ok(paths) => result.add_traces(traces, paths),
+                Err(e) => return Err(Error::io(output_folder.clone(), e)),
+            }
+        }
+        output_folder.pop();
+        Ok(result)
+    }
+
+    #[cfg(test)]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        graph::Graph,
+        utils::reader::test_reader::TestReader,
+        verifier::{
+            rules::{parse_reader, parse_rule_from_str},
+            trace::Trace,
+        },
+    };
+
+    use super::LinearTemporal;
+
+    fn lasso(graph: &Graph, rule: &str) -> Option<Trace> {
+        let rule = parse_rule_from_str(rule);
+        let rule = rule[0]
+            .as_any()
+            .downcast_ref::<LinearTemporal>()
+            .expect("expect LTL rule");
+        rule.inner_apply(graph).unwrap().map(|(prefix, cycle)| {
+            let transitions = |edges: Vec<(&crate::graph::multi_edge::MultiEdge, usize)>| {
+                edges
+                    .into_iter()
+                    .map(|(edge, index)| {
+                        crate::verifier::result::Transition::new(
+                            edge.get_source(),
+                            edge.get_dest(),
+                            &edge.get_labels()[index],
+                        )
+                    })
+                    .collect()
+            };
+            Trace::lasso(transitions(prefix), transitions(cycle))
+        })
+    }
+
+    #[test]
+    fn test_linear_temporal() {
+        let input = r#"digraph "Automata" {
+            "0" [shape=doublecircle];
+            "0" -> "1" [label="open/ok"];
+            "0" -> "0" [label="close/nok"];
+            "1" -> "2" [label="read/data"];
+            "1" -> "0" [label="close/ok"];
+            "2" -> "2" [label="read/data"];
+            "2" -> "0" [label="close/ok"];
+        }"#;
+        //    ┌─────────┐ close/nok
+        //    │    0    │ ◀────────┐
+        //    └─────────┘ ─────────┘
+        //      │     ▲ ▲
+        //      │open │ │ close/ok
+        //      ▼     │ │
+        //    ┌─────────┐
+        //    │    1    │  close/ok from 2 as well
+        //    └─────────┘
+        //      │ read/data
+        //      ▼
+        //    ┌─────────┐ read/data
+        //    │    2    │ ◀───────┐
+        //    └─────────┘ ────────┘
+        let graph = Graph::new(input, true);
+
+        let holds = "LTL:no_read_before_open\n!\"read/*\" U \"open/*\" | G !\"read/*\"\n:LTL";
+        assert!(lasso(&graph, holds).is_none());
+
+        //the session may stay open forever by reading
+        let trace = lasso(&graph, "LTL:closed\nG(\"open/ok\" -> F \"close/ok\")\n:LTL").unwrap();
+        assert_eq!(trace.inputs, vec!["open", "read", "read"]);
+        assert_eq!(trace.loop_start, Some(2));
+        assert_eq!(
+            trace.to_string(),
+            "# lasso, the inputs after the cycle mark are repeated forever\nopen / ok\nread / data\n# cycle\nread / data\n"
+        );
+
+        //every run opens a session, except the one closing forever in the initial state
+        let trace = lasso(&graph, "LTL:opened\nF \"open/*\"\n:LTL").unwrap();
+        assert_eq!(trace.inputs, vec!["close", "close"]);
+        assert_eq!(trace.loop_start, Some(1));
+        assert!(lasso(&graph, "LTL:next\n\"close/*\" | X(\"read/*\" | \"close/*\")\n:LTL").is_none());
+
+        //a state without outgoing transition stops the run, no event holds there afterwards
+        let dead_end = Graph::new(
+            r#"digraph { "0" [shape=doublecircle]; "0" -> "1" [label="a/b"]; }"#,
+            true,
+        );
+        let trace = lasso(&dead_end, "LTL:forever\nG F \"a/b\"\n:LTL").unwrap();
+        assert_eq!(trace.inputs, vec!["a"]);
+        assert_eq!(trace.loop_start, Some(1));
+        assert!(trace
+            .to_string()
+            .ends_with("# cycle: no transition leaves the last state\n"));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let rules = "LTL:unknown\n  G \"a/b\" W \"c/d\"\n:LTL\nLTL:two\nF \"a/b\"\nG \"a/b\"\n:LTL\nLTL:empty\n:LTL";
+        let mut reader = TestReader::from_text(rules).unwrap();
+        let (rules, errors) = parse_reader(&mut reader, "rules");
+        assert!(rules.is_empty());
+        let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "rules:2:9: unknown operator W, events are written between double quotes",
+                "rules:6:1: a LTL rule has one formula, unexpected line: G \"a/b\"",
+                "rules:9:1: no formula given for LTL rule",
+            ]
+        );
+    }
+}
@@ -1,43 +1,79 @@
+mod bounded_response;
+mod branching_temporal;
 mod conditional;
+mod counting;
+mod expected_cycle;
 mod expected_event_index;
 mod expected_event_sequence;
+mod linear_temporal;
 mod output;
+mod reachability;
+mod regular_word;
 mod restricted_events;
+mod severity;
 mod sink;
+mod state_invariant;
 mod unexpected_cycle;
+use core::fmt::Debug;
 #[cfg(test)]
 use std::any::Any;
+use std::collections::HashMap;
 use std::path::PathBuf;
-use core::fmt::Debug;
 
 use crate::{
+    error::{column, Error, Result},
     graph::prelude::*,
     utils::reader::{Reader, ReaderFile},
 };
 
+use super::{result::RuleResult, temporal::Formula};
+
 use self::{
+    bounded_response::BoundedResponse,
+    branching_temporal::BranchingTemporal,
     conditional::Conditional,
+    counting::Counting,
+    expected_cycle::ExpectedCycle,
     expected_event_index::ExpectedTransitionIndex,
     expected_event_sequence::ExpectedTransitionSequence,
+    linear_temporal::LinearTemporal,
     output::Output,
+    reachability::{Reachable, Unreachable},
+    regular_word::{ForbiddenWord, RequiredWord},
     restricted_events::RestrictedEvents,
+    severity::WithSeverity,
     sink::{SinkDescription, SinkTarget},
+    state_invariant::StateInvariant,
     unexpected_cycle::UnexpectedCycle,
 };
 
-pub(crate) trait Rule: Debug {
+pub use self::severity::Severity;
+
+pub trait Rule: Debug {
     ///A mathod to read the rule from a reader. The name is given.
-    fn from_reader(reader: &mut dyn Reader, name: String) -> std::io::Result<Self>
+    fn from_reader(reader: &mut dyn Reader, name: String) -> Result<Self>
     where
         Self: Sized;
 
     ///Get the name of the rule
     fn get_name(&self) -> &str;
 
+    ///Severity given in the header of the rule, if any
+    fn get_severity(&self) -> Option<Severity> {
+        None
+    }
+
+    ///Warnings on the rule for the graph, made before applying it: the patterns matching no symbol of the graph,
+    /// most likely typos, and the events the rule depends on that never occur, making it hold trivially.
+    fn lint(&self, _graph: &Graph) -> Vec<String> {
+        Vec::new()
+    }
+
     ///Apply the rule to obtain the output subgrpah
     /// The rules has to write the files within apply
     /// the output folder is the path to the folder where the output of the rules has to be.
-    fn apply(&mut self, graph: &Graph, output_folder: &mut PathBuf);
+    /// The verdict is returned with the violating transitions and the files written.
+    fn apply(&mut self, graph: &Graph, output_folder: &mut PathBuf) -> Result<RuleResult>;
     // fn as_any(&self) -> &dyn Any ;
     // fn inner_apply(&mut self,graph:&Graph,cycles: &HashMap<NodeId, Vec<Cycle>>)->;
     #[cfg(test)]
@@ -45,95 +81,151 @@ pub(crate) trait Rule: Debug {
 }
 
 //Rule parsing
-fn get_name(line: &str) -> String {
+//the name is the folder of the results of the rule, hence it can not be a path
+fn get_name(line: &str) -> Result<String> {
     let el: Vec<&str> = line.split(":").collect();
-    match el.get(1) {
-        Some(name) => {
-            if name.trim().len() == 0 {
-                panic!("Rules need a name")
-            }
-            name.trim().to_string()
+    match el.get(1).map(|name| name.trim()) {
+        None | Some("") => Err(Error::parse(line.len() + 1, "Rules need a name")),
+        Some(name) if name.contains(['/', '\\']) || name == "." || name == ".." => Err(Error::parse(
+            column(line, name),
+            format!("invalid rule name {}, a name can not be a path", name),
+        )),
+        Some(name) => Ok(name.to_string()),
+    }
+}
+
+//the severity is the optional third field of the header
+fn get_severity(line: &str) -> Result<Option<Severity>> {
+    match line.splitn(3, ':').nth(2).map(str::trim) {
+        Some(severity) if !severity.is_empty() => {
+            let column = line.rfind(':').map_or(1, |index| index + 2);
+            severity.parse().map(Some).map_err(|e: Error| e.at_column(column))
         }
-        None => panic!("Rules need a name"),
+        _ => Ok(None),
     }
 }
 
-pub(crate) fn parse_file(path_to_file: &str) -> Vec<Box<dyn Rule>> {
-    let mut reader = match ReaderFile::open(path_to_file) {
-        Ok(reader) => reader,
-        Err(e) => {
-            panic!("Error while reading rule file '{}' : {}", path_to_file, e.to_string())
+///Read the formula of a rule written on one line, up to the end of the rule `:kind`.
+fn read_formula<F>(reader: &mut dyn Reader, kind: &str, parse: fn(&str) -> Result<Formula<F>>) -> Result<Formula<F>> {
+    let end = format!(":{}", kind);
+    let mut formula: Option<Formula<F>> = None;
+    while let Some(raw_line) = reader.read_line() {
+        let line = raw_line.trim();
+        if line.starts_with(&end) {
+            break;
+        } else if line.is_empty() {
+            continue;
+        } else if formula.is_some() {
+            return Err(Error::parse(
+                column(raw_line, line),
+                format!("a {} rule has one formula, unexpected line: {}", kind, line),
+            ));
         }
+        let start = column(raw_line, line) - 1;
+        formula = Some(parse(line).map_err(|e| match e {
+            Error::Parse { location, message } => Error::parse(location.column + start, message),
+            e => e,
+        })?);
+    }
+    match formula {
+        Some(formula) => Ok(formula),
+        None => Err(Error::parse(0, format!("no formula given for {} rule", kind))),
+    }
+}
+
+fn boxed<R: Rule + 'static>(rule: Result<R>, severity: Option<Severity>) -> Result<Box<dyn Rule>> {
+    rule.map(|rule| match severity {
+        Some(_) => Box::new(WithSeverity { rule, severity }) as Box<dyn Rule>,
+        None => Box::new(rule) as Box<dyn Rule>,
+    })
+}
+
+//kinds of rules, the first field of their header
+const KINDS: [&str; 18] = [
+    "UC", "EC", "SD", "ST", "CT", "ETS", "ETI", "OR", "RE", "LTL", "CTL", "FW", "RW", "BR", "CNT", "REACH", "NOREACH",
+    "SI",
+];
+
+///Parse the rule starting at the header line, None if the line is not a rule header.
+fn parse_rule(line: &str, reader: &mut dyn Reader) -> Option<Result<Box<dyn Rule>>> {
+    let kind = match line.split_once(':') {
+        Some((kind, _)) => kind.to_string(),
+        None => return None,
+    };
+    if !KINDS.contains(&kind.as_str()) {
+        return None;
+    }
+    let name = match get_name(line.trim_end()) {
+        Ok(name) => name,
+        Err(e) => return Some(Err(e)),
+    };
+    let severity = match get_severity(line.trim_end()) {
+        Ok(severity) => severity,
+        Err(e) => return Some(Err(e)),
+    };
+    let rule = match kind.as_str() {
+        "UC" => boxed(UnexpectedCycle::from_reader(reader, name), severity),
+        "EC" => boxed(ExpectedCycle::from_reader(reader, name), severity),
+        "SD" => boxed(SinkDescription::from_reader(reader, name), severity),
+        "ST" => boxed(SinkTarget::from_reader(reader, name), severity),
+        "CT" => boxed(Conditional::from_reader(reader, name), severity),
+        "ETS" => boxed(ExpectedTransitionSequence::from_reader(reader, name), severity),
+        "ETI" => boxed(ExpectedTransitionIndex::from_reader(reader, name), severity),
+        "OR" => boxed(Output::from_reader(reader, name), severity),
+        "LTL" => boxed(LinearTemporal::from_reader(reader, name), severity),
+        "CTL" => boxed(BranchingTemporal::from_reader(reader, name), severity),
+        "FW" => boxed(ForbiddenWord::from_reader(reader, name), severity),
+        "RW" => boxed(RequiredWord::from_reader(reader, name), severity),
+        "BR" => boxed(BoundedResponse::from_reader(reader, name), severity),
+        "CNT" => boxed(Counting::from_reader(reader, name), severity),
+        "REACH" => boxed(Reachable::from_reader(reader, name), severity),
+        "NOREACH" => boxed(Unreachable::from_reader(reader, name), severity),
+        "SI" => boxed(StateInvariant::from_reader(reader, name), severity),
+        _ => boxed(RestrictedEvents::from_reader(reader, name), severity),
     };
+    Some(rule)
+}
+
+///Parse every rule of the reader, a malformed rule is reported and the parsing goes on with the next one.
+fn parse_reader(reader: &mut dyn Reader, source: &str) -> (Vec<Box<dyn Rule>>, Vec<Error>) {
     let mut ret: Vec<Box<dyn Rule>> = Vec::new();
+    let mut errors = Vec::new();
+    //line of the header of each rule, two rules with the same name would share their results folder
+    let mut names: HashMap<String, usize> = HashMap::new();
     while let Some(line) = reader.read_line() {
-        if line.starts_with("UC:") {
-            let name = get_name(line);
-            match UnexpectedCycle::from_reader(&mut reader, name) {
-                Ok(rule) => ret.push(Box::new(rule)),
-                Err(e) => {
-                    panic!("failed to parse rule file:{}", e.to_string())
-                }
-            }
-        } else if line.starts_with("SD:") {
-            let name = get_name(line);
-            match SinkDescription::from_reader(&mut reader, name) {
-                Ok(rule) => ret.push(Box::new(rule)),
-                Err(e) => {
-                    panic!("failed to parse rule file:{}", e.to_string())
-                }
-            }
-        } else if line.starts_with("ST:") {
-            let name = get_name(line);
-            match SinkTarget::from_reader(&mut reader, name) {
-                Ok(rule) => ret.push(Box::new(rule)),
-                Err(e) => {
-                    panic!("failed to parse rule file:{}", e.to_string())
-                }
-            }
-        } else if line.starts_with("CT:") {
-            let name = get_name(line);
-            match Conditional::from_reader(&mut reader, name) {
-                Ok(rule) => ret.push(Box::new(rule)),
-                Err(e) => {
-                    panic!("failed to parse rule file:{}", e.to_string())
-                }
-            }
-        } else if line.starts_with("ETS:") {
-            let name = get_name(line);
-            match ExpectedTransitionSequence::from_reader(&mut reader, name) {
-                Ok(rule) => ret.push(Box::new(rule)),
-                Err(e) => {
-                    panic!("failed to parse rule file:{}", e.to_string())
-                }
-            }
-        } else if line.starts_with("ETI:") {
-            let name = get_name(line);
-            match ExpectedTransitionIndex::from_reader(&mut reader, name) {
-                Ok(rule) => ret.push(Box::new(rule)),
-                Err(e) => {
-                    panic!("failed to parse rule file:{}", e.to_string())
+        let header = line.clone();
+        let header_line = reader.line_number();
+        match parse_rule(&header, reader) {
+            Some(Ok(rule)) => match names.get(rule.get_name()) {
+                Some(first) => {
+                    let message = format!(
+                        "duplicate rule name {}, already used on line {}",
+                        rule.get_name(),
+                        first
+                    );
+                    let error = Error::parse(column(&header, rule.get_name()), message);
+                    errors.push(error.locate(source, header_line));
                 }
-            }
-        } else if line.starts_with("OR:") {
-            let name = get_name(line);
-            match Output::from_reader(&mut reader, name) {
-                Ok(rule) => ret.push(Box::new(rule)),
-                Err(e) => {
-                    panic!("failed to parse rule file:{}", e.to_string())
+                None => {
+                    names.insert(rule.get_name().to_string(), header_line);
+                    ret.push(rule);
                 }
-            }
-        } else if line.starts_with("RE:") {
-            let name = get_name(line);
-            match RestrictedEvents::from_reader(&mut reader, name) {
-                Ok(rule) => ret.push(Box::new(rule)),
-                Err(e) => {
-                    panic!("failed to parse rule file:{}", e.to_string())
-                }
-            }
+            },
+            //the reader stopped on the line that could not be parsed
+            Some(Err(e)) => errors.push(e.locate(source, reader.line_number())),
+            None => (),
         }
     }
-    ret
+    (ret, errors)
+}
+
+///Parse the rules of a file, the rules that could be parsed are returned with the errors of the other ones.
+pub fn parse_file(path_to_file: &str) -> (Vec<Box<dyn Rule>>, Vec<Error>) {
+    let mut reader = match ReaderFile::open(path_to_file) {
+        Ok(reader) => reader,
+        Err(e) => return (Vec::new(), vec![Error::io(path_to_file, e)]),
+    };
+    parse_reader(&mut reader, path_to_file)
 }
 
 #[cfg(test)]
@@ -144,74 +236,93 @@ pub(crate) fn parse_rule_from_str(rules_str: &str) -> Vec<Box<dyn Rule>> {
         Ok(r) => r,
         Err(_) => panic!("unable to create the test reader to parse rule"),
     };
-    let mut ret: Vec<Box<dyn Rule>> = Vec::new();
-    while let Some(line) = reader.read_line() {
-        if line.starts_with("UC:") {
-            let name = get_name(line);
-            match UnexpectedCycle::from_reader(&mut reader, name) {
-                Ok(rule) => ret.push(Box::new(rule)),
-                Err(e) => {
-                    panic!("failed to parse rule file:{}", e.to_string())
-                }
-            }
-        } else if line.starts_with("SD:") {
-            let name = get_name(line);
-            match SinkDescription::from_reader(&mut reader, name) {
-                Ok(rule) => ret.push(Box::new(rule)),
-                Err(e) => {
-                    panic!("failed to parse rule file:{}", e.to_string())
-                }
-            }
-        } else if line.starts_with("ST:") {
-            let name = get_name(line);
-            match SinkTarget::from_reader(&mut reader, name) {
-                Ok(rule) => ret.push(Box::new(rule)),
-                Err(e) => {
-                    panic!("failed to parse rule file:{}", e.to_string())
-                }
-            }
-        } else if line.starts_with("CT:") {
-            let name = get_name(line);
-            match Conditional::from_reader(&mut reader, name) {
-                Ok(rule) => ret.push(Box::new(rule)),
-                Err(e) => {
-                    panic!("failed to parse rule file:{}", e.to_string())
-                }
-            }
-        } else if line.starts_with("ETS:") {
-            let name = get_name(line);
-            match ExpectedTransitionSequence::from_reader(&mut reader, name) {
-                Ok(rule) => ret.push(Box::new(rule)),
-                Err(e) => {
-                    panic!("failed to parse rule file:{}", e.to_string())
-                }
-            }
-        } else if line.starts_with("ETI:") {
-            let name = get_name(line);
-            match ExpectedTransitionIndex::from_reader(&mut reader, name) {
-                Ok(rule) => ret.push(Box::new(rule)),
-                Err(e) => {
-                    panic!("failed to parse rule file:{}", e.to_string())
-                }
-            }
-        } else if line.starts_with("OR:") {
-            let name = get_name(line);
-            match Output::from_reader(&mut reader, name) {
-                Ok(rule) => ret.push(Box::new(rule)),
-                Err(e) => {
-                    panic!("failed to parse rule file:{}", e.to_string())
-                }
-            }
-        } else if line.starts_with("RE:") {
-            let name = get_name(line);
-            match RestrictedEvents::from_reader(&mut reader, name) {
-                Ok(rule) => ret.push(Box::new(rule)),
-                Err(e) => {
-                    panic!("failed to parse rule file:{}", e.to_string())
-                }
-            }
-        }
+    let (ret, errors) = parse_reader(&mut reader, "test");
+    if let Some(e) = errors.first() {
+        panic!("failed to parse rule file:{}", e)
     }
-
     ret
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::reader::test_reader::TestReader;
+
+    use super::{parse_reader, Severity};
+
+    #[test]
+    fn parse_errors_do_not_stop_parsing() {
+        let rules = r#"UC:missing_slash
+        a
+        :UC
+        RE:
+        A:a/b
+        R:c/d
+        :RE
+        ETI:bad_index
+        a/b
+        x
+        :ETI
+        OR:valid
+        I:0
+        O:1
+        :OR"#;
+        let mut reader = TestReader::from_text(rules).unwrap();
+        let (rules, errors) = parse_reader(&mut reader, "rules");
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].get_name(), "valid");
+        let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "rules:2:1: failed to parse unexpected cycle rule: a",
+                "rules:4:4: Rules need a name",
+                "rules:10:1: failed to parse index of ExpectedTransitionIndex rule x: invalid digit found in string",
+            ]
+        );
+    }
+
+    #[test]
+    fn rule_severity() {
+        let rules = r#"OR:graded:high
+        I:0
+        O:1
+        :OR
+        OR:ungraded
+        I:0
+        O:1
+        :OR
+        OR:misgraded:urgent
+        I:0
+        O:1
+        :OR"#;
+        let mut reader = TestReader::from_text(rules).unwrap();
+        let (rules, errors) = parse_reader(&mut reader, "rules");
+        let severities: Vec<(&str, Option<Severity>)> =
+            rules.iter().map(|r| (r.get_name(), r.get_severity())).collect();
+        assert_eq!(severities, vec![("graded", Some(Severity::High)), ("ungraded", None)]);
+        assert_eq!(
+            errors[0].to_string(),
+            "rules:9:14: unknown severity urgent, expected low, medium or high"
+        );
+    }
+
+    #[test]
+    fn rule_names() {
+        let rule = |name: &str| format!("OR:{}\nI:0\nO:1\n:OR\n", name);
+        let rules: String = ["/tmp/victim", "..", ".", "a\\b", "valid", "valid"].map(rule).concat();
+        let mut reader = TestReader::from_text(&rules).unwrap();
+        let (rules, errors) = parse_reader(&mut reader, "rules");
+        assert_eq!(rules.len(), 1);
+        let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "rules:1:4: invalid rule name /tmp/victim, a name can not be a path",
+                "rules:5:4: invalid rule name .., a name can not be a path",
+                "rules:9:4: invalid rule name ., a name can not be a path",
+                "rules:13:4: invalid rule name a\\b, a name can not be a path",
+                "rules:21:4: duplicate rule name valid, already used on line 17",
+            ]
+        );
+    }
+}
@@ -1,7 +1,11 @@
 use std::collections::HashSet;
 
-use super::{Graph, MultiEdge, NodeId, Rule};
-use crate::{utils, verifier::event::Pattern};
+use super::{Graph, MultiEdge, NodeId, Rule, RuleResult};
+use crate::{
+    error::{column, Error, Result},
+    utils,
+    verifier::{event::Pattern, lint},
+};
 #[cfg(test)]
 use std::any::Any;
 #[derive(Debug)]
@@ -22,9 +26,9 @@ impl Output {
             for allowed_outputs in &self.allowed_outputs {
                 res = res || allowed_outputs.check(output);
             }
-            return res;
+            res
         } else {
-            return true;
+            true
         }
     }
 
@@ -38,7 +42,7 @@ impl Output {
             }
             let mut indexes = Vec::new();
             for (index, label) in edge.get_labels().iter().enumerate() {
-                if !self.allowed_event(&label) {
+                if !self.allowed_event(label) {
                     indexes.push(index);
                 }
             }
@@ -53,29 +57,37 @@ impl Output {
 }
 
 impl Rule for Output {
-    fn from_reader(reader: &mut dyn crate::utils::reader::Reader, name: String) -> std::io::Result<Self>
+    fn from_reader(reader: &mut dyn crate::utils::reader::Reader, name: String) -> Result<Self>
     where
         Self: Sized,
     {
         let mut input = None;
-        let mut allowed_outputs: Vec<String> = Vec::new();
-        while let Some(line) = reader.read_line() {
-            let line = line.trim_start();
+        let mut allowed_outputs: Vec<Pattern> = Vec::new();
+        while let Some(raw_line) = reader.read_line() {
+            let line = raw_line.trim_start();
             if line.starts_with("I:") {
                 let e: Vec<_> = line.split(":").collect();
-                input = Some(e[1].trim().to_string());
+                let value = e[1].trim();
+                input = Some(Pattern::from_str(value).map_err(|e| e.at_column(column(raw_line, value)))?);
             } else if line.starts_with("O:") {
                 let e: Vec<_> = line.split(":").collect();
-                allowed_outputs.push(e[1].trim().to_string());
+                let value = e[1].trim();
+                allowed_outputs.push(Pattern::from_str(value).map_err(|e| e.at_column(column(raw_line, value)))?);
             } else if line.starts_with(":OR") {
                 break;
             } else {
-                panic!("failed to parse Message Reject Rule:{}", line);
+                return Err(Error::parse(
+                    column(raw_line, line),
+                    format!("failed to parse Message Reject Rule:{}", line.trim()),
+                ));
             }
         }
-        let allowed_outputs = allowed_outputs.iter().map(|e| Pattern::from_str(e)).collect();
+        let input = match input {
+            Some(input) => input,
+            None => return Err(Error::parse(0, "you must specify the input of the output rule")),
+        };
         Ok(Output {
-            input: Pattern::from_str(&input.as_ref().unwrap()),
+            input,
             allowed_outputs,
             name,
         })
@@ -85,28 +97,30 @@ impl Rule for Output {
         &self.name
     }
 
-    fn apply(
-        &mut self,
-        graph: &super::Graph,
-        output_folder: &mut std::path::PathBuf,
-    ) {
+    fn lint(&self, graph: &Graph) -> Vec<String> {
+        let mut warnings = lint::unmatched_patterns(graph, [&self.input], self.allowed_outputs.iter());
+        let input = format!("input {}", self.input);
+        let matches = |label: &str| self.input.check(label.split('/').next().unwrap_or(label).trim());
+        warnings.extend(lint::never_occurs(graph, input, matches));
+        warnings
+    }
+
+    fn apply(&mut self, graph: &super::Graph, output_folder: &mut std::path::PathBuf) -> Result<RuleResult> {
         let (nodes, edges) = self.inner_apply(graph);
+        let mut result = RuleResult::from_edges(self.get_name(), edges.iter());
 
         output_folder.push(self.get_name());
         // println!("graph: {}",graph.get_name());
         if let Err(e) = std::fs::create_dir_all(&output_folder) {
-            panic!("failed to create output directory due to :{}", e.to_string());
+            return Err(Error::io(output_folder.clone(), e));
         }
 
-        let ret = utils::output::write_files(graph, nodes, edges, output_folder);
-        if let Err(e) = ret {
-            panic!(
-                "unable to output the result of rule {} due to {}",
-                self.get_name(),
-                e.to_string()
-            );
+        match utils::output::write_files(graph, nodes, edges, output_folder) {
+            Ok(path) => result.add_counterexample(path),
+            Err(e) => return Err(Error::io(output_folder.clone(), e)),
         }
         output_folder.pop();
+        Ok(result)
     }
 
     #[cfg(test)]
@@ -172,7 +186,7 @@ mod tests {
         assert!(nodes.contains(&NodeId::new("1")));
         let edges_expected = r#"
         "0"->"1" [fontsize=5,label="0/osef"];"#;
-        let edges_expected = edges_expected.split(";\n").into_iter().map(|e| e.trim());
+        let edges_expected = edges_expected.split(";\n").map(|e| e.trim());
         let mut expected_edge_map = EdgeMap::new();
         for edge_str in edges_expected {
             expected_edge_map.add_edge_from_str(edge_str);
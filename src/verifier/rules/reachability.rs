@@ -0,0 +1,312 @@
+use std::collections::HashSet;
+
+use crate::{
+    error::{column, Error, Result},
+    graph::nodeid::NodeId,
+    utils::{self, reader::Reader},
+    verifier::{
+        event::Events,
+        lint,
+        result::Transition,
+        trace::{self, Trace},
+    },
+};
+
+use super::{Graph, Rule, RuleResult};
+#[cfg(test)]
+use std::any::Any;
+
+/// A transition matching the event has to be reachable from the initial state.
+#[derive(Debug)]
+pub(crate) struct Reachable {
+    name: String,
+    target: Target,
+}
+
+/// No transition matching the event may be reachable from the initial state.
+#[derive(Debug)]
+pub(crate) struct Unreachable {
+    name: String,
+    target: Target,
+}
+
+//transitions looked for, with the events the paths leading to them have to traverse and the ones they avoid
+#[derive(Debug)]
+struct Target {
+    events: Events,
+    traversed: Vec<Events>,
+    avoided: Option<Events>,
+}
+
+impl Target {
+    fn from_reader(reader: &mut dyn Reader, kind: &str) -> Result<Self> {
+        let mut events: Option<Events> = None;
+        let mut traversed: Vec<Events> = Vec::new();
+        let mut avoided: Option<Events> = None;
+        while let Some(raw_line) = reader.read_line() {
+            let line = raw_line.trim_start();
+            if line.starts_with("E:") || line.starts_with("T:") || line.starts_with("A:") {
+                let split_line: Vec<_> = line.split(":").collect();
+                if split_line.len() != 2 {
+                    return Err(Error::parse(
+                        column(raw_line, line),
+                        format!("failed to parse Reachability events: {}", line.trim()),
+                    ));
+                }
+                let value = split_line[1].trim();
+                let parsed = Events::from_str(value).map_err(|e| e.at_column(column(raw_line, value)))?;
+                if line.starts_with("E:") {
+                    events = Some(parsed);
+                } else if line.starts_with("T:") {
+                    traversed.push(parsed);
+                } else {
+                    avoided = Some(parsed);
+                }
+            } else if line.starts_with(&format!(":{}", kind)) {
+                break;
+            } else {
+                return Err(Error::parse(
+                    column(raw_line, line),
+                    format!("failed to parse until Rule:{}", line.trim()),
+                ));
+            }
+        }
+        let events = match events {
+            Some(events) => events,
+            None => return Err(Error::parse(0, "you must specify the event for reachability rule")),
+        };
+        Ok(Target {
+            events,
+            traversed,
+            avoided,
+        })
+    }
+
+    fn lint(&self, graph: &Graph) -> Vec<String> {
+        let traversed = self.traversed.iter().flat_map(Events::iter);
+        let avoided = self.avoided.iter().flat_map(Events::iter);
+        lint::unmatched(graph, self.events.iter().chain(traversed).chain(avoided))
+    }
+
+    fn avoided(&self, event_str: &str) -> bool {
+        match &self.avoided {
+            Some(avoided) => avoided.check_all(event_str),
+            None => false,
+        }
+    }
+
+    //shortest trace to every transition matching the event after the traversed ones,
+    //with the states reachable from the initial state without the avoided events, in the order of the graph
+    fn shortest_traces(&self, graph: &Graph) -> (Vec<Trace>, Vec<NodeId>) {
+        let mut reached: HashSet<NodeId> = graph.get_root().into_iter().cloned().collect();
+        //for every group of events to traverse, whether one of them was seen
+        let init = vec![false; self.traversed.len()];
+        let traces = trace::shortest_traces(graph, init, |seen: &Vec<bool>, neighbor_id, label| {
+            let found = seen.iter().all(|seen| *seen) && self.events.check_all(label);
+            if self.avoided(label) {
+                return (found, None);
+            }
+            reached.insert(neighbor_id.clone());
+            let seen = seen
+                .iter()
+                .zip(self.traversed.iter())
+                .map(|(seen, traversed)| *seen || traversed.check_all(label))
+                .collect();
+            (found, Some(seen))
+        });
+        let reached = graph
+            .iter_node_id()
+            .filter(|id| reached.contains(*id))
+            .cloned()
+            .collect();
+        (traces, reached)
+    }
+}
+
+//the states explored without finding the event, written in `reachable.txt`
+fn write_proof(reached: &[NodeId], output_folder: &mut std::path::PathBuf) -> std::io::Result<std::path::PathBuf> {
+    let mut text = String::from(
+        "# no transition matching the event is reachable, these are all the states reachable from the initial state without the avoided events\n",
+    );
+    for node_id in reached {
+        text.push_str(&format!("{}\n", node_id));
+    }
+    utils::output::write_text("reachable.txt", &text, output_folder)
+}
+
+impl Rule for Reachable {
+    fn from_reader(reader: &mut dyn Reader, name: String) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let target = Target::from_reader(reader, "REACH")?;
+        Ok(Reachable { name, target })
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn lint(&self, graph: &Graph) -> Vec<String> {
+        self.target.lint(graph)
+    }
+
+    fn apply(&mut self, graph: &super::Graph, output_folder: &mut std::path::PathBuf) -> Result<RuleResult> {
+        if graph.get_root().is_none() {
+            return Err(Error::graph(graph.get_name(), "no starting state on the mealy machine"));
+        }
+        let (traces, reached) = self.target.shortest_traces(graph);
+        let witness: Option<Vec<Transition>> = traces
+            .into_iter()
+            .min_by_key(|trace| trace.transitions.len())
+            .map(|trace| trace.transitions);
+        let mut result = RuleResult::from_witness(self.get_name(), witness.clone());
+        output_folder.push(self.get_name());
+        if let Err(e) = std::fs::create_dir_all(&output_folder) {
+            return Err(Error::io(output_folder.clone(), e));
+        }
+        let written = match witness {
+            Some(witness) => utils::output::write_transitions(graph, &witness, output_folder, "witness.dot"),
+            None => write_proof(&reached, output_folder).map(Some),
+        };
+        match written {
+            Ok(path) => result.add_counterexample(path),
+            Err(e) => return Err(Error::io(output_folder.clone(), e)),
+        }
+        output_folder.pop();
+        Ok(result)
+    }
+
+    #[cfg(test)]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Rule for Unreachable {
+    fn from_reader(reader: &mut dyn Reader, name: String) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let target = Target::from_reader(reader, "NOREACH")?;
+        Ok(Unreachable { name, target })
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn lint(&self, graph: &Graph) -> Vec<String> {
+        self.target.lint(graph)
+    }
+
+    fn apply(&mut self, graph: &super::Graph, output_folder: &mut std::path::PathBuf) -> Result<RuleResult> {
+        if graph.get_root().is_none() {
+            return Err(Error::graph(graph.get_name(), "no starting state on the mealy machine"));
+        }
+        let (traces, reached) = self.target.shortest_traces(graph);
+        let violations = traces.iter().map(|trace| trace.violation().clone()).collect();
+        let mut result = RuleResult::from_violations(self.get_name(), violations);
+        output_folder.push(self.get_name());
+        if let Err(e) = std::fs::create_dir_all(&output_folder) {
+            return Err(Error::io(output_folder.clone(), e));
+        }
+        if traces.is_empty() {
+            match write_proof(&reached, output_folder) {
+                Ok(path) => result.add_counterexample(Some(path)),
+                Err(e) => return Err(Error::io(output_folder.clone(), e)),
+            }
+        }
+        let transitions: Vec<Transition> = traces.iter().flat_map(|t| t.transitions.iter().cloned()).collect();
+        match utils::output::write_transitions(graph, &transitions, output_folder, "ce.dot") {
+            Ok(path) => result.add_counterexample(path),
+            Err(e) => return Err(Error::io(output_folder.clone(), e)),
+        }
+        match utils::output::write_traces(&traces, output_folder) {
+            Ok(paths) => result.add_traces(traces, paths),
+            Err(e) => return Err(Error::io(output_folder.clone(), e)),
+        }
+        output_folder.pop();
+        Ok(result)
+    }
+
+    #[cfg(test)]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        graph::Graph,
+        utils::reader::test_reader::TestReader,
+        verifier::rules::{parse_reader, parse_rule_from_str},
+    };
+
+    use super::{Reachable, Unreachable};
+
+    #[test]
+    fn test_reachability() {
+        let input = r#"digraph "Automata" {
+            "0" [shape=doublecircle];
+            "0" -> "1" [label="hello/Ack"];
+            "0" -> "3" [label="read_req/ReadRepOK"];
+            "1" -> "2" [label="active_session/AcSesResOK"];
+            "1" -> "0" [label="close/ok"];
+            "2" -> "2" [label="read_req/ReadRepOK"];
+            "2" -> "0" [label="close/ok"];
+        }"#;
+        //0 -hello-> 1 -activate-> 2 -close-> 0, 1 -close-> 0, reads on 2 and from 0 to 3
+        let graph = Graph::new(input, true);
+        let search = |rule: &str| {
+            let rule = parse_rule_from_str(rule);
+            let rule = rule[0].as_any();
+            let (traces, reached) = match rule.downcast_ref::<Reachable>() {
+                Some(rule) => rule.target.shortest_traces(&graph),
+                None => rule
+                    .downcast_ref::<Unreachable>()
+                    .expect("expect a reachability rule")
+                    .target
+                    .shortest_traces(&graph),
+            };
+            let traces: Vec<Vec<String>> = traces.into_iter().map(|trace| trace.inputs).collect();
+            let reached: Vec<String> = reached.iter().map(|id| id.to_string()).collect();
+            (traces, reached)
+        };
+        let (traces, _) = search("REACH:activate\nE: active_session/AcSesResOK\n:REACH");
+        assert_eq!(traces, vec![vec!["hello", "active_session"]]);
+        //the read without session is reachable
+        let (traces, _) = search("NOREACH:read\nE: read_req/ReadRepOK\n:NOREACH");
+        assert_eq!(
+            traces,
+            vec![vec!["read_req"], vec!["hello", "active_session", "read_req"]]
+        );
+        //once a session was activated, even after closing it
+        let (traces, _) = search("NOREACH:read\nE: read_req/ReadRepOK\nT: active_session/AcSesResOK\n:NOREACH");
+        assert_eq!(
+            traces,
+            vec![
+                vec!["hello", "active_session", "close", "read_req"],
+                vec!["hello", "active_session", "read_req"]
+            ]
+        );
+        //nor without hello, the proof lists the states reachable without it
+        let (traces, reached) = search("NOREACH:read\nE: read_req/ReadRepOK\nT: */AcSesResOK\nA: hello/*\n:NOREACH");
+        assert!(traces.is_empty());
+        assert_eq!(reached, vec!["0", "3"]);
+
+        let rules = "REACH:no_event\nT: a/b\n:REACH\nNOREACH:malformed\nE: ab\n:NOREACH";
+        let mut reader = TestReader::from_text(rules).unwrap();
+        let (rules, errors) = parse_reader(&mut reader, "rules");
+        assert!(rules.is_empty());
+        let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "rules:3:1: you must specify the event for reachability rule",
+                "rules:5:4: event parsing error ab, an event is written as input/output",
+            ]
+        );
+    }
+}
@@ -0,0 +1,315 @@
+use std::collections::{HashMap, VecDeque};
+
+use super::{Rule, RuleResult};
+use crate::{
+    error::{column, Error, Result},
+    graph::{nodeid::NodeId, Graph},
+    utils::{self, reader::Reader},
+    verifier::{
+        lint,
+        regular::Expression,
+        result::Transition,
+        trace::{self, path_to, sorted_neighbors, Parents, Trace},
+    },
+};
+#[cfg(test)]
+use std::any::Any;
+
+/// No trace from the initial state may match the expression.
+#[derive(Debug)]
+pub(crate) struct ForbiddenWord {
+    pub(crate) expression: Expression,
+    pub(crate) name: String,
+}
+
+/// Some trace from the initial state has to match the expression.
+#[derive(Debug)]
+pub(crate) struct RequiredWord {
+    pub(crate) expression: Expression,
+    pub(crate) name: String,
+}
+
+//outcome of the search of a trace matching the expression
+#[derive(Debug)]
+enum Search {
+    Found(Vec<Transition>),
+    //the trace getting the closest to a match with the events that would bring it closer
+    Missing(Vec<Transition>, Vec<String>),
+}
+
+//read the expression of a rule, written on one line before the closing tag
+fn read_expression(reader: &mut dyn Reader, kind: &str) -> Result<Expression> {
+    let mut expression: Option<Expression> = None;
+    while let Some(raw_line) = reader.read_line() {
+        let line = raw_line.trim();
+        if line.starts_with(&format!(":{}", kind)) {
+            break;
+        } else if line.is_empty() {
+            continue;
+        } else if expression.is_some() {
+            return Err(Error::parse(
+                column(raw_line, line),
+                format!("a {} rule has one expression, unexpected line: {}", kind, line),
+            ));
+        }
+        let start = column(raw_line, line) - 1;
+        expression = Some(Expression::parse(line).map_err(|e| match e {
+            Error::Parse { location, message } => Error::parse(location.column + start, message),
+            e => e,
+        })?);
+    }
+    match expression {
+        Some(expression) => Ok(expression),
+        None => Err(Error::parse(0, format!("no expression given for {} rule", kind))),
+    }
+}
+
+impl ForbiddenWord {
+    //shortest trace matching the expression for every transition ending such a trace
+    fn inner_apply(&self, graph: &Graph) -> Result<Vec<Trace>> {
+        if graph.get_root().is_none() {
+            return Err(Error::graph(graph.get_name(), "no starting state on the mealy machine"));
+        }
+        Ok(trace::shortest_traces(
+            graph,
+            self.expression.initial(),
+            |states, _, label| {
+                let next = self.expression.step(states, label);
+                let matches = self.expression.accepts(&next);
+                (matches, Some(next).filter(|next| !next.is_empty()))
+            },
+        ))
+    }
+}
+
+impl Rule for ForbiddenWord {
+    fn from_reader(reader: &mut dyn Reader, name: String) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let expression = read_expression(reader, "FW")?;
+        Ok(ForbiddenWord { expression, name })
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn lint(&self, graph: &Graph) -> Vec<String> {
+        lint::unmatched(graph, self.expression.atoms.iter())
+    }
+
+    fn apply(&mut self, graph: &Graph, output_folder: &mut std::path::PathBuf) -> Result<RuleResult> {
+        let traces = self.inner_apply(graph)?;
+        let violations = traces.iter().map(|trace| trace.violation().clone()).collect();
+        let mut result = RuleResult::from_violations(self.get_name(), violations);
+        output_folder.push(self.get_name());
+        if let Err(e) = std::fs::create_dir_all(&output_folder) {
+            return Err(Error::io(output_folder.clone(), e));
+        }
+        let transitions: Vec<Transition> = traces.iter().flat_map(|t| t.transitions.iter().cloned()).collect();
+        match utils::output::write_transitions(graph, &transitions, output_folder, "ce.dot") {
+            Ok(path) => result.add_counterexample(path),
+            Err(e) => return Err(Error::io(output_folder.clone(), e)),
+        }
+        match utils::output::write_traces(&traces, output_folder) {
+            Ok(paths) => result.add_traces(traces, paths),
+            Err(e) => return Err(Error::io(output_folder.clone(), e)),
+        }
+        output_folder.pop();
+        Ok(result)
+    }
+
+    #[cfg(test)]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl RequiredWord {
+    //breadth first search on the product of the Mealy machine and of the automaton of the expression
+    fn inner_apply(&self, graph: &Graph) -> Result<Search> {
+        let root = match graph.get_root() {
+            Some(root) => root,
+            None => return Err(Error::graph(graph.get_name(), "no starting state on the mealy machine")),
+        };
+        let start = (root.clone(), self.expression.initial());
+        let mut parents: Parents<(NodeId, Vec<usize>)> = HashMap::new();
+        parents.insert(start.clone(), None);
+        let mut queue = VecDeque::from([start.clone()]);
+        let mut closest = start;
+        while let Some(current) = queue.pop_front() {
+            let (node_id, states) = &current;
+            for (neighbor_id, edge) in sorted_neighbors(graph, node_id) {
+                for label in edge.get_label_iterator() {
+                    let next = self.expression.step(states, label);
+                    if next.is_empty() {
+                        continue;
+                    }
+                    let transition = Transition::new(node_id, &neighbor_id, label);
+                    if self.expression.accepts(&next) {
+                        let mut path = path_to(&parents, &current);
+                        path.push(transition);
+                        return Ok(Search::Found(path));
+                    }
+                    let next = (neighbor_id.clone(), next);
+                    if parents.contains_key(&next) {
+                        continue;
+                    }
+                    if self.expression.distance(&next.1) < self.expression.distance(&closest.1) {
+                        closest = next.clone();
+                    }
+                    parents.insert(next.clone(), Some((current.clone(), transition)));
+                    queue.push_back(next);
+                }
+            }
+        }
+        Ok(Search::Missing(
+            path_to(&parents, &closest),
+            self.expression.expected(&closest.1),
+        ))
+    }
+}
+
+impl Rule for RequiredWord {
+    fn from_reader(reader: &mut dyn Reader, name: String) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let expression = read_expression(reader, "RW")?;
+        Ok(RequiredWord { expression, name })
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn lint(&self, graph: &Graph) -> Vec<String> {
+        lint::unmatched(graph, self.expression.atoms.iter())
+    }
+
+    fn apply(&mut self, graph: &Graph, output_folder: &mut std::path::PathBuf) -> Result<RuleResult> {
+        let search = self.inner_apply(graph)?;
+        let mut result = match &search {
+            Search::Found(path) => RuleResult::from_witness(self.get_name(), Some(path.clone())),
+            Search::Missing(..) => RuleResult::from_witness(self.get_name(), None),
+        };
+        output_folder.push(self.get_name());
+        if let Err(e) = std::fs::create_dir_all(&output_folder) {
+            return Err(Error::io(output_folder.clone(), e));
+        }
+        match search {
+            Search::Found(path) => match utils::output::write_transitions(graph, &path, output_folder, "witness.dot") {
+                Ok(path) => result.add_counterexample(path),
+                Err(e) => return Err(Error::io(output_folder.clone(), e)),
+            },
+            Search::Missing(prefix, expected) => {
+                match utils::output::write_transitions(graph, &prefix, output_folder, "ce.dot") {
+                    Ok(path) => result.add_counterexample(path),
+                    Err(e) => return Err(Error::io(output_folder.clone(), e)),
+                }
+                let trace = Trace::new(prefix);
+                let mut text = String::from("# no trace matches the expression, the closest one is\n");
+                for (input, output) in trace.inputs.iter().zip(trace.outputs.iter()) {
+                    text.push_str(&format!("{} / {}\n", input, output));
+                }
+                text.push_str(&format!("# missing next: {}\n", expected.join(" | ")));
+                match utils::output::write_text("missing.txt", &text, output_folder) {
+                    Ok(path) => result.add_counterexample(Some(path)),
+                    Err(e) => return Err(Error::io(output_folder.clone(), e)),
+                }
+            }
+        }
+        output_folder.pop();
+        Ok(result)
+    }
+
+    #[cfg(test)]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        graph::Graph,
+        utils::reader::test_reader::TestReader,
+        verifier::rules::{parse_reader, parse_rule_from_str},
+    };
+
+    use super::{ForbiddenWord, RequiredWord, Search};
+
+    const GRAPH: &str = r#"digraph "Automata" {
+        "0" [shape=doublecircle];
+        "0" -> "1" [label="hello/Ack"];
+        "0" -> "0" [label="read_req/NoSession"];
+        "1" -> "2" [label="open_session/ok"];
+        "1" -> "1" [label="ping/pong"];
+        "1" -> "3" [label="read_req/ReadRepOK"];
+        "2" -> "2" [label="read_req/ReadRepOK"];
+        "2" -> "0" [label="close/ok"];
+    }"#;
+    //0 -hello-> 1 -open-> 2 -close-> 0, reads loop on 0 and 2, 1 -read-> 3 without outgoing transition
+
+    #[test]
+    fn test_forbidden_word() {
+        let graph = Graph::new(GRAPH, true);
+        let traces = |rule: &str| {
+            let rule = parse_rule_from_str(rule);
+            let rule = rule[0]
+                .as_any()
+                .downcast_ref::<ForbiddenWord>()
+                .expect("expect FW rule");
+            rule.inner_apply(&graph).unwrap()
+        };
+        //reading without opening a session
+        let found = traces("FW:read_without_session\n\"hello/Ack\" . (!\"open*/*\")* . \"read_req/ReadRepOK\"\n:FW");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].inputs, vec!["hello", "read_req"]);
+        //a read right after the second hello
+        let found = traces("FW:reopen\n\"hello/*\" (!\"hello/*\")* \"hello/*\" \"read_req/*\"\n:FW");
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            found[0].inputs,
+            vec!["hello", "open_session", "close", "hello", "read_req"]
+        );
+        assert!(traces("FW:no_double_hello\n\"hello/*\"{2}\n:FW").is_empty());
+    }
+
+    #[test]
+    fn test_required_word() {
+        let graph = Graph::new(GRAPH, true);
+        let search = |rule: &str| {
+            let rule = parse_rule_from_str(rule);
+            let rule = rule[0].as_any().downcast_ref::<RequiredWord>().expect("expect RW rule");
+            rule.inner_apply(&graph).unwrap()
+        };
+        match search("RW:read_in_session\n\"hello/*\" \"ping/*\"* \"open_session/ok\" \"read_req/ReadRepOK\"+\n:RW") {
+            Search::Found(path) => assert_eq!(path.len(), 3),
+            other => panic!("expected a matching trace, got {:?}", other),
+        }
+        match search("RW:close_twice\n\"hello/*\" (\"*/*\")* \"close/*\" \"close/*\"\n:RW") {
+            Search::Missing(prefix, expected) => {
+                assert_eq!(prefix.len(), 3);
+                assert_eq!(prefix[2].label, "close/ok");
+                assert_eq!(expected, vec!["\"close/*\""]);
+            }
+            other => panic!("expected a missing trace, got {:?}", other),
+        }
+
+        let rules = "RW:unknown\n\"a/b\" W\n:RW\nFW:twice\n\"a/b\"\n\"c/d\"\n:FW\nFW:empty\n\"a/b\"*\n:FW";
+        let mut reader = TestReader::from_text(rules).unwrap();
+        let (rules, errors) = parse_reader(&mut reader, "rules");
+        assert!(rules.is_empty());
+        let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "rules:2:7: unknown operator W, events are written between double quotes",
+                "rules:6:1: a FW rule has one expression, unexpected line: \"c/d\"",
+                "rules:9:1: the expression matches the empty trace",
+            ]
+        );
+    }
+}
@@ -1,10 +1,20 @@
 use std::collections::HashSet;
 
-use crate::{utils, verifier::event::Events};
+use crate::{
+    error::{column, Error, Result},
+    utils,
+    verifier::{
+        event::Events,
+        lint,
+        trace::{self, Trace},
+    },
+};
 
-use super::{Graph, MultiEdge, NodeId, Rule};
+use super::{Graph, MultiEdge, NodeId, Rule, RuleResult};
 #[cfg(test)]
 use std::any::Any;
+type ExecutionStack<'a> = Vec<(NodeId, Vec<(NodeId, &'a MultiEdge)>, State)>;
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 enum State {
     UnInit,     //before seing init
@@ -55,45 +65,52 @@ impl RestrictedEvents {
         match state {
             State::UnInit => {
                 if self.start(event_str) {
-                    return (false, State::Restricted);
+                    (false, State::Restricted)
                 } else if self.cancel(event_str) {
-                    return (false, State::Cancel);
+                    (false, State::Cancel)
                 } else {
-                    return (false, State::UnInit);
+                    (false, State::UnInit)
                 }
             }
             State::Restricted => {
                 if self.end(event_str) {
-                    return (false, State::Released);
+                    (false, State::Released)
                 } else if self.is_authorized(event_str) {
-                    return (false, State::Restricted);
+                    (false, State::Restricted)
                 } else {
-                    return (true, State::Restricted);
+                    (true, State::Restricted)
                 }
             }
-            State::Released => {
-                return (false, State::Released);
-            }
-            State::Cancel => {
-                return (false, State::Cancel);
-            }
+            State::Released => (false, State::Released),
+            State::Cancel => (false, State::Cancel),
         }
     }
 
-    fn inner_apply<'a>(&'a self, graph: &'a Graph) -> (HashSet<NodeId>, HashSet<(&MultiEdge, Vec<usize>)>) {
+    fn shortest_traces(&self, graph: &Graph) -> Vec<Trace> {
+        let sink_nodes = graph.get_sink_state_set();
+        trace::shortest_traces(graph, self.get_init_state(), |state, neighbor_id, label| {
+            if sink_nodes.contains(neighbor_id) {
+                return (false, None);
+            }
+            let (error, new_state) = self.update_state(state, label);
+            (error, Some(new_state))
+        })
+    }
+
+    fn inner_apply<'a>(&'a self, graph: &'a Graph) -> (HashSet<NodeId>, HashSet<(&'a MultiEdge, Vec<usize>)>) {
         let mut nodes = HashSet::new();
         let mut edges = HashSet::new();
-        let mut execution_stack: Vec<(NodeId, Vec<(NodeId, &MultiEdge)>, State)> = Vec::new();
+        let mut execution_stack: ExecutionStack = Vec::new();
         let mut seen: HashSet<(NodeId, State)> = HashSet::new();
         let sink_nodes = graph.get_sink_state_set();
-        let root_id = graph.get_root().clone().unwrap();
+        let root_id = graph.get_root().unwrap();
         execution_stack.push((
             root_id.clone(),
-            graph.neighbors_edges_iterator(&root_id),
+            graph.neighbors_edges_iterator(root_id),
             self.get_init_state(),
         ));
         while let Some((node_id, neighbors, current_state)) = execution_stack.last_mut() {
-            seen.insert((node_id.clone(), current_state.clone()));
+            seen.insert((node_id.clone(), *current_state));
             if let Some((neighbor_id, edge)) = neighbors.pop() {
                 if sink_nodes.contains(&neighbor_id) {
                     continue;
@@ -108,9 +125,9 @@ impl RestrictedEvents {
                     indexes_next.insert(new_state);
                 }
                 for new_state in indexes_next {
-                    if !seen.contains(&(neighbor_id.clone(), new_state.clone())) {
+                    if !seen.contains(&(neighbor_id.clone(), new_state)) {
                         let new_neighbors = graph.neighbors_edges_iterator(&neighbor_id);
-                        execution_stack.push((neighbor_id.clone(), new_neighbors, new_state.clone()));
+                        execution_stack.push((neighbor_id.clone(), new_neighbors, new_state));
                     }
                 }
                 if !indexes_error.is_empty() {
@@ -129,7 +146,7 @@ impl RestrictedEvents {
 }
 
 impl Rule for RestrictedEvents {
-    fn from_reader(reader: &mut dyn crate::utils::reader::Reader, name: String) -> std::io::Result<Self>
+    fn from_reader(reader: &mut dyn crate::utils::reader::Reader, name: String) -> Result<Self>
     where
         Self: Sized,
     {
@@ -137,43 +154,54 @@ impl Rule for RestrictedEvents {
         let mut release: Option<Events> = None;
         let mut authorized: Option<Events> = None;
         let mut cancel: Option<Events> = None;
-        while let Some(line) = reader.read_line() {
-            let line = line.trim_start();
-            if line.starts_with("A:") {
-                let split_line: Vec<_> = line.split(":").collect();
-                if split_line.len() != 2 {
-                    panic!("no authorized event for RestrictedEvents rule");
-                }
-                authorized = Some(Events::from_str(split_line[1].trim()))
-            } else if line.starts_with("R:") {
+        while let Some(raw_line) = reader.read_line() {
+            let line = raw_line.trim_start();
+            if line.starts_with("A:") || line.starts_with("R:") || line.starts_with("I:") || line.starts_with("C:") {
                 let split_line: Vec<_> = line.split(":").collect();
                 if split_line.len() != 2 {
-                    panic!("no release for RestrictedEvents rule");
+                    return Err(Error::parse(
+                        column(raw_line, line),
+                        format!("failed to parse RestrictedEvents events: {}", line.trim()),
+                    ));
                 }
-                release = Some(Events::from_str(split_line[1].trim()));
-            } else if line.starts_with("I:") {
-                let split_line: Vec<_> = line.split(":").collect();
-                if split_line.len() != 2 {
-                    panic!("no init for RestrictedEvents rule");
-                }
-                init = Some(Events::from_str(split_line[1].trim()));
-            } else if line.starts_with("C:") {
-                let split_line: Vec<_> = line.split(":").collect();
-                if split_line.len() != 2 {
-                    panic!("no release for RestrictedEvents rule");
+                let value = split_line[1].trim();
+                let events = Events::from_str(value).map_err(|e| e.at_column(column(raw_line, value)))?;
+                if line.starts_with("A:") {
+                    authorized = Some(events);
+                } else if line.starts_with("R:") {
+                    release = Some(events);
+                } else if line.starts_with("I:") {
+                    init = Some(events);
+                } else {
+                    cancel = Some(events);
                 }
-                cancel = Some(Events::from_str(split_line[1].trim()));
             } else if line.starts_with(":RE") {
                 break;
             } else {
-                panic!("failed to parse until Rule:{}", line);
+                return Err(Error::parse(
+                    column(raw_line, line),
+                    format!("failed to parse until Rule:{}", line.trim()),
+                ));
             }
         }
+        let authorized = match authorized {
+            Some(authorized) => authorized,
+            None => {
+                return Err(Error::parse(
+                    0,
+                    "you must specify authorized event for restricted event rule",
+                ))
+            }
+        };
+        let release = match release {
+            Some(release) => release,
+            None => return Err(Error::parse(0, "you must specify end event for restricted event rule")),
+        };
         Ok(RestrictedEvents {
             name,
-            init: init,
-            authorized: authorized.expect("you must specify authorized event for restricted event rule"),
-            release: release.expect("you must specify end event for restrictued event rule"),
+            init,
+            authorized,
+            release,
             cancel_rule: cancel,
         })
     }
@@ -182,27 +210,37 @@ impl Rule for RestrictedEvents {
         &self.name
     }
 
-    fn apply(
-        &mut self,
-        graph: &super::Graph,
-        output_folder: &mut std::path::PathBuf,
-    ) {
+    fn lint(&self, graph: &Graph) -> Vec<String> {
+        let optional = self.init.iter().chain(self.cancel_rule.iter()).flat_map(Events::iter);
+        let events = self.release.iter().chain(self.authorized.iter()).chain(optional);
+        let mut warnings = lint::unmatched(graph, events);
+        if let Some(init) = &self.init {
+            let what = format!("init event {}", init);
+            warnings.extend(lint::never_occurs(graph, what, |label| init.check_all(label)));
+        }
+        warnings
+    }
+
+    fn apply(&mut self, graph: &super::Graph, output_folder: &mut std::path::PathBuf) -> Result<RuleResult> {
         let (nodes, edges) = self.inner_apply(graph);
+        let mut result = RuleResult::from_edges(self.get_name(), edges.iter());
         output_folder.push(self.get_name());
         // println!("graph: {}",graph.get_name());
         if let Err(e) = std::fs::create_dir_all(&output_folder) {
-            panic!("failed to create output directory due to :{}", e.to_string());
+            return Err(Error::io(output_folder.clone(), e));
         }
 
-        let ret = utils::output::write_files(graph, nodes, edges, output_folder);
-        if let Err(e) = ret {
-            panic!(
-                "unable to output the result of rule {} due to {}",
-                self.get_name(),
-                e.to_string()
-            );
+        match utils::output::write_files(graph, nodes, edges, output_folder) {
+            Ok(path) => result.add_counterexample(path),
+            Err(e) => return Err(Error::io(output_folder.clone(), e)),
+        }
+        let traces = self.shortest_traces(graph);
+        match utils::output::write_traces(&traces, output_folder) {
+            Ok(paths) => result.add_traces(traces, paths),
+            Err(e) => return Err(Error::io(output_folder.clone(), e)),
         }
         output_folder.pop();
+        Ok(result)
     }
 
     #[cfg(test)]
@@ -302,7 +340,7 @@ mod test {
             .downcast_ref::<RestrictedEvents>()
             .expect("expect conditional rule");
         let (nodes, edges) = rule.inner_apply(&graph);
-        let node_ids = vec!["6", "5", "7"];
+        let node_ids = ["6", "5", "7"];
         let node_ids: Vec<NodeId> = node_ids.iter().map(|e| NodeId::new(e)).collect();
         for node_id in node_ids.iter() {
             assert!(nodes.contains(node_id));
@@ -311,11 +349,22 @@ mod test {
         let edges_expected = r#"
         "6" -> "5" [fontsize=5, label="nok/nok"];
         "5" -> "7" [fontsize=5, label="nok/nok"];"#;
-        let edges_expected = edges_expected.split(";\n").into_iter().map(|e| e.trim());
+        let edges_expected = edges_expected.split(";\n").map(|e| e.trim());
         let mut expected_edge_map = EdgeMap::new();
         for edge_str in edges_expected {
             expected_edge_map.add_edge_from_str(edge_str);
         }
         assert_eq!(expected_edge_map, edges);
+
+        let traces = rule.shortest_traces(&graph);
+        let inputs: Vec<Vec<String>> = traces.iter().map(|t| t.inputs.clone()).collect();
+        assert_eq!(
+            inputs,
+            vec![
+                vec!["nok", "init", "ok", "ok", "nok", "nok"],
+                vec!["nok", "init", "ok", "ok", "nok"]
+            ]
+        );
+        assert_eq!(traces[1].violation().to_string(), "6 -> 5 [nok/nok]");
     }
 }
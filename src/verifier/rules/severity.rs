@@ -0,0 +1,85 @@
+//! Severity given as the optional third field of a rule header, such as `RE:session_over_channel:high`.
+#[cfg(test)]
+use std::any::Any;
+use std::{fmt, path::PathBuf, str::FromStr};
+
+use serde::Serialize;
+
+use super::{Rule, RuleResult};
+use crate::{
+    error::{Error, Result},
+    graph::Graph,
+    utils::reader::Reader,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+}
+
+impl FromStr for Severity {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "low" => Ok(Severity::Low),
+            "medium" => Ok(Severity::Medium),
+            "high" => Ok(Severity::High),
+            _ => Err(Error::parse(
+                0,
+                format!("unknown severity {}, expected low, medium or high", s),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Low => write!(f, "low"),
+            Severity::Medium => write!(f, "medium"),
+            Severity::High => write!(f, "high"),
+        }
+    }
+}
+
+/// Rule whose header gives a severity, the rule itself is applied unchanged.
+#[derive(Debug)]
+pub(crate) struct WithSeverity<R: Rule> {
+    pub(crate) rule: R,
+    pub(crate) severity: Option<Severity>,
+}
+
+impl<R: Rule> Rule for WithSeverity<R> {
+    fn from_reader(reader: &mut dyn Reader, name: String) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let rule = R::from_reader(reader, name)?;
+        Ok(WithSeverity { rule, severity: None })
+    }
+
+    fn get_name(&self) -> &str {
+        self.rule.get_name()
+    }
+
+    fn get_severity(&self) -> Option<Severity> {
+        self.severity
+    }
+
+    fn lint(&self, graph: &Graph) -> Vec<String> {
+        self.rule.lint(graph)
+    }
+
+    fn apply(&mut self, graph: &Graph, output_folder: &mut PathBuf) -> Result<RuleResult> {
+        self.rule.apply(graph, output_folder)
+    }
+
+    #[cfg(test)]
+    fn as_any(&self) -> &dyn Any {
+        self.rule.as_any()
+    }
+}
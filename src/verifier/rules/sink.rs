@@ -1,7 +1,12 @@
 use std::collections::HashSet;
 
-use super::{MultiEdge, NodeId, Rule};
-use crate::{graph::Graph, utils, verifier::event::Events};
+use super::{MultiEdge, NodeId, Rule, RuleResult};
+use crate::{
+    error::{column, Error, Result},
+    graph::Graph,
+    utils,
+    verifier::{event::Events, lint},
+};
 #[cfg(test)]
 use std::any::Any;
 #[derive(Debug)]
@@ -16,7 +21,7 @@ impl SinkTarget {
         let mut match_one_description;
         let outgoing_labels = graph.get_outgoing_labels(sink_id);
         for label in outgoing_labels.iter() {
-            match_one_description = self.sink_description.check_all(&label);
+            match_one_description = self.sink_description.check_all(label);
             if !match_one_description {
                 return false;
             }
@@ -47,7 +52,7 @@ impl SinkTarget {
             let mut indexes = Vec::new();
             let dest = edge.get_dest();
             for (index, label) in edge.get_labels().iter().enumerate() {
-                if self.triggers.check_all(&label) && !matching_sink_ids.contains(dest) {
+                if self.triggers.check_all(label) && !matching_sink_ids.contains(dest) {
                     indexes.push(index);
                 }
             }
@@ -62,7 +67,7 @@ impl SinkTarget {
 }
 
 impl Rule for SinkTarget {
-    fn from_reader(reader: &mut dyn crate::utils::reader::Reader, name: String) -> std::io::Result<Self>
+    fn from_reader(reader: &mut dyn crate::utils::reader::Reader, name: String) -> Result<Self>
     where
         Self: Sized,
     {
@@ -74,21 +79,36 @@ impl Rule for SinkTarget {
             } else if line.contains('/') {
                 let line_split: Vec<&str> = line.split('|').map(|s| s.trim()).collect();
                 if line_split.len() != 2 {
-                    panic!("Failed to parse {line}. You must write sink's rule line as trigger|sink edge");
+                    return Err(Error::parse(
+                        column(line, line.trim()),
+                        format!(
+                            "Failed to parse {}. You must write sink's rule line as trigger|sink edge",
+                            line.trim()
+                        ),
+                    ));
                 }
-                triggers = Some(Events::from_str(line_split[0]));
-                sink_description = Some(Events::from_str(line_split[1]));
+                triggers = Some(Events::from_str(line_split[0]).map_err(|e| e.at_column(column(line, line_split[0])))?);
+                sink_description =
+                    Some(Events::from_str(line_split[1]).map_err(|e| e.at_column(column(line, line_split[1])))?);
             } else {
-                panic!("failed to parse expected loop rule");
+                return Err(Error::parse(
+                    column(line, line.trim()),
+                    format!("failed to parse sink target rule: {}", line.trim()),
+                ));
             }
         }
-        let triggers = triggers.expect("you must indicate the event that lead to the sink state for Sink target rule");
-
-        let sink_description =
-            sink_description.expect("you must indicate the event that lead to the sink state for Sink target rule");
+        let (triggers, sink_description) = match (triggers, sink_description) {
+            (Some(triggers), Some(sink_description)) => (triggers, sink_description),
+            _ => {
+                return Err(Error::parse(
+                    0,
+                    "you must indicate the event that lead to the sink state for Sink target rule",
+                ))
+            }
+        };
         Ok(SinkTarget {
-            triggers: triggers,
-            sink_description: sink_description,
+            triggers,
+            sink_description,
             name,
         })
     }
@@ -97,28 +117,31 @@ impl Rule for SinkTarget {
         &self.name
     }
 
-    fn apply(
-        &mut self,
-        graph: &Graph,
-        output_folder: &mut std::path::PathBuf,
-    ) {
+    fn lint(&self, graph: &Graph) -> Vec<String> {
+        let mut warnings = lint::unmatched(graph, self.triggers.iter().chain(self.sink_description.iter()));
+        let triggers = format!("trigger {}", self.triggers);
+        warnings.extend(lint::never_occurs(graph, triggers, |label| {
+            self.triggers.check_all(label)
+        }));
+        warnings
+    }
+
+    fn apply(&mut self, graph: &Graph, output_folder: &mut std::path::PathBuf) -> Result<RuleResult> {
         let (nodes, edges) = self.inner_apply(graph);
+        let mut result = RuleResult::from_edges(self.get_name(), edges.iter());
 
         output_folder.push(self.get_name());
         // println!("graph: {}",graph.get_name());
         if let Err(e) = std::fs::create_dir_all(&output_folder) {
-            panic!("failed to create output directory due to :{}", e.to_string());
+            return Err(Error::io(output_folder.clone(), e));
         }
 
-        let ret = utils::output::write_files(graph, nodes, edges, output_folder);
-        if let Err(e) = ret {
-            panic!(
-                "unable to output the result of rule {} due to {}",
-                self.get_name(),
-                e.to_string()
-            );
+        match utils::output::write_files(graph, nodes, edges, output_folder) {
+            Ok(path) => result.add_counterexample(path),
+            Err(e) => return Err(Error::io(output_folder.clone(), e)),
         }
         output_folder.pop();
+        Ok(result)
     }
 
     #[cfg(test)]
@@ -141,7 +164,7 @@ impl SinkDescription {
         for label in outgoing_labels.iter() {
             match_one_description = false;
             for description in self.sink_description.iter() {
-                match_one_description = match_one_description || description.check_all(&label);
+                match_one_description = match_one_description || description.check_all(label);
             }
             if !match_one_description {
                 return false;
@@ -155,7 +178,7 @@ impl SinkDescription {
         let mut nodes: HashSet<NodeId> = HashSet::new();
 
         for sink_id in sinks {
-            if !self.match_description(graph, &sink_id) {
+            if !self.match_description(graph, sink_id) {
                 nodes.insert(sink_id.clone());
             }
         }
@@ -173,7 +196,7 @@ impl SinkDescription {
 }
 
 impl Rule for SinkDescription {
-    fn from_reader(reader: &mut dyn crate::utils::reader::Reader, name: String) -> std::io::Result<Self>
+    fn from_reader(reader: &mut dyn crate::utils::reader::Reader, name: String) -> Result<Self>
     where
         Self: Sized,
     {
@@ -182,9 +205,12 @@ impl Rule for SinkDescription {
             if line.starts_with(":SD") {
                 break;
             } else if line.contains('/') {
-                sinks_labels.push(Events::from_str(line));
+                sinks_labels.push(Events::from_str(line).map_err(|e| e.at_column(column(line, line.trim())))?);
             } else {
-                panic!("failed to parse expected loop rule");
+                return Err(Error::parse(
+                    column(line, line.trim()),
+                    format!("failed to parse sink description rule: {}", line.trim()),
+                ));
             }
         }
         Ok(SinkDescription {
@@ -197,28 +223,26 @@ impl Rule for SinkDescription {
         &self.name
     }
 
-    fn apply(
-        &mut self,
-        graph: &Graph,
-        output_folder: &mut std::path::PathBuf,
-    ) {
+    fn lint(&self, graph: &Graph) -> Vec<String> {
+        lint::unmatched(graph, self.sink_description.iter().flat_map(Events::iter))
+    }
+
+    fn apply(&mut self, graph: &Graph, output_folder: &mut std::path::PathBuf) -> Result<RuleResult> {
         let (nodes, edges) = self.inner_apply(graph);
+        let mut result = RuleResult::from_edges(self.get_name(), edges.iter());
 
         output_folder.push(self.get_name());
         // println!("graph: {}",graph.get_name());
         if let Err(e) = std::fs::create_dir_all(&output_folder) {
-            panic!("failed to create output directory due to :{}", e.to_string());
+            return Err(Error::io(output_folder.clone(), e));
         }
 
-        let ret = utils::output::write_files(graph, nodes, edges, output_folder);
-        if let Err(e) = ret {
-            panic!(
-                "unable to output the result of rule {} due to {}",
-                self.get_name(),
-                e.to_string()
-            );
+        match utils::output::write_files(graph, nodes, edges, output_folder) {
+            Ok(path) => result.add_counterexample(path),
+            Err(e) => return Err(Error::io(output_folder.clone(), e)),
         }
         output_folder.pop();
+        Ok(result)
     }
 
     #[cfg(test)]
@@ -331,7 +355,7 @@ mod tests {
         let edges_expected = r#"
         "0" -> "1" [fontsize=5, label="a/b", color=black];
         "2" -> "sink3" [fontsize=5, label="a/b", color=black];"#;
-        let edges_expected = edges_expected.split(";\n").into_iter().map(|e| e.trim());
+        let edges_expected = edges_expected.split(";\n").map(|e| e.trim());
         let mut expected_edge_map = EdgeMap::new();
         for edge_str in edges_expected {
             expected_edge_map.add_edge_from_str(edge_str);
@@ -423,7 +447,7 @@ mod tests {
         "sink3"->"sink3" [fontsize=5,label="e/nok"];
         "sink3"->"sink3" [fontsize=5,label="a/no_resp"];
         "sink3"->"sink3" [fontsize=5,label="c/no_resp"];"#;
-        let edges_expected = edges_expected.split(";\n").into_iter().map(|e| e.trim());
+        let edges_expected = edges_expected.split(";\n").map(|e| e.trim());
         let mut expected_edge_map = EdgeMap::new();
         for edge_str in edges_expected {
             expected_edge_map.add_edge_from_str(edge_str);
@@ -446,7 +470,7 @@ mod tests {
         "sink3"->"sink3" [fontsize=5,label="e/nok"];
         "sink3"->"sink3" [fontsize=5,label="a/no_resp"];
         "sink3"->"sink3" [fontsize=5,label="c/no_resp"];"#;
-        let edges_expected = edges_expected.split(";\n").into_iter().map(|e| e.trim());
+        let edges_expected = edges_expected.split(";\n").map(|e| e.trim());
         let mut expected_edge_map = EdgeMap::new();
         for edge_str in edges_expected {
             expected_edge_map.add_edge_from_str(edge_str);
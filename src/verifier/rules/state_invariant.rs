@@ -0,0 +1,151 @@
+use std::collections::HashSet;
+
+use super::{Rule, RuleResult};
+use crate::{
+    error::{Error, Result},
+    graph::{multi_edge::MultiEdge, nodeid::NodeId, Graph},
+    utils::{self, reader::Reader},
+    verifier::{
+        invariant::{self, Invariant},
+        lint,
+        temporal::Formula,
+        trace,
+    },
+};
+#[cfg(test)]
+use std::any::Any;
+
+/// The formula has to hold on the outgoing transitions of every state reachable from the initial state.
+#[derive(Debug)]
+pub(crate) struct StateInvariant {
+    pub(crate) formula: Formula<Invariant>,
+    pub(crate) name: String,
+}
+
+impl StateInvariant {
+    //states reachable from the initial state where the formula does not hold, in the order of the graph
+    fn inner_apply(&self, graph: &Graph) -> Result<Vec<NodeId>> {
+        if graph.get_root().is_none() {
+            return Err(Error::graph(graph.get_name(), "no starting state on the mealy machine"));
+        }
+        let reachable = trace::shortest_paths(graph);
+        Ok(graph
+            .iter_node_id()
+            .filter(|id| reachable.contains_key(*id))
+            .filter(|id| !self.formula.holds(&graph.get_outgoing_labels(id)))
+            .cloned()
+            .collect())
+    }
+}
+
+impl Rule for StateInvariant {
+    fn from_reader(reader: &mut dyn Reader, name: String) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let formula = super::read_formula(reader, "SI", invariant::parse)?;
+        Ok(StateInvariant { formula, name })
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn lint(&self, graph: &Graph) -> Vec<String> {
+        lint::unmatched(graph, self.formula.atoms.iter())
+    }
+
+    fn apply(&mut self, graph: &Graph, output_folder: &mut std::path::PathBuf) -> Result<RuleResult> {
+        let states = self.inner_apply(graph)?;
+        let mut result = RuleResult::from_states(self.get_name(), states.iter().map(|s| s.to_string()).collect());
+        output_folder.push(self.get_name());
+        if let Err(e) = std::fs::create_dir_all(&output_folder) {
+            return Err(Error::io(output_folder.clone(), e));
+        }
+        //the violating states with all their outgoing transitions, which the formula is about
+        let nodes: HashSet<NodeId> = states.into_iter().collect();
+        let edges: HashSet<(&MultiEdge, Vec<usize>)> = graph
+            .iter_edges()
+            .into_iter()
+            .filter(|edge| nodes.contains(edge.get_source()))
+            .map(|edge| (edge, (0..edge.get_labels().len()).collect()))
+            .collect();
+        match utils::output::write_files(graph, nodes, edges, output_folder) {
+            Ok(path) => result.add_counterexample(path),
+            Err(e) => return Err(Error::io(output_folder.clone(), e)),
+        }
+        match utils::output::write_states(&result.states, output_folder) {
+            Ok(path) => result.add_counterexample(path),
+            Err(e) => return Err(Error::io(output_folder.clone(), e)),
+        }
+        output_folder.pop();
+        Ok(result)
+    }
+
+    #[cfg(test)]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        graph::Graph,
+        utils::reader::test_reader::TestReader,
+        verifier::rules::{parse_reader, parse_rule_from_str},
+    };
+
+    use super::StateInvariant;
+
+    #[test]
+    fn test_state_invariant() {
+        let input = r#"digraph "Automata" {
+            "0" [shape=doublecircle];
+            "0" -> "1" [label="open/OK"];
+            "0" -> "0" [label="close/NOK"];
+            "1" -> "2" [label="write/OK"];
+            "1" -> "0" [label="close/OK"];
+            "2" -> "2" [label="read/OK"];
+            "2" -> "2" [label="write/OK"];
+            "2" -> "0" [label="close/NOK"];
+            "3" -> "3" [label="read/OK"];
+            "3" -> "3" [label="write/OK"];
+        }"#;
+        //0 -open-> 1 -write-> 2 -close-> 0, 1 -close-> 0, 3 is not reachable
+        let graph = Graph::new(input, true);
+        let states = |rule: &str| {
+            let rule = parse_rule_from_str(rule);
+            let rule = rule[0]
+                .as_any()
+                .downcast_ref::<StateInvariant>()
+                .expect("expect SI rule");
+            let states: Vec<String> = rule
+                .inner_apply(&graph)
+                .unwrap()
+                .iter()
+                .map(|id| id.to_string())
+                .collect();
+            states
+        };
+        //a state accepting writes has to close successfully
+        assert_eq!(states("SI:close\n\"write/OK\" -> \"close/OK\"\n:SI"), vec!["2"]);
+        //no state may both read and write, the unreachable one is not checked
+        assert_eq!(states("SI:exclusive\n!(\"read/OK\" & \"write/OK\")\n:SI"), vec!["2"]);
+        assert!(states("SI:closing\n\"close/*\"\n:SI").is_empty());
+
+        let rules = "SI:temporal\nG \"a/b\"\n:SI\nSI:twice\n\"a/b\"\n\"c/d\"\n:SI\nSI:empty\n:SI";
+        let mut reader = TestReader::from_text(rules).unwrap();
+        let (rules, errors) = parse_reader(&mut reader, "rules");
+        assert!(rules.is_empty());
+        let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "rules:2:1: unknown operator G, events are written between double quotes",
+                "rules:6:1: a SI rule has one formula, unexpected line: \"c/d\"",
+                "rules:9:1: no formula given for SI rule",
+            ]
+        );
+    }
+}
@@ -0,0 +1,258 @@
+//! Parsing shared by the temporal logic formulas and the regular expressions of the rules.
+//! The atoms are events written between double quotes, the operators are symbols or words such as `G` or `AF`.
+use std::{iter::Peekable, str::CharIndices};
+
+use super::event::Event;
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Token {
+    Atom(String),
+    /// operator or constant written as a word
+    Word(&'static str),
+    Not,
+    And,
+    Or,
+    Implies,
+    Equiv,
+    Open,
+    Close,
+    OpenBracket,
+    CloseBracket,
+    Dot,
+    Star,
+    Plus,
+    Question,
+    OpenBrace,
+    CloseBrace,
+    Comma,
+    Number(usize),
+}
+
+/// Boolean connectives of a logic, they are parsed the same way whatever the temporal operators.
+pub(crate) trait Connectives: Sized {
+    fn and(a: Self, b: Self) -> Self;
+    fn or(a: Self, b: Self) -> Self;
+    fn implies(a: Self, b: Self) -> Self;
+    fn equiv(a: Self, b: Self) -> Self;
+}
+
+/// A formula with the events it is built on.
+#[derive(Debug)]
+pub(crate) struct Formula<F> {
+    pub(crate) root: F,
+    pub(crate) atoms: Vec<Event>,
+}
+
+impl<F: Connectives> Formula<F> {
+    /// Parse a formula of the logic whose operators and constants are `words`, `operand` parses what the connectives apply on.
+    /// The column of an error is the position in `text` starting at 1.
+    pub(crate) fn parse(text: &str, words: &[&'static str], operand: fn(&mut Parser) -> Result<F>) -> Result<Self> {
+        let mut parser = Parser::new(text, words)?;
+        let root = parser.formula(operand)?;
+        let atoms = parser.finish()?;
+        Ok(Formula { root, atoms })
+    }
+}
+
+//tokens of the formula with their column, `words` are the operators and constants of the logic
+fn tokenize(text: &str, words: &[&'static str]) -> Result<Vec<(Token, usize)>> {
+    let mut tokens = Vec::new();
+    let mut chars: Peekable<CharIndices> = text.char_indices().peekable();
+    let column = |index: usize| text[..index].chars().count() + 1;
+    while let Some((index, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '"' => {
+                let mut atom = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, c)) => atom.push(c),
+                        None => return Err(Error::parse(column(index), "missing closing quote of event")),
+                    }
+                }
+                Token::Atom(atom)
+            }
+            '!' => Token::Not,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '[' => Token::OpenBracket,
+            ']' => Token::CloseBracket,
+            '.' => Token::Dot,
+            '*' => Token::Star,
+            '+' => Token::Plus,
+            '?' => Token::Question,
+            '{' => Token::OpenBrace,
+            '}' => Token::CloseBrace,
+            ',' => Token::Comma,
+            c if c.is_ascii_digit() => {
+                let mut number = String::from(c);
+                while let Some((_, next)) = chars.peek().filter(|(_, next)| next.is_ascii_digit()) {
+                    number.push(*next);
+                    chars.next();
+                }
+                match number.parse() {
+                    Ok(number) => Token::Number(number),
+                    Err(_) => return Err(Error::parse(column(index), format!("number too large {}", number))),
+                }
+            }
+            '&' | '|' => {
+                //&& and || are accepted as well
+                if chars.peek().is_some_and(|(_, next)| *next == c) {
+                    chars.next();
+                }
+                if c == '&' {
+                    Token::And
+                } else {
+                    Token::Or
+                }
+            }
+            '-' if chars.peek().is_some_and(|(_, next)| *next == '>') => {
+                chars.next();
+                Token::Implies
+            }
+            '<' if text[index..].starts_with("<->") => {
+                chars.next();
+                chars.next();
+                Token::Equiv
+            }
+            c if c.is_ascii_alphabetic() => {
+                let mut word = String::from(c);
+                while let Some((_, next)) = chars.peek() {
+                    if !next.is_ascii_alphanumeric() && *next != '_' {
+                        break;
+                    }
+                    word.push(*next);
+                    chars.next();
+                }
+                match words.iter().find(|w| **w == word) {
+                    Some(word) => Token::Word(word),
+                    None => {
+                        return Err(Error::parse(
+                            column(index),
+                            format!("unknown operator {}, events are written between double quotes", word),
+                        ))
+                    }
+                }
+            }
+            _ => return Err(Error::parse(column(index), format!("unexpected character {}", c))),
+        };
+        tokens.push((token, column(index)));
+    }
+    Ok(tokens)
+}
+
+pub(crate) struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+    //column after the last character, where a missing token is reported
+    end: usize,
+    atoms: Vec<Event>,
+    //text of the atoms, an event written twice is the same atom
+    texts: Vec<String>,
+}
+
+impl Parser {
+    pub(crate) fn new(text: &str, words: &[&'static str]) -> Result<Self> {
+        Ok(Parser {
+            tokens: tokenize(text, words)?,
+            position: 0,
+            end: text.chars().count() + 1,
+            atoms: Vec::new(),
+            texts: Vec::new(),
+        })
+    }
+
+    /// The events of the formula once it is parsed, an error if some tokens are left.
+    pub(crate) fn finish(self) -> Result<Vec<Event>> {
+        if let Some((_, column)) = self.tokens.get(self.position) {
+            return Err(Error::parse(*column, "unexpected token after the end of the formula"));
+        }
+        Ok(self.atoms)
+    }
+
+    /// Column of the next token.
+    pub(crate) fn column(&self) -> usize {
+        self.tokens.get(self.position).map_or(self.end, |(_, column)| *column)
+    }
+
+    /// Next token without consuming it, None at the end of the formula.
+    pub(crate) fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    /// Next token, an error at the end of the formula.
+    pub(crate) fn next(&mut self) -> Result<Token> {
+        match self.tokens.get(self.position) {
+            Some((token, _)) => {
+                self.position += 1;
+                Ok(token.clone())
+            }
+            None => Err(Error::parse(self.end, "unexpected end of the formula")),
+        }
+    }
+
+    /// Accept the next token if it is the given one.
+    pub(crate) fn accept(&mut self, token: Token) -> bool {
+        if self.tokens.get(self.position).map(|(t, _)| t) == Some(&token) {
+            self.position += 1;
+            return true;
+        }
+        false
+    }
+
+    pub(crate) fn expect(&mut self, token: Token, message: &str) -> Result<()> {
+        if !self.accept(token) {
+            return Err(Error::parse(self.column(), message));
+        }
+        Ok(())
+    }
+
+    /// Index of the event written at the given column, the same text is the same atom.
+    pub(crate) fn atom(&mut self, text: String, column: usize) -> Result<usize> {
+        let text = text.trim().to_string();
+        if let Some(index) = self.texts.iter().position(|t| *t == text) {
+            return Ok(index);
+        }
+        //the event starts after the quote
+        let event = Event::new(&text).map_err(|e| e.at_column(column + 1))?;
+        self.atoms.push(event);
+        self.texts.push(text);
+        Ok(self.atoms.len() - 1)
+    }
+
+    /// Boolean combination of operands, from the loosest to the tightest: <->, ->, |, &.
+    /// `operand` parses what the connectives apply on, the negation is left to it.
+    pub(crate) fn formula<F: Connectives>(&mut self, operand: fn(&mut Parser) -> Result<F>) -> Result<F> {
+        let mut formula = self.implies(operand)?;
+        while self.accept(Token::Equiv) {
+            formula = F::equiv(formula, self.implies(operand)?);
+        }
+        Ok(formula)
+    }
+
+    fn implies<F: Connectives>(&mut self, operand: fn(&mut Parser) -> Result<F>) -> Result<F> {
+        let formula = self.or(operand)?;
+        if self.accept(Token::Implies) {
+            return Ok(F::implies(formula, self.implies(operand)?));
+        }
+        Ok(formula)
+    }
+
+    fn or<F: Connectives>(&mut self, operand: fn(&mut Parser) -> Result<F>) -> Result<F> {
+        let mut formula = self.and(operand)?;
+        while self.accept(Token::Or) {
+            formula = F::or(formula, self.and(operand)?);
+        }
+        Ok(formula)
+    }
+
+    fn and<F: Connectives>(&mut self, operand: fn(&mut Parser) -> Result<F>) -> Result<F> {
+        let mut formula = operand(self)?;
+        while self.accept(Token::And) {
+            formula = F::and(formula, operand(self)?);
+        }
+        Ok(formula)
+    }
+}
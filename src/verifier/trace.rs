@@ -0,0 +1,195 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Display,
+    hash::Hash,
+};
+
+use indexmap::IndexMap;
+use serde::Serialize;
+
+use crate::graph::prelude::*;
+
+use super::result::Transition;
+
+//state of the search -> previous state and transition taken from it
+pub(crate) type Parents<K> = HashMap<K, Option<(K, Transition)>>;
+
+/// Word leading from the initial state of the Mealy machine to a violating transition.
+/// The last transition is the violating one, the word can be replayed on the implementation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Trace {
+    pub inputs: Vec<String>,
+    /// outputs expected from the implementation according to the Mealy machine
+    pub outputs: Vec<String>,
+    pub transitions: Vec<Transition>,
+    /// for an infinite word, index of the first transition of the cycle repeated forever after the prefix
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loop_start: Option<usize>,
+}
+
+impl Trace {
+    pub fn new(transitions: Vec<Transition>) -> Self {
+        let (inputs, outputs) = transitions
+            .iter()
+            .map(|t| match t.label.split_once('/') {
+                Some((input, output)) => (input.trim().to_string(), output.trim().to_string()),
+                None => (t.label.trim().to_string(), String::new()),
+            })
+            .unzip();
+        Trace {
+            inputs,
+            outputs,
+            transitions,
+            loop_start: None,
+        }
+    }
+
+    /// Infinite word made of a prefix then of a cycle repeated forever.
+    /// The cycle is empty when the word ends in a state without outgoing transition.
+    pub fn lasso(prefix: Vec<Transition>, cycle: Vec<Transition>) -> Self {
+        let loop_start = prefix.len();
+        let mut trace = Trace::new(prefix.into_iter().chain(cycle).collect());
+        trace.loop_start = Some(loop_start);
+        trace
+    }
+
+    /// The violating transition ending the trace.
+    pub fn violation(&self) -> &Transition {
+        self.transitions
+            .last()
+            .expect("a trace ends with the violating transition")
+    }
+}
+
+impl Display for Trace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.loop_start {
+            Some(_) => writeln!(f, "# lasso, the inputs after the cycle mark are repeated forever")?,
+            None => writeln!(f, "# {}", self.violation())?,
+        }
+        for (index, (input, output)) in self.inputs.iter().zip(self.outputs.iter()).enumerate() {
+            if self.loop_start == Some(index) {
+                writeln!(f, "# cycle")?;
+            }
+            writeln!(f, "{} / {}", input, output)?;
+        }
+        if self.loop_start == Some(self.inputs.len()) {
+            writeln!(f, "# cycle: no transition leaves the last state")?;
+        }
+        Ok(())
+    }
+}
+
+/// Outgoing transitions of a node, sorted so that the traces do not depend on hashing order.
+pub(crate) fn sorted_neighbors<'a>(graph: &'a Graph, node_id: &NodeId) -> Vec<(NodeId, &'a MultiEdge)> {
+    let mut neighbors = graph.neighbors_edges_iterator(node_id);
+    neighbors.sort_by_key(|(a, _)| a.to_string());
+    neighbors
+}
+
+/// Rebuild the transitions leading to `key` from the parents found by a breadth first search.
+pub(crate) fn path_to<K: Eq + Hash + Clone>(parents: &Parents<K>, key: &K) -> Vec<Transition> {
+    let mut path = Vec::new();
+    let mut current = key.clone();
+    while let Some(Some((parent, transition))) = parents.get(&current) {
+        path.push(transition.clone());
+        current = parent.clone();
+    }
+    path.reverse();
+    path
+}
+
+/// Shortest transitions from the root to every node reachable from it.
+pub(crate) fn shortest_paths(graph: &Graph) -> HashMap<NodeId, Vec<Transition>> {
+    let mut parents: Parents<NodeId> = HashMap::new();
+    let mut queue = VecDeque::new();
+    if let Some(root) = graph.get_root() {
+        parents.insert(root.clone(), None);
+        queue.push_back(root.clone());
+    }
+    while let Some(node_id) = queue.pop_front() {
+        for (neighbor_id, edge) in sorted_neighbors(graph, &node_id) {
+            if parents.contains_key(&neighbor_id) {
+                continue;
+            }
+            let label = edge.get_labels()[0].clone();
+            let transition = Transition::new(&node_id, &neighbor_id, &label);
+            parents.insert(neighbor_id.clone(), Some((node_id.clone(), transition)));
+            queue.push_back(neighbor_id);
+        }
+    }
+    parents
+        .keys()
+        .map(|node_id| (node_id.clone(), path_to(&parents, node_id)))
+        .collect()
+}
+
+/// Breadth first search on the product of the graph and the state of a rule, starting from the root.
+/// For a state of the rule and a transition (destination, label), `step` tells if the transition violates the rule
+/// and the state of the rule after it, None if the exploration stops there.
+/// The shortest trace of every violating transition is returned, sorted by violating transition.
+pub(crate) fn shortest_traces<S, F>(graph: &Graph, init: S, mut step: F) -> Vec<Trace>
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S, &NodeId, &str) -> (bool, Option<S>),
+{
+    let root = match graph.get_root() {
+        Some(root) => root.clone(),
+        None => return Vec::new(),
+    };
+    let mut parents: Parents<(NodeId, S)> = HashMap::new();
+    let mut traces: IndexMap<Transition, Trace> = IndexMap::new();
+    let mut queue = VecDeque::new();
+    parents.insert((root.clone(), init.clone()), None);
+    queue.push_back((root, init));
+    while let Some(current) = queue.pop_front() {
+        let (node_id, state) = &current;
+        for (neighbor_id, edge) in sorted_neighbors(graph, node_id) {
+            for label in edge.get_label_iterator() {
+                let (violation, next_state) = step(state, &neighbor_id, label);
+                let transition = Transition::new(node_id, &neighbor_id, label);
+                if violation && !traces.contains_key(&transition) {
+                    let mut path = path_to(&parents, &current);
+                    path.push(transition.clone());
+                    traces.insert(transition.clone(), Trace::new(path));
+                }
+                if let Some(next_state) = next_state {
+                    let next = (neighbor_id.clone(), next_state);
+                    if !parents.contains_key(&next) {
+                        parents.insert(next.clone(), Some((current.clone(), transition)));
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+    }
+    traces.sort_keys();
+    traces.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::Graph;
+
+    use super::shortest_traces;
+
+    #[test]
+    fn shortest_trace_to_violation() {
+        let input = r#"digraph "Automata" {
+            "0" [shape=ellipse, style=filed, fillcolor=white, URL="0"];
+            "1" [shape=ellipse, style=filed, fillcolor=white, URL="1"];
+            "2" [shape=ellipse, style=filed, fillcolor=white, URL="2"];
+            "0" -> "1" [fontsize=5, label="a / x", color=black];
+            "1" -> "2" [fontsize=5, label="b / y", color=black];
+            "0" -> "2" [fontsize=5, label="c / z", color=black];
+            "2" -> "0" [fontsize=5, label="bad / err", color=black];
+        }"#;
+        let graph = Graph::new(input, true);
+        //no state, every "bad" input is a violation
+        let traces = shortest_traces(&graph, (), |_, _, label| (label.starts_with("bad"), Some(())));
+        assert_eq!(traces.len(), 1);
+        assert_eq!(traces[0].inputs, vec!["c", "bad"]);
+        assert_eq!(traces[0].outputs, vec!["z", "err"]);
+        assert_eq!(traces[0].to_string(), "# 2 -> 0 [bad / err]\nc / z\nbad / err\n");
+    }
+}